@@ -0,0 +1,606 @@
+//! Automatic differentiation.
+//!
+//! In forward mode, `Dual` and `HyperDual` are scalars that carry derivatives alongside
+//! their value, so any computation that is generic over `FloatScalar` can be
+//! differentiated exactly by seeding its inputs.
+//!
+//! In reverse mode, operations on `Var`s are recorded on a `Tape`, and the gradient with
+//! respect to every input is computed in a single backward pass.
+
+/// Implement the numeric traits shared by all dual numbers.
+///
+/// The type needs the inherent methods `constant`, `is_constant` and `chain`, a `value`
+/// field, and implementations of the arithmetic operators.
+macro_rules! impl_dual_number {
+    ($type:ident) => {
+        impl<S> Zero for $type<S>
+        where
+            S: FloatScalar,
+        {
+            fn zero() -> Self {
+                $type::constant(S::zero())
+            }
+
+            fn is_zero(&self) -> bool {
+                self.value.is_zero()
+            }
+        }
+
+        impl<S> One for $type<S>
+        where
+            S: FloatScalar,
+        {
+            fn one() -> Self {
+                $type::constant(S::one())
+            }
+        }
+
+        impl<S> Num for $type<S>
+        where
+            S: FloatScalar,
+        {
+            type FromStrRadixErr = S::FromStrRadixErr;
+
+            fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+                S::from_str_radix(s, radix).map($type::constant)
+            }
+        }
+
+        impl<S> NumCast for $type<S>
+        where
+            S: FloatScalar,
+        {
+            fn from<T: ToPrimitive>(n: T) -> Option<Self> {
+                <S as NumCast>::from(n).map($type::constant)
+            }
+        }
+
+        impl<S> ToPrimitive for $type<S>
+        where
+            S: FloatScalar,
+        {
+            fn to_i64(&self) -> Option<i64> {
+                self.value.to_i64()
+            }
+
+            fn to_u64(&self) -> Option<u64> {
+                self.value.to_u64()
+            }
+
+            fn to_f64(&self) -> Option<f64> {
+                self.value.to_f64()
+            }
+        }
+
+        /// Dual numbers compare by value only, like the functions they represent.
+        impl<S> PartialEq for $type<S>
+        where
+            S: FloatScalar,
+        {
+            fn eq(&self, other: &Self) -> bool {
+                self.value == other.value
+            }
+        }
+
+        /// Dual numbers compare by value only, like the functions they represent.
+        impl<S> PartialOrd for $type<S>
+        where
+            S: FloatScalar,
+        {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                self.value.partial_cmp(&other.value)
+            }
+        }
+
+        impl<S> From<S> for $type<S>
+        where
+            S: FloatScalar,
+        {
+            fn from(value: S) -> Self {
+                $type::constant(value)
+            }
+        }
+
+        impl_dual_number!(@assign $type, AddAssign, add_assign, add);
+        impl_dual_number!(@assign $type, SubAssign, sub_assign, sub);
+        impl_dual_number!(@assign $type, MulAssign, mul_assign, mul);
+        impl_dual_number!(@assign $type, DivAssign, div_assign, div);
+        impl_dual_number!(@assign $type, RemAssign, rem_assign, rem);
+
+        impl<S> Float for $type<S>
+        where
+            S: FloatScalar,
+        {
+            fn nan() -> Self {
+                $type::constant(S::nan())
+            }
+
+            fn infinity() -> Self {
+                $type::constant(S::infinity())
+            }
+
+            fn neg_infinity() -> Self {
+                $type::constant(S::neg_infinity())
+            }
+
+            fn neg_zero() -> Self {
+                $type::constant(S::neg_zero())
+            }
+
+            fn min_value() -> Self {
+                $type::constant(S::min_value())
+            }
+
+            fn min_positive_value() -> Self {
+                $type::constant(S::min_positive_value())
+            }
+
+            fn epsilon() -> Self {
+                $type::constant(S::epsilon())
+            }
+
+            fn max_value() -> Self {
+                $type::constant(S::max_value())
+            }
+
+            fn is_nan(self) -> bool {
+                self.value.is_nan()
+            }
+
+            fn is_infinite(self) -> bool {
+                self.value.is_infinite()
+            }
+
+            fn is_finite(self) -> bool {
+                self.value.is_finite()
+            }
+
+            fn is_normal(self) -> bool {
+                self.value.is_normal()
+            }
+
+            fn classify(self) -> std::num::FpCategory {
+                self.value.classify()
+            }
+
+            fn floor(self) -> Self {
+                $type::constant(self.value.floor())
+            }
+
+            fn ceil(self) -> Self {
+                $type::constant(self.value.ceil())
+            }
+
+            fn round(self) -> Self {
+                $type::constant(self.value.round())
+            }
+
+            fn trunc(self) -> Self {
+                $type::constant(self.value.trunc())
+            }
+
+            fn fract(self) -> Self {
+                self - self.trunc()
+            }
+
+            fn abs(self) -> Self {
+                if self.value.is_sign_negative() {
+                    -self
+                } else {
+                    self
+                }
+            }
+
+            fn signum(self) -> Self {
+                $type::constant(self.value.signum())
+            }
+
+            fn is_sign_positive(self) -> bool {
+                self.value.is_sign_positive()
+            }
+
+            fn is_sign_negative(self) -> bool {
+                self.value.is_sign_negative()
+            }
+
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                self * a + b
+            }
+
+            fn recip(self) -> Self {
+                let a = self.value;
+                let r = a.recip();
+                self.chain(r, -r * r, two::<S>() * r * r * r)
+            }
+
+            fn powi(self, n: i32) -> Self {
+                let a = self.value;
+                let n_s = <S as NumCast>::from(n).unwrap();
+                let df = if n == 0 { S::zero() } else { n_s * a.powi(n - 1) };
+                let ddf = if n == 0 || n == 1 {
+                    S::zero()
+                } else {
+                    n_s * (n_s - S::one()) * a.powi(n - 2)
+                };
+                self.chain(a.powi(n), df, ddf)
+            }
+
+            fn powf(self, n: Self) -> Self {
+                if n.is_constant() {
+                    let a = self.value;
+                    let n = n.value;
+                    let df = if n.is_zero() { S::zero() } else { n * a.powf(n - S::one()) };
+                    let ddf = if n.is_zero() || n == S::one() {
+                        S::zero()
+                    } else {
+                        n * (n - S::one()) * a.powf(n - two::<S>())
+                    };
+                    self.chain(a.powf(n), df, ddf)
+                } else {
+                    (n * self.ln()).exp()
+                }
+            }
+
+            fn sqrt(self) -> Self {
+                let a = self.value;
+                let s = a.sqrt();
+                let df = (two::<S>() * s).recip();
+                self.chain(s, df, -df / (two::<S>() * a))
+            }
+
+            fn exp(self) -> Self {
+                let e = self.value.exp();
+                self.chain(e, e, e)
+            }
+
+            fn exp2(self) -> Self {
+                let e = self.value.exp2();
+                let ln2 = two::<S>().ln();
+                self.chain(e, ln2 * e, ln2 * ln2 * e)
+            }
+
+            fn ln(self) -> Self {
+                let a = self.value;
+                self.chain(a.ln(), a.recip(), -(a * a).recip())
+            }
+
+            fn log(self, base: Self) -> Self {
+                self.ln() / base.ln()
+            }
+
+            fn log2(self) -> Self {
+                let a = self.value;
+                let ln2 = two::<S>().ln();
+                self.chain(a.log2(), (a * ln2).recip(), -(a * a * ln2).recip())
+            }
+
+            fn log10(self) -> Self {
+                let a = self.value;
+                let ln10 = <S as NumCast>::from(10).unwrap().ln();
+                self.chain(a.log10(), (a * ln10).recip(), -(a * a * ln10).recip())
+            }
+
+            fn to_degrees(self) -> Self {
+                self.chain(self.value.to_degrees(), S::one().to_degrees(), S::zero())
+            }
+
+            fn to_radians(self) -> Self {
+                self.chain(self.value.to_radians(), S::one().to_radians(), S::zero())
+            }
+
+            fn max(self, other: Self) -> Self {
+                if other.value > self.value || self.value.is_nan() {
+                    other
+                } else {
+                    self
+                }
+            }
+
+            fn min(self, other: Self) -> Self {
+                if other.value < self.value || self.value.is_nan() {
+                    other
+                } else {
+                    self
+                }
+            }
+
+            fn abs_sub(self, other: Self) -> Self {
+                if self.value > other.value {
+                    self - other
+                } else {
+                    Self::zero()
+                }
+            }
+
+            fn cbrt(self) -> Self {
+                let c = self.value.cbrt();
+                let three = <S as NumCast>::from(3).unwrap();
+                let nine = <S as NumCast>::from(9).unwrap();
+                self.chain(
+                    c,
+                    (three * c * c).recip(),
+                    -two::<S>() / (nine * c.powi(5)),
+                )
+            }
+
+            fn hypot(self, other: Self) -> Self {
+                (self * self + other * other).sqrt()
+            }
+
+            fn sin(self) -> Self {
+                let (s, c) = self.value.sin_cos();
+                self.chain(s, c, -s)
+            }
+
+            fn cos(self) -> Self {
+                let (s, c) = self.value.sin_cos();
+                self.chain(c, -s, -c)
+            }
+
+            fn tan(self) -> Self {
+                let t = self.value.tan();
+                let sec2 = S::one() + t * t;
+                self.chain(t, sec2, two::<S>() * t * sec2)
+            }
+
+            fn asin(self) -> Self {
+                let a = self.value;
+                let d = S::one() - a * a;
+                self.chain(a.asin(), d.sqrt().recip(), a / (d * d.sqrt()))
+            }
+
+            fn acos(self) -> Self {
+                let a = self.value;
+                let d = S::one() - a * a;
+                self.chain(a.acos(), -d.sqrt().recip(), -a / (d * d.sqrt()))
+            }
+
+            fn atan(self) -> Self {
+                let a = self.value;
+                let d = S::one() + a * a;
+                self.chain(a.atan(), d.recip(), -two::<S>() * a / (d * d))
+            }
+
+            fn atan2(self, other: Self) -> Self {
+                // atan2 only differs from atan(y/x) by a constant
+                let t = (self / other).atan();
+                t + $type::constant(self.value.atan2(other.value) - t.value)
+            }
+
+            fn sin_cos(self) -> (Self, Self) {
+                (self.sin(), self.cos())
+            }
+
+            fn exp_m1(self) -> Self {
+                let e = self.value.exp();
+                self.chain(self.value.exp_m1(), e, e)
+            }
+
+            fn ln_1p(self) -> Self {
+                let d = (S::one() + self.value).recip();
+                self.chain(self.value.ln_1p(), d, -d * d)
+            }
+
+            fn sinh(self) -> Self {
+                let (s, c) = (self.value.sinh(), self.value.cosh());
+                self.chain(s, c, s)
+            }
+
+            fn cosh(self) -> Self {
+                let (s, c) = (self.value.sinh(), self.value.cosh());
+                self.chain(c, s, c)
+            }
+
+            fn tanh(self) -> Self {
+                let t = self.value.tanh();
+                let sech2 = S::one() - t * t;
+                self.chain(t, sech2, -two::<S>() * t * sech2)
+            }
+
+            fn asinh(self) -> Self {
+                let a = self.value;
+                let d = a * a + S::one();
+                self.chain(a.asinh(), d.sqrt().recip(), -a / (d * d.sqrt()))
+            }
+
+            fn acosh(self) -> Self {
+                let a = self.value;
+                let d = a * a - S::one();
+                self.chain(a.acosh(), d.sqrt().recip(), -a / (d * d.sqrt()))
+            }
+
+            fn atanh(self) -> Self {
+                let a = self.value;
+                let d = S::one() - a * a;
+                self.chain(a.atanh(), d.recip(), two::<S>() * a / (d * d))
+            }
+
+            fn integer_decode(self) -> (u64, i16, i8) {
+                self.value.integer_decode()
+            }
+        }
+    };
+
+    (@assign $type:ident, $trait:ident, $fn:ident, $op:ident) => {
+        impl<S> $trait for $type<S>
+        where
+            S: FloatScalar,
+        {
+            fn $fn(&mut self, rhs: Self) {
+                *self = self.$op(rhs);
+            }
+        }
+    };
+}
+
+mod dual;
+mod hyper_dual;
+mod tape;
+
+pub use self::dual::*;
+pub use self::hyper_dual::*;
+pub use self::tape::*;
+
+use crate::{matrix::Matrix, traits::FloatScalar, vector::Vector};
+
+fn two<S: FloatScalar>() -> S {
+    S::one() + S::one()
+}
+
+/// The gradient of a scalar function at `x`.
+///
+/// The function is evaluated once for every element of `x`, each time with the
+/// derivative of that element seeded to one.
+/// ```
+/// # use land::{gradient, mat};
+/// # fn main() {
+/// let x = mat![1.0, 2.0, 3.0];
+///
+/// let grad = gradient(|x| x.dot(x), &x);
+///
+/// assert_eq!(grad, mat![2.0, 4.0, 6.0]);
+/// # }
+/// ```
+pub fn gradient<S, F>(f: F, x: &Vector<S>) -> Vector<S>
+where
+    S: FloatScalar,
+    F: Fn(&Vector<Dual<S>>) -> Dual<S>,
+{
+    (0..x.len())
+        .map(|i| f(&seed(x, i)).derivative)
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// The Jacobian of a vector function at `x`, such that `jacobian(f, x)[i][j]` is the
+/// derivative of output `i` with respect to input `j`.
+pub fn jacobian<S, F>(f: F, x: &Vector<S>) -> Matrix<S>
+where
+    S: FloatScalar,
+    F: Fn(&Vector<Dual<S>>) -> Vector<Dual<S>>,
+{
+    let columns: Vec<Vector<Dual<S>>> = (0..x.len()).map(|i| f(&seed(x, i))).collect();
+
+    // Without inputs there are no columns to take the output length from
+    let rows = match columns.first() {
+        Some(column) => column.len(),
+        None => f(&seed(x, 0)).len(),
+    };
+    let mut out = Matrix::zeros([rows, x.len()].into());
+
+    for (j, column) in columns.iter().enumerate() {
+        for (i, y) in column.iter().enumerate() {
+            out[i][j] = y.derivative;
+        }
+    }
+
+    out
+}
+
+/// The Hessian of a scalar function at `x`: the matrix of second derivatives.
+///
+/// Uses hyper-dual numbers, so there is no cancellation error, and evaluates the function
+/// once for every pair of inputs.
+pub fn hessian<S, F>(f: F, x: &Vector<S>) -> Matrix<S>
+where
+    S: FloatScalar,
+    F: Fn(&Vector<HyperDual<S>>) -> HyperDual<S>,
+{
+    let n = x.len();
+    let mut out = Matrix::zeros([n, n].into());
+
+    for i in 0..n {
+        for j in i..n {
+            let inputs: Vec<HyperDual<S>> = x
+                .iter()
+                .enumerate()
+                .map(|(k, &value)| {
+                    let eps1 = if k == i { S::one() } else { S::zero() };
+                    let eps2 = if k == j { S::one() } else { S::zero() };
+                    HyperDual::new(value, eps1, eps2, S::zero())
+                })
+                .collect();
+
+            let second = f(&inputs.into()).eps1eps2;
+            out[i][j] = second;
+            out[j][i] = second;
+        }
+    }
+
+    out
+}
+
+/// Promote `x` to dual numbers where only element `i` has a non-zero derivative.
+fn seed<S>(x: &Vector<S>, i: usize) -> Vector<Dual<S>>
+where
+    S: FloatScalar,
+{
+    x.iter()
+        .enumerate()
+        .map(|(k, &value)| {
+            if k == i {
+                Dual::variable(value)
+            } else {
+                Dual::constant(value)
+            }
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::Float;
+
+    #[test]
+    fn gradient_of_elementwise_functions() {
+        let x = mat![0.5, 2.0];
+
+        let grad = gradient(|x| x.clone().sin().dot(&x.clone().exp()), &x);
+
+        assert!((grad[0] - (0.5f64.cos() + 0.5f64.sin()) * 0.5f64.exp()).abs() < 1e-12);
+        assert!((grad[1] - (2.0f64.cos() + 2.0f64.sin()) * 2.0f64.exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn jacobian_of_matrix_product() {
+        let a = mat![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+        let a_dual = a.clone().map(Dual::constant);
+        let x = mat![1.0, -1.0, 2.0];
+
+        let jac = jacobian(|x| &a_dual * x, &x);
+
+        assert_eq!(jac, a);
+    }
+
+    #[test]
+    fn jacobian_of_nonlinear_function() {
+        let x = mat![3.0, 2.0];
+
+        // f(x, y) = [x * y, x^2]
+        let jac = jacobian(|v| mat![v[0] * v[1], v[0].powi(2)], &x);
+
+        assert_eq!(jac, mat![[2.0, 3.0], [6.0, 0.0]]);
+    }
+
+    #[test]
+    fn jacobian_without_inputs() {
+        let x = Vector::<f64>::from(Vec::new());
+
+        let jac = jacobian(|_| mat![Dual::constant(1.0), Dual::constant(2.0)], &x);
+
+        assert_eq!(jac.dim(), [2, 0].into());
+    }
+
+    #[test]
+    fn hessian_of_quadratic_form() {
+        let a = mat![[1.0, 2.0], [0.0, 3.0]];
+        let a_dual = a.clone().map(HyperDual::constant);
+        let x = mat![0.3, -0.7];
+
+        let hess = hessian(|x| x.dot(&(&a_dual * x)), &x);
+
+        assert_eq!(hess, &a + &a.transpose());
+    }
+}
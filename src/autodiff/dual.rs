@@ -0,0 +1,192 @@
+use super::two;
+use crate::traits::FloatScalar;
+use num::{Float, Num, NumCast, One, ToPrimitive, Zero};
+use std::{fmt, ops::*};
+
+/// A dual number `value + derivative * ε` where `ε² = 0`.
+///
+/// Evaluating a function `f` at `Dual::variable(x)` gives `f(x)` in `value` and `f'(x)`
+/// in `derivative`.
+#[derive(Debug, Copy, Clone)]
+pub struct Dual<S> {
+    pub value: S,
+    pub derivative: S,
+}
+
+impl<S> Dual<S>
+where
+    S: FloatScalar,
+{
+    pub fn new(value: S, derivative: S) -> Dual<S> {
+        Dual { value, derivative }
+    }
+
+    /// A value that does not depend on the variable being differentiated.
+    pub fn constant(value: S) -> Dual<S> {
+        Dual::new(value, S::zero())
+    }
+
+    /// The variable being differentiated, with derivative one.
+    pub fn variable(value: S) -> Dual<S> {
+        Dual::new(value, S::one())
+    }
+
+    fn is_constant(&self) -> bool {
+        self.derivative.is_zero()
+    }
+
+    /// Apply a function given its value and derivative at `self.value`. The second
+    /// derivative is not needed for dual numbers.
+    fn chain(self, f: S, df: S, _ddf: S) -> Dual<S> {
+        Dual::new(f, df * self.derivative)
+    }
+}
+
+impl<S> Add for Dual<S>
+where
+    S: FloatScalar,
+{
+    type Output = Dual<S>;
+
+    fn add(self, rhs: Dual<S>) -> Dual<S> {
+        Dual::new(self.value + rhs.value, self.derivative + rhs.derivative)
+    }
+}
+
+impl<S> Sub for Dual<S>
+where
+    S: FloatScalar,
+{
+    type Output = Dual<S>;
+
+    fn sub(self, rhs: Dual<S>) -> Dual<S> {
+        Dual::new(self.value - rhs.value, self.derivative - rhs.derivative)
+    }
+}
+
+impl<S> Mul for Dual<S>
+where
+    S: FloatScalar,
+{
+    type Output = Dual<S>;
+
+    fn mul(self, rhs: Dual<S>) -> Dual<S> {
+        Dual::new(
+            self.value * rhs.value,
+            self.derivative * rhs.value + self.value * rhs.derivative,
+        )
+    }
+}
+
+impl<S> Div for Dual<S>
+where
+    S: FloatScalar,
+{
+    type Output = Dual<S>;
+
+    fn div(self, rhs: Dual<S>) -> Dual<S> {
+        Dual::new(
+            self.value / rhs.value,
+            (self.derivative * rhs.value - self.value * rhs.derivative) / (rhs.value * rhs.value),
+        )
+    }
+}
+
+impl<S> Rem for Dual<S>
+where
+    S: FloatScalar,
+{
+    type Output = Dual<S>;
+
+    // a % b = a - b * trunc(a / b)
+    fn rem(self, rhs: Dual<S>) -> Dual<S> {
+        let quotient = (self.value / rhs.value).trunc();
+        Dual::new(
+            self.value % rhs.value,
+            self.derivative - rhs.derivative * quotient,
+        )
+    }
+}
+
+impl<S> Neg for Dual<S>
+where
+    S: FloatScalar,
+{
+    type Output = Dual<S>;
+
+    fn neg(self) -> Dual<S> {
+        Dual::new(-self.value, -self.derivative)
+    }
+}
+
+impl_dual_number!(Dual);
+
+impl<S> fmt::Display for Dual<S>
+where
+    S: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt(f)?;
+        write!(f, " + ")?;
+        self.derivative.fmt(f)?;
+        write!(f, "ε")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn derivative<F>(f: F, x: f64) -> f64
+    where
+        F: Fn(Dual<f64>) -> Dual<f64>,
+    {
+        f(Dual::variable(x)).derivative
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-12, "{} is not close to {}", a, b);
+    }
+
+    #[test]
+    fn product_and_quotient_rules() {
+        assert_eq!(derivative(|x| x * x * x, 2.0), 12.0);
+        assert_eq!(derivative(|x| Dual::one() / x, 2.0), -0.25);
+    }
+
+    #[test]
+    fn elementary_functions() {
+        let x = 0.3;
+
+        assert_close(derivative(|x| x.sin(), x), x.cos());
+        assert_close(derivative(|x| x.exp(), x), x.exp());
+        assert_close(derivative(|x| x.ln(), x), 1.0 / x);
+        assert_close(derivative(|x| x.sqrt(), x), 0.5 / x.sqrt());
+        assert_close(derivative(|x| x.tanh(), x), 1.0 - x.tanh().powi(2));
+        assert_close(derivative(|x| x.asin(), x), 1.0 / (1.0 - x * x).sqrt());
+        assert_close(derivative(|x| x.cbrt(), x), x.powf(-2.0 / 3.0) / 3.0);
+    }
+
+    #[test]
+    fn powf_with_variable_exponent() {
+        let x = 1.5;
+
+        // d/dx x^x = x^x (ln x + 1)
+        assert_close(derivative(|x| x.powf(x), x), x.powf(x) * (x.ln() + 1.0));
+        assert_close(
+            derivative(|x| x.powf(Dual::constant(2.5)), x),
+            2.5 * x.powf(1.5),
+        );
+    }
+
+    #[test]
+    fn atan2_derivative() {
+        let y = Dual::variable(1.0);
+        let x = Dual::constant(-1.0);
+
+        let angle = y.atan2(x);
+
+        assert_close(angle.value, 1.0f64.atan2(-1.0));
+        assert_close(angle.derivative, -0.5);
+    }
+}
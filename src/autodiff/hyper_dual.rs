@@ -0,0 +1,214 @@
+use super::two;
+use crate::traits::FloatScalar;
+use num::{Float, Num, NumCast, One, ToPrimitive, Zero};
+use std::{fmt, ops::*};
+
+/// A hyper-dual number `value + eps1 * ε₁ + eps2 * ε₂ + eps1eps2 * ε₁ε₂` where
+/// `ε₁² = ε₂² = 0`.
+///
+/// Evaluating a function `f` at `HyperDual::new(x, 1, 1, 0)` gives `f'(x)` in both `eps1`
+/// and `eps2`, and the exact second derivative `f''(x)` in `eps1eps2`.
+#[derive(Debug, Copy, Clone)]
+pub struct HyperDual<S> {
+    pub value: S,
+    pub eps1: S,
+    pub eps2: S,
+    pub eps1eps2: S,
+}
+
+impl<S> HyperDual<S>
+where
+    S: FloatScalar,
+{
+    pub fn new(value: S, eps1: S, eps2: S, eps1eps2: S) -> HyperDual<S> {
+        HyperDual {
+            value,
+            eps1,
+            eps2,
+            eps1eps2,
+        }
+    }
+
+    /// A value that does not depend on the variables being differentiated.
+    pub fn constant(value: S) -> HyperDual<S> {
+        HyperDual::new(value, S::zero(), S::zero(), S::zero())
+    }
+
+    /// The variable being differentiated, seeded in both directions.
+    pub fn variable(value: S) -> HyperDual<S> {
+        HyperDual::new(value, S::one(), S::one(), S::zero())
+    }
+
+    fn is_constant(&self) -> bool {
+        self.eps1.is_zero() && self.eps2.is_zero() && self.eps1eps2.is_zero()
+    }
+
+    /// Apply a function given its value and first and second derivatives at `self.value`.
+    fn chain(self, f: S, df: S, ddf: S) -> HyperDual<S> {
+        HyperDual::new(
+            f,
+            df * self.eps1,
+            df * self.eps2,
+            df * self.eps1eps2 + ddf * self.eps1 * self.eps2,
+        )
+    }
+}
+
+impl<S> Add for HyperDual<S>
+where
+    S: FloatScalar,
+{
+    type Output = HyperDual<S>;
+
+    fn add(self, rhs: HyperDual<S>) -> HyperDual<S> {
+        HyperDual::new(
+            self.value + rhs.value,
+            self.eps1 + rhs.eps1,
+            self.eps2 + rhs.eps2,
+            self.eps1eps2 + rhs.eps1eps2,
+        )
+    }
+}
+
+impl<S> Sub for HyperDual<S>
+where
+    S: FloatScalar,
+{
+    type Output = HyperDual<S>;
+
+    fn sub(self, rhs: HyperDual<S>) -> HyperDual<S> {
+        HyperDual::new(
+            self.value - rhs.value,
+            self.eps1 - rhs.eps1,
+            self.eps2 - rhs.eps2,
+            self.eps1eps2 - rhs.eps1eps2,
+        )
+    }
+}
+
+impl<S> Mul for HyperDual<S>
+where
+    S: FloatScalar,
+{
+    type Output = HyperDual<S>;
+
+    fn mul(self, rhs: HyperDual<S>) -> HyperDual<S> {
+        HyperDual::new(
+            self.value * rhs.value,
+            self.eps1 * rhs.value + self.value * rhs.eps1,
+            self.eps2 * rhs.value + self.value * rhs.eps2,
+            self.eps1eps2 * rhs.value
+                + self.eps1 * rhs.eps2
+                + self.eps2 * rhs.eps1
+                + self.value * rhs.eps1eps2,
+        )
+    }
+}
+
+impl<S> Div for HyperDual<S>
+where
+    S: FloatScalar,
+{
+    type Output = HyperDual<S>;
+
+    fn div(self, rhs: HyperDual<S>) -> HyperDual<S> {
+        self.mul(rhs.recip())
+    }
+}
+
+impl<S> Rem for HyperDual<S>
+where
+    S: FloatScalar,
+{
+    type Output = HyperDual<S>;
+
+    // a % b = a - b * trunc(a / b)
+    fn rem(self, rhs: HyperDual<S>) -> HyperDual<S> {
+        let quotient = HyperDual::constant((self.value / rhs.value).trunc());
+        let mut out = self - rhs * quotient;
+        out.value = self.value % rhs.value;
+        out
+    }
+}
+
+impl<S> Neg for HyperDual<S>
+where
+    S: FloatScalar,
+{
+    type Output = HyperDual<S>;
+
+    fn neg(self) -> HyperDual<S> {
+        HyperDual::new(-self.value, -self.eps1, -self.eps2, -self.eps1eps2)
+    }
+}
+
+impl_dual_number!(HyperDual);
+
+impl<S> fmt::Display for HyperDual<S>
+where
+    S: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt(f)?;
+        write!(f, " + ")?;
+        self.eps1.fmt(f)?;
+        write!(f, "ε₁ + ")?;
+        self.eps2.fmt(f)?;
+        write!(f, "ε₂ + ")?;
+        self.eps1eps2.fmt(f)?;
+        write!(f, "ε₁ε₂")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn second_derivative<F>(f: F, x: f64) -> f64
+    where
+        F: Fn(HyperDual<f64>) -> HyperDual<f64>,
+    {
+        f(HyperDual::variable(x)).eps1eps2
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-12, "{} is not close to {}", a, b);
+    }
+
+    #[test]
+    fn second_derivatives() {
+        let x = 0.7;
+
+        assert_eq!(second_derivative(|x| x * x * x, 2.0), 12.0);
+        assert_close(second_derivative(|x| x.sin(), x), -x.sin());
+        assert_close(second_derivative(|x| x.exp(), x), x.exp());
+        assert_close(second_derivative(|x| x.ln(), x), -1.0 / (x * x));
+        assert_close(second_derivative(|x| x.recip(), x), 2.0 / (x * x * x));
+        assert_close(second_derivative(|x| x.sqrt(), x), -0.25 * x.powf(-1.5));
+        assert_close(
+            second_derivative(|x| x.atan(), x),
+            -2.0 * x / (1.0 + x * x).powi(2),
+        );
+    }
+
+    #[test]
+    fn second_derivative_of_composition() {
+        let x = 0.4;
+
+        // d²/dx² sin(x²) = 2 cos(x²) - 4 x² sin(x²)
+        assert_close(
+            second_derivative(|x| (x * x).sin(), x),
+            2.0 * (x * x).cos() - 4.0 * x * x * (x * x).sin(),
+        );
+    }
+
+    #[test]
+    fn quotient_matches_chain_rule() {
+        let x = 1.3;
+
+        assert_close(
+            second_derivative(|x| x / (x + HyperDual::one()), x),
+            -2.0 / (x + 1.0).powi(3),
+        );
+    }
+}
@@ -0,0 +1,553 @@
+use crate::{
+    matrix::{Dimensions, Matrix},
+    traits::{FloatScalar, Scalar},
+    vector::Vector,
+};
+use num::NumCast;
+use std::{cell::RefCell, ops::*};
+
+/// Records operations on matrices so that gradients can be computed in reverse mode.
+///
+/// Every value on the tape is a matrix; vectors are stored as single column matrices.
+/// Computing the gradient of a scalar output takes a single backward pass, regardless of
+/// the number of inputs.
+/// ```
+/// # use land::{mat, Tape};
+/// # fn main() {
+/// let tape = Tape::new();
+/// let a = tape.var(mat![[1.0, 2.0], [3.0, 4.0]]);
+/// let x = tape.vector(&mat![1.0, -1.0]);
+///
+/// let y = (a * x).sum();
+/// let grads = y.backward();
+///
+/// assert_eq!(grads.wrt(a), &mat![[1.0, -1.0], [1.0, -1.0]]);
+/// assert_eq!(grads.wrt_vector(x), mat![4.0, 6.0]);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Tape<S> {
+    nodes: RefCell<Vec<Node<S>>>,
+}
+
+/// A value recorded on a `Tape`.
+#[derive(Debug)]
+pub struct Var<'t, S> {
+    tape: &'t Tape<S>,
+    index: usize,
+}
+
+/// The gradients of an output with respect to every value on a tape.
+#[derive(Debug, Clone)]
+pub struct Gradients<S> {
+    grads: Vec<Matrix<S>>,
+}
+
+#[derive(Debug)]
+struct Node<S> {
+    value: Matrix<S>,
+    op: Op<S>,
+}
+
+#[derive(Debug)]
+enum Op<S> {
+    Input,
+    Add(usize, usize),
+    Sub(usize, usize),
+    Neg(usize),
+    MatMul(usize, usize),
+    Hadamard(usize, usize),
+    Transpose(usize),
+    Scale(usize, S),
+    Sum(usize),
+    Dot(usize, usize),
+    /// An elementwise function, with its derivative evaluated at every element
+    Map(usize, Matrix<S>),
+}
+
+impl<S> Tape<S>
+where
+    S: Scalar,
+{
+    pub fn new() -> Tape<S> {
+        Tape {
+            nodes: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Record an input matrix.
+    pub fn var(&self, value: Matrix<S>) -> Var<'_, S> {
+        self.push(value, Op::Input)
+    }
+
+    /// Record an input vector as a single column matrix.
+    pub fn vector(&self, value: &Vector<S>) -> Var<'_, S> {
+        let column = Matrix::from_row_major([value.len(), 1].into(), value.clone().into());
+        self.var(column)
+    }
+
+    /// The number of values recorded on the tape.
+    pub fn len(&self) -> usize {
+        self.nodes.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.borrow().is_empty()
+    }
+
+    fn push(&self, value: Matrix<S>, op: Op<S>) -> Var<'_, S> {
+        let mut nodes = self.nodes.borrow_mut();
+        nodes.push(Node { value, op });
+
+        Var {
+            tape: self,
+            index: nodes.len() - 1,
+        }
+    }
+
+    fn dim(&self, index: usize) -> Dimensions {
+        self.nodes.borrow()[index].value.dim()
+    }
+}
+
+impl<S> Default for Tape<S>
+where
+    S: Scalar,
+{
+    fn default() -> Tape<S> {
+        Tape::new()
+    }
+}
+
+impl<'t, S> Var<'t, S>
+where
+    S: Scalar,
+{
+    /// The value of the variable.
+    pub fn value(&self) -> Matrix<S> {
+        self.tape.nodes.borrow()[self.index].value.clone()
+    }
+
+    /// Get the dimensions of the value
+    pub fn dim(&self) -> Dimensions {
+        self.tape.dim(self.index)
+    }
+
+    /// Matrix multiplication, also available as `a * b`.
+    pub fn matmul(self, rhs: Var<'t, S>) -> Var<'t, S> {
+        self.binary(rhs, |a, b| a * b, Op::MatMul)
+    }
+
+    /// The elementwise (Hadamard) product.
+    pub fn hadamard(self, rhs: Var<'t, S>) -> Var<'t, S> {
+        self.binary(rhs, Matrix::hadamard, Op::Hadamard)
+    }
+
+    /// The dot product of two values of the same size, treating them as flat vectors.
+    pub fn dot(self, rhs: Var<'t, S>) -> Var<'t, S> {
+        assert!(
+            self.dim() == rhs.dim(),
+            "Matrix dimensions must agree. Left hand side is {} and right hand side is {}",
+            self.dim(),
+            rhs.dim()
+        );
+
+        self.binary(
+            rhs,
+            |a, b| {
+                Matrix::filled(
+                    a.hadamard(b).into_iter_rows().fold(S::zero(), Add::add),
+                    [1, 1].into(),
+                )
+            },
+            Op::Dot,
+        )
+    }
+
+    pub fn transpose(self) -> Var<'t, S> {
+        let value = self.tape.nodes.borrow()[self.index].value.transpose();
+        self.tape.push(value, Op::Transpose(self.index))
+    }
+
+    /// The sum of every element, as a 1x1 matrix.
+    pub fn sum(self) -> Var<'t, S> {
+        let sum = self.tape.nodes.borrow()[self.index]
+            .value
+            .iter_rows()
+            .fold(S::zero(), |acc, e| acc + e.clone());
+        self.tape
+            .push(Matrix::filled(sum, [1, 1].into()), Op::Sum(self.index))
+    }
+
+    /// Multiply every element by a constant.
+    pub fn scale(self, factor: S) -> Var<'t, S> {
+        let value = self.value().map(|e| e * factor.clone());
+        self.tape.push(value, Op::Scale(self.index, factor))
+    }
+
+    /// Apply an elementwise function `f` with derivative `df`.
+    pub fn map<F, D>(self, f: F, df: D) -> Var<'t, S>
+    where
+        F: Fn(S) -> S,
+        D: Fn(S) -> S,
+    {
+        let input = self.value();
+        let derivative = input.clone().map(df);
+        self.tape
+            .push(input.map(f), Op::Map(self.index, derivative))
+    }
+
+    /// Compute the gradient of this value with respect to every value on the tape.
+    ///
+    /// Panics if the value is not a scalar (1x1 matrix).
+    pub fn backward(&self) -> Gradients<S> {
+        let nodes = self.tape.nodes.borrow();
+        let dim = nodes[self.index].value.dim();
+        assert!(
+            dim == Dimensions::square(1),
+            "Can only compute gradients of scalars. Value is of size {}",
+            dim
+        );
+
+        let mut grads: Vec<Matrix<S>> = nodes
+            .iter()
+            .map(|node| Matrix::zeros(node.value.dim()))
+            .collect();
+        grads[self.index] = Matrix::ones(dim);
+
+        for index in (0..=self.index).rev() {
+            let grad = grads[index].clone();
+            let value = |i: usize| &nodes[i].value;
+
+            // Elementwise operations broadcast, so the gradient of an operand that was
+            // stretched is summed over the stretched axes
+            let unbroadcast = |grad: Matrix<S>, i: usize| {
+                let dim = value(i).dim();
+                let grad = if dim.rows == 1 && grad.dim().rows != 1 {
+                    grad.sum_cols()
+                } else {
+                    grad
+                };
+                if dim.cols == 1 && grad.dim().cols != 1 {
+                    grad.sum_rows()
+                } else {
+                    grad
+                }
+            };
+
+            match nodes[index].op {
+                Op::Input => {}
+                Op::Add(a, b) => {
+                    grads[a] += unbroadcast(grad.clone(), a);
+                    grads[b] += unbroadcast(grad, b);
+                }
+                Op::Sub(a, b) => {
+                    grads[a] += unbroadcast(grad.clone(), a);
+                    grads[b] -= unbroadcast(grad, b);
+                }
+                Op::Neg(a) => grads[a] -= &grad,
+                Op::MatMul(a, b) => {
+                    grads[a] += &grad * &value(b).transpose();
+                    grads[b] += &value(a).transpose() * &grad;
+                }
+                Op::Hadamard(a, b) => {
+                    grads[a] += unbroadcast(grad.hadamard(value(b)), a);
+                    grads[b] += unbroadcast(grad.hadamard(value(a)), b);
+                }
+                Op::Transpose(a) => grads[a] += grad.transpose(),
+                Op::Scale(a, ref factor) => grads[a] += grad.map(|g| g * factor.clone()),
+                Op::Sum(a) => grads[a] += Matrix::filled(grad[0][0].clone(), value(a).dim()),
+                Op::Dot(a, b) => {
+                    let g = &grad[0][0];
+                    grads[a] += value(b).clone().map(|e| e * g.clone());
+                    grads[b] += value(a).clone().map(|e| e * g.clone());
+                }
+                Op::Map(a, ref derivative) => grads[a] += grad.hadamard(derivative),
+            }
+        }
+
+        Gradients { grads }
+    }
+
+    fn binary<F>(self, rhs: Var<'t, S>, f: F, op: fn(usize, usize) -> Op<S>) -> Var<'t, S>
+    where
+        F: Fn(&Matrix<S>, &Matrix<S>) -> Matrix<S>,
+    {
+        assert!(
+            std::ptr::eq(self.tape, rhs.tape),
+            "Variables must be recorded on the same tape"
+        );
+
+        let value = {
+            let nodes = self.tape.nodes.borrow();
+            f(&nodes[self.index].value, &nodes[rhs.index].value)
+        };
+        self.tape.push(value, op(self.index, rhs.index))
+    }
+}
+
+macro_rules! impl_elementwise_function {
+    ($(#[$attr:meta])* $name:ident, |$x:ident| $df:expr) => {
+        $(#[$attr])*
+        pub fn $name(self) -> Var<'t, F> {
+            self.map(F::$name, |$x| $df)
+        }
+    };
+}
+
+impl<'t, F> Var<'t, F>
+where
+    F: FloatScalar,
+{
+    impl_elementwise_function!(
+        /// Elementwise exponential function, `e^(self)`.
+        exp,
+        |x| x.exp()
+    );
+
+    impl_elementwise_function!(
+        /// Elementwise natural logarithm
+        ln,
+        |x| x.recip()
+    );
+
+    impl_elementwise_function!(
+        /// Elementwise square root
+        sqrt,
+        |x| (x.sqrt() + x.sqrt()).recip()
+    );
+
+    impl_elementwise_function!(
+        /// Elementwise sine in radians
+        sin,
+        |x| x.cos()
+    );
+
+    impl_elementwise_function!(
+        /// Elementwise cosine in radians
+        cos,
+        |x| -x.sin()
+    );
+
+    impl_elementwise_function!(
+        /// Elementwise hyperbolic tangent
+        tanh,
+        |x| F::one() - x.tanh() * x.tanh()
+    );
+
+    impl_elementwise_function!(
+        /// Elementwise absolute value
+        abs,
+        |x| x.signum()
+    );
+
+    /// Raise to the power of an integer power elementwise
+    pub fn powi(self, n: i32) -> Var<'t, F> {
+        let n_f = <F as NumCast>::from(n).unwrap();
+        self.map(move |x| x.powi(n), move |x| n_f * x.powi(n - 1))
+    }
+
+    /// Raise to the power of a constant elementwise
+    pub fn powf(self, n: F) -> Var<'t, F> {
+        self.map(move |x| x.powf(n), move |x| n * x.powf(n - F::one()))
+    }
+
+    /// Elementwise logistic function, `1 / (1 + e^(-self))`.
+    pub fn sigmoid(self) -> Var<'t, F> {
+        let sigmoid = |x: F| (F::one() + (-x).exp()).recip();
+        self.map(sigmoid, move |x| {
+            let s = sigmoid(x);
+            s * (F::one() - s)
+        })
+    }
+
+    /// Elementwise rectified linear unit, `max(0, self)`.
+    pub fn relu(self) -> Var<'t, F> {
+        self.map(
+            |x| x.max(F::zero()),
+            |x| if x > F::zero() { F::one() } else { F::zero() },
+        )
+    }
+}
+
+impl<'t, S> Clone for Var<'t, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'t, S> Copy for Var<'t, S> {}
+
+impl<'t, S> Add for Var<'t, S>
+where
+    S: Scalar,
+{
+    type Output = Var<'t, S>;
+
+    fn add(self, rhs: Var<'t, S>) -> Var<'t, S> {
+        self.binary(rhs, |a, b| a + b, Op::Add)
+    }
+}
+
+impl<'t, S> Sub for Var<'t, S>
+where
+    S: Scalar,
+{
+    type Output = Var<'t, S>;
+
+    fn sub(self, rhs: Var<'t, S>) -> Var<'t, S> {
+        self.binary(rhs, |a, b| a - b, Op::Sub)
+    }
+}
+
+impl<'t, S> Mul for Var<'t, S>
+where
+    S: Scalar,
+{
+    type Output = Var<'t, S>;
+
+    fn mul(self, rhs: Var<'t, S>) -> Var<'t, S> {
+        self.matmul(rhs)
+    }
+}
+
+impl<'t, S> Neg for Var<'t, S>
+where
+    S: Scalar,
+{
+    type Output = Var<'t, S>;
+
+    fn neg(self) -> Var<'t, S> {
+        let value = self.value().map(|e| -e);
+        self.tape.push(value, Op::Neg(self.index))
+    }
+}
+
+impl<S> Gradients<S>
+where
+    S: Scalar,
+{
+    /// The gradient with respect to a variable, of the same size as its value.
+    pub fn wrt(&self, var: Var<S>) -> &Matrix<S> {
+        &self.grads[var.index]
+    }
+
+    /// The gradient with respect to a variable created with `Tape::vector`.
+    pub fn wrt_vector(&self, var: Var<S>) -> Vector<S> {
+        let grad = self.wrt(var);
+        assert!(
+            grad.dim().cols == 1,
+            "Variable is not a vector. Variable is of size {}",
+            grad.dim()
+        );
+
+        grad.iter_rows().cloned().collect::<Vec<_>>().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::autodiff::Dual;
+    use num::Float;
+
+    #[test]
+    fn matmul_gradients() {
+        let tape = Tape::new();
+        let a = tape.var(mat![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let b = tape.var(mat![[1.0, 0.0], [2.0, 1.0], [0.0, 3.0]]);
+
+        let grads = (a * b).sum().backward();
+
+        // d/dA sum(A B) = 1 B^T and d/dB sum(A B) = A^T 1
+        assert_eq!(grads.wrt(a), &mat![[1.0, 3.0, 3.0], [1.0, 3.0, 3.0]]);
+        assert_eq!(grads.wrt(b), &mat![[5.0, 5.0], [7.0, 7.0], [9.0, 9.0]]);
+    }
+
+    #[test]
+    fn broadcast_bias() {
+        let tape = Tape::new();
+        let x = tape.var(mat![[1.0, 2.0], [3.0, 4.0]]);
+        let bias = tape.var(mat![[10.0, 20.0]]);
+        let w = tape.var(mat![[1.0], [-1.0]]);
+
+        let grads = ((x + bias).hadamard(w) - bias).sum().backward();
+
+        // The row and column are stretched over the rows and columns of x, so their
+        // gradients are summed over the stretched axis
+        assert_eq!(grads.wrt(x), &mat![[1.0, 1.0], [-1.0, -1.0]]);
+        assert_eq!(grads.wrt(bias), &mat![[-2.0, -2.0]]);
+        assert_eq!(grads.wrt(w), &mat![[33.0], [37.0]]);
+    }
+
+    #[test]
+    fn reused_variable_accumulates() {
+        let tape = Tape::new();
+        let x = tape.vector(&mat![1.0, 2.0, 3.0]);
+
+        let y = x.dot(x) + x.hadamard(x).sum().scale(2.0) - x.transpose().transpose().sum();
+        let grads = y.backward();
+
+        // y = 3 |x|^2 - sum(x)
+        assert_eq!(grads.wrt_vector(x), mat![5.0, 11.0, 17.0]);
+    }
+
+    #[test]
+    fn agrees_with_forward_mode() {
+        let w = mat![[0.5, -0.3], [0.8, 0.1]];
+        let x = mat![0.2, -0.7];
+
+        let tape = Tape::new();
+        let w_var = tape.var(w.clone());
+        let x_var = tape.vector(&x);
+        let y = (w_var * x_var).tanh().sigmoid().sum();
+        let reverse = y.backward().wrt_vector(x_var);
+
+        let w_dual = w.map(Dual::constant);
+        let forward = crate::autodiff::gradient(
+            |x| {
+                (&w_dual * x)
+                    .tanh()
+                    .iter()
+                    .map(|&t| (Dual::constant(1.0) + (-t).exp()).recip())
+                    .fold(Dual::constant(0.0), |acc, s| acc + s)
+            },
+            &x,
+        );
+
+        for i in 0..2 {
+            assert!((reverse[i] - forward[i]).abs() < 1e-12f64);
+        }
+    }
+
+    #[test]
+    fn train_linear_regression() {
+        // y = 2 x_0 - 3 x_1 + 1, with a column of ones for the intercept
+        let inputs = mat![
+            [1.0, 0.0, 1.0],
+            [0.0, 1.0, 1.0],
+            [1.0, 1.0, 1.0],
+            [2.0, 1.0, 1.0],
+            [0.5, 2.0, 1.0]
+        ];
+        let targets = mat![3.0, -2.0, 0.0, 2.0, -4.0];
+        let mut weights: Vector<f64> = Vector::zeros(3);
+
+        for _ in 0..2000 {
+            let tape = Tape::new();
+            let x = tape.var(inputs.clone());
+            let y = tape.vector(&targets);
+            let w = tape.vector(&weights);
+
+            let residual = x * w - y;
+            let loss = residual.dot(residual).scale(0.5);
+            let grad = loss.backward().wrt_vector(w);
+
+            weights -= grad * 0.05;
+        }
+
+        assert!((weights[0] - 2.0).abs() < 1e-6);
+        assert!((weights[1] + 3.0).abs() < 1e-6);
+        assert!((weights[2] - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,532 @@
+use crate::{
+    matrix::{Dimensions, Matrix},
+    traits::{FloatScalar, Scalar},
+    vector::Vector,
+};
+use std::ops::Mul;
+
+/// Many matrices of the same size, stored together.
+///
+/// The elements are grouped by position rather than by matrix: element `(row, col)` of
+/// every matrix is contiguous, in a slice called a plane. Batched operations loop over the
+/// matrices innermost, so they run the same arithmetic on contiguous elements, which the
+/// compiler can vectorise.
+/// ```
+/// # use land::{mat, Batch, Dimensions};
+/// # fn main() {
+/// let a = mat![[2.0, 0.0], [1.0, 1.0]];
+/// let b = mat![[0.0, 1.0], [1.0, 0.0]];
+/// let batch = Batch::from_matrices(Dimensions::square(2), &[a.clone(), b.clone()]);
+///
+/// assert_eq!(batch.plane(0, 0), [2.0, 0.0]);
+/// assert_eq!(batch.determinant(), mat![2.0, -1.0]);
+///
+/// let product = &batch * &batch.inverse();
+/// assert_eq!(product.matrix(1), mat![[1.0, 0.0], [0.0, 1.0]]);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Batch<S> {
+    elements: Vec<S>,
+    dimensions: Dimensions,
+    len: usize,
+}
+
+impl<S> Batch<S> {
+    /// The dimensions of each matrix
+    pub fn dim(&self) -> Dimensions {
+        self.dimensions
+    }
+
+    /// The number of matrices
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn plane_start(&self, row: usize, col: usize) -> usize {
+        assert!(
+            row < self.dimensions.rows && col < self.dimensions.cols,
+            "Position ({}, {}) is out of bounds for a batch of {} matrices",
+            row,
+            col,
+            self.dimensions
+        );
+        self.dimensions.row_major(row, col) * self.len
+    }
+
+    /// Element `(row, col)` of every matrix.
+    pub fn plane(&self, row: usize, col: usize) -> &[S] {
+        let start = self.plane_start(row, col);
+        &self.elements[start..start + self.len]
+    }
+
+    /// Element `(row, col)` of every matrix mutably.
+    pub fn plane_mut(&mut self, row: usize, col: usize) -> &mut [S] {
+        let start = self.plane_start(row, col);
+        &mut self.elements[start..start + self.len]
+    }
+
+    /// Borrow plane `target` mutably and a different plane `source`, by their row-major
+    /// positions.
+    fn two_planes(&mut self, target: usize, source: usize) -> (&mut [S], &[S]) {
+        let len = self.len;
+        if target < source {
+            let (head, tail) = self.elements.split_at_mut(source * len);
+            (&mut head[target * len..(target + 1) * len], &tail[..len])
+        } else {
+            let (head, tail) = self.elements.split_at_mut(target * len);
+            (&mut tail[..len], &head[source * len..(source + 1) * len])
+        }
+    }
+}
+
+impl<S> Batch<S>
+where
+    S: Clone,
+{
+    /// A batch of `len` matrices filled with a value.
+    pub fn filled(value: S, dimensions: Dimensions, len: usize) -> Batch<S> {
+        Batch {
+            elements: vec![value; dimensions.elements() * len],
+            dimensions,
+            len,
+        }
+    }
+
+    /// Copy matrices of the given dimensions into a batch.
+    pub fn from_matrices(dimensions: Dimensions, matrices: &[Matrix<S>]) -> Batch<S> {
+        let len = matrices.len();
+        let mut elements = Vec::with_capacity(dimensions.elements() * len);
+
+        for matrix in matrices {
+            assert!(
+                matrix.dim() == dimensions,
+                "Matrix dimensions must agree. Batch is of size {} and matrix is of size {}",
+                dimensions,
+                matrix.dim()
+            );
+        }
+        for position in 0..dimensions.elements() {
+            let (row, col) = (position / dimensions.cols, position % dimensions.cols);
+            elements.extend(matrices.iter().map(|matrix| matrix[row][col].clone()));
+        }
+
+        Batch {
+            elements,
+            dimensions,
+            len,
+        }
+    }
+
+    /// Copy out one matrix.
+    pub fn matrix(&self, index: usize) -> Matrix<S> {
+        assert!(
+            index < self.len,
+            "Index {} is out of bounds for a batch of {} matrices",
+            index,
+            self.len
+        );
+
+        let elements = self
+            .elements
+            .iter()
+            .skip(index)
+            .step_by(self.len)
+            .cloned()
+            .collect();
+        Matrix::from_row_major(self.dimensions, elements)
+    }
+
+    /// Overwrite one matrix.
+    pub fn set_matrix(&mut self, index: usize, matrix: &Matrix<S>) {
+        assert!(
+            index < self.len,
+            "Index {} is out of bounds for a batch of {} matrices",
+            index,
+            self.len
+        );
+        assert!(
+            matrix.dim() == self.dimensions,
+            "Matrix dimensions must agree. Batch is of size {} and matrix is of size {}",
+            self.dimensions,
+            matrix.dim()
+        );
+
+        for (e, value) in self
+            .elements
+            .iter_mut()
+            .skip(index)
+            .step_by(self.len)
+            .zip(matrix.iter_rows())
+        {
+            *e = value.clone();
+        }
+    }
+}
+
+impl<S> Batch<S>
+where
+    S: Scalar,
+{
+    /// A batch of `len` matrices filled with zeros.
+    pub fn zeros(dimensions: Dimensions, len: usize) -> Batch<S> {
+        Self::filled(S::zero(), dimensions, len)
+    }
+
+    /// A batch of `len` identity matrices.
+    pub fn identity(size: usize, len: usize) -> Batch<S> {
+        let mut batch = Self::zeros(Dimensions::square(size), len);
+        for i in 0..size {
+            batch.plane_mut(i, i).fill(S::one());
+        }
+        batch
+    }
+}
+
+// Matrix-Matrix Multiplication of each pair of matrices
+impl<S> Mul<Self> for &Batch<S>
+where
+    S: Scalar,
+{
+    type Output = Batch<S>;
+
+    fn mul(self, rhs: Self) -> Batch<S> {
+        assert!(
+            self.dimensions.cols == rhs.dimensions.rows,
+            "Matrix dimensions must agree. Left hand side is {} and right hand side is {}",
+            self.dimensions,
+            rhs.dimensions,
+        );
+        assert!(
+            self.len == rhs.len,
+            "Batch lengths must agree. Left hand side has {} matrices and right hand side has {}",
+            self.len,
+            rhs.len
+        );
+
+        let dimensions = Dimensions {
+            rows: self.dimensions.rows,
+            cols: rhs.dimensions.cols,
+        };
+        let mut out = Batch::zeros(dimensions, self.len);
+
+        for i in 0..dimensions.rows {
+            for k in 0..self.dimensions.cols {
+                let a = self.plane(i, k);
+                for j in 0..dimensions.cols {
+                    let b = rhs.plane(k, j);
+                    for ((out, a), b) in out.plane_mut(i, j).iter_mut().zip(a).zip(b) {
+                        *out += a.clone() * b.clone();
+                    }
+                }
+            }
+        }
+
+        out
+    }
+}
+
+impl<S: Scalar> Mul<Batch<S>> for Batch<S> {
+    type Output = Batch<S>;
+    fn mul(self, rhs: Batch<S>) -> Batch<S> {
+        (&self).mul(&rhs)
+    }
+}
+
+impl<F> Batch<F>
+where
+    F: FloatScalar,
+{
+    /// Reduce every matrix to upper triangular form with Gaussian elimination, applying the
+    /// same row operations to `rhs`. Returns the determinant of each matrix.
+    ///
+    /// Pivots are chosen by largest magnitude in each matrix separately. Only the row swaps
+    /// depend on the matrix; the elimination itself runs across the whole batch.
+    fn eliminate(&mut self, rhs: &mut Batch<F>) -> Vec<F> {
+        let size = self.dimensions.rows;
+        let cols = rhs.dimensions.cols;
+        let len = self.len;
+        let mut determinant = vec![F::one(); len];
+        let mut factors = vec![F::zero(); len];
+
+        for col in 0..size {
+            for (m, determinant) in determinant.iter_mut().enumerate() {
+                let magnitude = |row: usize| self.plane(row, col)[m].abs();
+                let pivot = (col + 1..size).fold(col, |pivot, row| {
+                    if magnitude(row) > magnitude(pivot) {
+                        row
+                    } else {
+                        pivot
+                    }
+                });
+
+                if pivot != col {
+                    for j in 0..size {
+                        let (a, b) = (self.plane_start(col, j), self.plane_start(pivot, j));
+                        self.elements.swap(a + m, b + m);
+                    }
+                    for j in 0..cols {
+                        let (a, b) = (rhs.plane_start(col, j), rhs.plane_start(pivot, j));
+                        rhs.elements.swap(a + m, b + m);
+                    }
+                    *determinant = -*determinant;
+                }
+                *determinant *= self.plane(col, col)[m];
+            }
+
+            for row in col + 1..size {
+                for ((factor, &a), &pivot) in factors
+                    .iter_mut()
+                    .zip(self.plane(row, col))
+                    .zip(self.plane(col, col))
+                {
+                    // A column that is already zero below the pivot needs no elimination,
+                    // and the matrix is singular if the pivot is zero too
+                    *factor = if pivot.is_zero() {
+                        F::zero()
+                    } else {
+                        a / pivot
+                    };
+                }
+
+                let (target, source) = (row * size, col * size);
+                for j in col..size {
+                    let (target, source) = self.two_planes(target + j, source + j);
+                    for ((t, &s), &factor) in target.iter_mut().zip(source).zip(&factors) {
+                        *t -= factor * s;
+                    }
+                }
+
+                let (target, source) = (row * cols, col * cols);
+                for j in 0..cols {
+                    let (target, source) = rhs.two_planes(target + j, source + j);
+                    for ((t, &s), &factor) in target.iter_mut().zip(source).zip(&factors) {
+                        *t -= factor * s;
+                    }
+                }
+            }
+        }
+
+        determinant
+    }
+
+    fn assert_square(&self) {
+        assert!(
+            self.dimensions.rows == self.dimensions.cols,
+            "Matrix must be square. Matrix is of size {}",
+            self.dimensions
+        );
+    }
+
+    /// The determinant of every square matrix.
+    pub fn determinant(&self) -> Vector<F> {
+        self.assert_square();
+
+        let mut rhs = Batch::zeros((self.dimensions.rows, 0).into(), self.len);
+        self.clone().eliminate(&mut rhs).into()
+    }
+
+    /// Find each `x` such that `self * x == b`, for a batch `b` of matrices with as many
+    /// rows. Solve for a single right hand side by giving `b` one column.
+    ///
+    /// The system is solved with partial pivoting. A singular matrix gives a solution of
+    /// NaN, rather than stopping the rest of the batch. A matrix is treated as singular when a
+    /// pivot is no larger than the rounding error of the elimination, that is machine epsilon
+    /// times the size times the largest magnitude in the matrix.
+    pub fn solve(&self, b: &Batch<F>) -> Batch<F> {
+        self.assert_square();
+        assert!(
+            self.dimensions.rows == b.dimensions.rows,
+            "Matrix dimensions must agree. Left hand side is {} and right hand side is {}",
+            self.dimensions,
+            b.dimensions,
+        );
+        assert!(
+            self.len == b.len,
+            "Batch lengths must agree. Left hand side has {} matrices and right hand side has {}",
+            self.len,
+            b.len
+        );
+
+        let size = self.dimensions.rows;
+        let cols = b.dimensions.cols;
+        let mut scale = vec![F::zero(); self.len];
+        for plane in self.elements.chunks(self.len.max(1)) {
+            for (scale, &e) in scale.iter_mut().zip(plane) {
+                *scale = scale.max(e.abs());
+            }
+        }
+
+        let mut upper = self.clone();
+        let mut x = b.clone();
+        upper.eliminate(&mut x);
+
+        let tolerance = F::epsilon() * F::from(size).unwrap();
+        let mut singular = vec![false; self.len];
+        for col in 0..size {
+            for ((singular, &pivot), &scale) in
+                singular.iter_mut().zip(upper.plane(col, col)).zip(&scale)
+            {
+                *singular |= pivot.abs() <= tolerance * scale;
+            }
+        }
+
+        let mut factors = vec![F::zero(); self.len];
+        for col in (0..size).rev() {
+            for (factor, &pivot) in factors.iter_mut().zip(upper.plane(col, col)) {
+                *factor = pivot.recip();
+            }
+            for j in 0..cols {
+                for (e, &factor) in x.plane_mut(col, j).iter_mut().zip(&factors) {
+                    *e *= factor;
+                }
+            }
+
+            for row in 0..col {
+                let (target, source) = (row * cols, col * cols);
+                let u = upper.plane(row, col);
+                for j in 0..cols {
+                    let (target, source) = x.two_planes(target + j, source + j);
+                    for ((t, &s), &u) in target.iter_mut().zip(source).zip(u) {
+                        *t -= u * s;
+                    }
+                }
+            }
+        }
+
+        for (m, &singular) in singular.iter().enumerate() {
+            if singular {
+                x.elements
+                    .iter_mut()
+                    .skip(m)
+                    .step_by(self.len)
+                    .for_each(|e| *e = F::nan());
+            }
+        }
+
+        x
+    }
+
+    /// The inverse of every square matrix. A singular matrix gives an inverse of NaN.
+    pub fn inverse(&self) -> Batch<F> {
+        self.assert_square();
+        self.solve(&Batch::identity(self.dimensions.rows, self.len))
+    }
+}
+
+impl<S> PartialEq<Self> for Batch<S>
+where
+    S: Scalar + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.dimensions == other.dimensions
+            && self.len == other.len
+            && self.elements == other.elements
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Matrices with small integer elements, that are exact in floating point.
+    fn matrices(dimensions: Dimensions, len: usize) -> Vec<Matrix<f64>> {
+        (0..len)
+            .map(|m| {
+                let elements = (0..dimensions.elements())
+                    .map(|i| ((i * i * 7 + m * m * 3 + i * m * 5 + i * 3 + m) % 19) as f64 - 9.0)
+                    .collect();
+                Matrix::from_row_major(dimensions, elements)
+            })
+            .collect()
+    }
+
+    fn assert_close(a: &Matrix<f64>, b: &Matrix<f64>) {
+        assert_eq!(a.dim(), b.dim());
+        for (a, b) in a.iter_rows().zip(b.iter_rows()) {
+            assert!((a - b).abs() < 1e-9, "{} is not close to {}", a, b);
+        }
+    }
+
+    #[test]
+    fn multiply() {
+        let a = matrices((3, 4).into(), 5);
+        let b = matrices((4, 2).into(), 5);
+
+        let product =
+            &Batch::from_matrices((3, 4).into(), &a) * &Batch::from_matrices((4, 2).into(), &b);
+
+        assert_eq!(product.dim(), (3, 2).into());
+        for m in 0..5 {
+            assert_eq!(product.matrix(m), &a[m] * &b[m]);
+        }
+    }
+
+    #[test]
+    fn determinant_and_inverse() {
+        for size in [3, 6] {
+            let a = matrices(Dimensions::square(size), 17);
+            let batch = Batch::from_matrices(Dimensions::square(size), &a);
+
+            let determinant = batch.determinant();
+            let inverse = batch.inverse();
+            for m in 0..a.len() {
+                assert!((determinant[m] - a[m].determinant()).abs() < 1e-6);
+                assert_close(&(&a[m] * &inverse.matrix(m)), &Matrix::identity(size));
+            }
+
+            let b = Batch::from_matrices((size, 1).into(), &matrices((size, 1).into(), 17));
+            let x = batch.solve(&b);
+            assert_close(&(&batch * &x).matrix(3), &b.matrix(3));
+        }
+    }
+
+    #[test]
+    fn singular_matrices() {
+        let mut batch = Batch::from_matrices(
+            Dimensions::square(2),
+            &[mat![[1.0, 2.0], [2.0, 4.0]], mat![[0.0, 1.0], [1.0, 0.0]]],
+        );
+
+        assert_eq!(batch.determinant(), mat![0.0, -1.0]);
+        let inverse = batch.inverse();
+        assert!(inverse.matrix(0).iter_rows().all(|e: &f64| e.is_nan()));
+        assert_eq!(inverse.matrix(1), mat![[0.0, 1.0], [1.0, 0.0]]);
+
+        batch.set_matrix(0, &mat![[4.0, 0.0], [0.0, 0.5]]);
+        assert_eq!(batch.inverse().matrix(0), mat![[0.25, 0.0], [0.0, 2.0]]);
+    }
+
+    #[test]
+    fn zero_and_nearly_zero_pivots() {
+        let batch = Batch::from_matrices(
+            Dimensions::square(3),
+            &[
+                mat![[0.0f64, 1.0, 2.0], [0.0, 3.0, 4.0], [0.0, 5.0, 6.0]],
+                mat![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]],
+                Matrix::identity(3),
+            ],
+        );
+
+        // A zero column must not divide by its zero pivot and spread NaN into the result
+        assert_eq!(batch.determinant()[0], 0.0);
+        assert!(batch.determinant()[1].abs() < 1e-12);
+
+        let inverse = batch.inverse();
+        assert!(inverse.matrix(0).iter_rows().all(|e: &f64| e.is_nan()));
+        assert!(inverse.matrix(1).iter_rows().all(|e: &f64| e.is_nan()));
+        assert_eq!(inverse.matrix(2), Matrix::identity(3));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Batch lengths must agree. Left hand side has 2 matrices and right hand side has 3"
+    )]
+    fn mismatched_lengths() {
+        let _ = &Batch::<f64>::identity(3, 2) * &Batch::identity(3, 3);
+    }
+}
@@ -0,0 +1,334 @@
+use num::{Num, NumCast, One, ToPrimitive, Zero};
+use std::{cmp::Ordering, fmt, ops::*};
+
+macro_rules! impl_fixed {
+    ($(#[$attr:meta])* $name:ident, $bits:ty, $wide:ty) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        pub struct $name<const FRAC: u32>($bits);
+
+        impl<const FRAC: u32> $name<FRAC> {
+            /// `FRAC`, checked at compile time to leave at least one integer bit besides the
+            /// sign. Every use of `FRAC` in arithmetic goes through this.
+            const SHIFT: u32 = {
+                assert!(
+                    FRAC < <$bits>::BITS - 1,
+                    "FRAC must leave at least one integer bit besides the sign"
+                );
+                FRAC
+            };
+
+            /// The number of bits used for the integer part and the sign.
+            const INT_BITS: u32 = <$bits>::BITS - Self::SHIFT;
+
+            /// The largest representable value.
+            pub const MAX: $name<FRAC> = $name(<$bits>::MAX);
+
+            /// The smallest representable value.
+            pub const MIN: $name<FRAC> = $name(<$bits>::MIN);
+
+            /// The smallest positive value, `2^-FRAC`.
+            pub const EPSILON: $name<FRAC> = $name(1);
+
+            /// Create a number from its raw bits, which are the value multiplied by `2^FRAC`.
+            pub fn from_bits(bits: $bits) -> $name<FRAC> {
+                $name(bits)
+            }
+
+            /// The raw bits, which are the value multiplied by `2^FRAC`.
+            pub fn to_bits(self) -> $bits {
+                self.0
+            }
+
+            /// Convert an integer, saturating if it is out of range.
+            pub fn from_int(value: $bits) -> $name<FRAC> {
+                $name::saturate(<$wide as From<$bits>>::from(value) << Self::SHIFT)
+            }
+
+            /// Convert a floating point number, rounding to the nearest representable value
+            /// and saturating if it is out of range. `NaN` becomes zero.
+            pub fn from_f64(value: f64) -> $name<FRAC> {
+                let scaled = (value * (Self::SHIFT as f64).exp2()).round();
+
+                if scaled.is_nan() {
+                    $name(0)
+                } else if scaled >= <$bits>::MAX as f64 {
+                    $name::MAX
+                } else if scaled <= <$bits>::MIN as f64 {
+                    $name::MIN
+                } else {
+                    $name(scaled as $bits)
+                }
+            }
+
+            /// The value as a floating point number.
+            pub fn to_f64(self) -> f64 {
+                self.0 as f64 / (Self::SHIFT as f64).exp2()
+            }
+
+            /// The absolute value, saturating at `MAX`.
+            pub fn abs(self) -> $name<FRAC> {
+                $name(self.0.saturating_abs())
+            }
+
+            /// Clamp a wide intermediate result to the representable range.
+            fn saturate(wide: $wide) -> $name<FRAC> {
+                if wide > <$wide as From<$bits>>::from(<$bits>::MAX) {
+                    $name::MAX
+                } else if wide < <$wide as From<$bits>>::from(<$bits>::MIN) {
+                    $name::MIN
+                } else {
+                    $name(wide as $bits)
+                }
+            }
+        }
+
+        impl<const FRAC: u32> Zero for $name<FRAC> {
+            fn zero() -> $name<FRAC> {
+                $name(0)
+            }
+
+            fn is_zero(&self) -> bool {
+                self.0 == 0
+            }
+        }
+
+        impl<const FRAC: u32> One for $name<FRAC> {
+            fn one() -> $name<FRAC> {
+                $name::from_int(1)
+            }
+        }
+
+        impl<const FRAC: u32> Num for $name<FRAC> {
+            type FromStrRadixErr = num::traits::ParseFloatError;
+
+            fn from_str_radix(s: &str, radix: u32) -> Result<$name<FRAC>, Self::FromStrRadixErr> {
+                <f64 as Num>::from_str_radix(s, radix).map($name::from_f64)
+            }
+        }
+
+        impl<const FRAC: u32> Add for $name<FRAC> {
+            type Output = $name<FRAC>;
+
+            fn add(self, rhs: $name<FRAC>) -> $name<FRAC> {
+                $name(self.0.saturating_add(rhs.0))
+            }
+        }
+
+        impl<const FRAC: u32> Sub for $name<FRAC> {
+            type Output = $name<FRAC>;
+
+            fn sub(self, rhs: $name<FRAC>) -> $name<FRAC> {
+                $name(self.0.saturating_sub(rhs.0))
+            }
+        }
+
+        impl<const FRAC: u32> Mul for $name<FRAC> {
+            type Output = $name<FRAC>;
+
+            /// The product is rounded to the nearest representable value.
+            fn mul(self, rhs: $name<FRAC>) -> $name<FRAC> {
+                let product = <$wide as From<$bits>>::from(self.0) * <$wide as From<$bits>>::from(rhs.0);
+                let half = if Self::SHIFT == 0 { 0 } else { 1 << (Self::SHIFT - 1) };
+                $name::saturate((product + half) >> Self::SHIFT)
+            }
+        }
+
+        impl<const FRAC: u32> Div for $name<FRAC> {
+            type Output = $name<FRAC>;
+
+            /// The quotient is truncated towards zero. Division by zero saturates to `MAX`
+            /// or `MIN` depending on the sign of the dividend, and `0 / 0` is zero.
+            fn div(self, rhs: $name<FRAC>) -> $name<FRAC> {
+                if rhs.0 == 0 {
+                    return match self.0.cmp(&0) {
+                        Ordering::Greater => $name::MAX,
+                        Ordering::Less => $name::MIN,
+                        Ordering::Equal => $name(0),
+                    };
+                }
+
+                $name::saturate((<$wide as From<$bits>>::from(self.0) << Self::SHIFT) / <$wide as From<$bits>>::from(rhs.0))
+            }
+        }
+
+        impl<const FRAC: u32> Rem for $name<FRAC> {
+            type Output = $name<FRAC>;
+
+            /// The remainder is exact. The remainder with a divisor of zero is zero.
+            fn rem(self, rhs: $name<FRAC>) -> $name<FRAC> {
+                $name(self.0.checked_rem(rhs.0).unwrap_or(0))
+            }
+        }
+
+        impl<const FRAC: u32> Neg for $name<FRAC> {
+            type Output = $name<FRAC>;
+
+            fn neg(self) -> $name<FRAC> {
+                $name(self.0.saturating_neg())
+            }
+        }
+
+        impl_assign_operators!([const FRAC: u32] $name<FRAC>);
+
+        impl<const FRAC: u32> ToPrimitive for $name<FRAC> {
+            /// The integer part, truncated towards zero.
+            fn to_i64(&self) -> Option<i64> {
+                (self.0 / (1 << Self::SHIFT)).to_i64()
+            }
+
+            fn to_u64(&self) -> Option<u64> {
+                (self.0 / (1 << Self::SHIFT)).to_u64()
+            }
+
+            fn to_f64(&self) -> Option<f64> {
+                Some($name::to_f64(*self))
+            }
+        }
+
+        /// Conversion fails if the value is out of range, rather than saturating.
+        impl<const FRAC: u32> NumCast for $name<FRAC> {
+            fn from<T: ToPrimitive>(n: T) -> Option<$name<FRAC>> {
+                let value = n.to_f64()?;
+                let limit = (Self::INT_BITS as f64 - 1.0).exp2();
+
+                if value >= -limit && value < limit {
+                    Some($name::from_f64(value))
+                } else {
+                    None
+                }
+            }
+        }
+
+        impl<const FRAC: u32> fmt::Debug for $name<FRAC> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}<{}>({})", stringify!($name), FRAC, $name::to_f64(*self))
+            }
+        }
+
+        impl<const FRAC: u32> fmt::Display for $name<FRAC> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                $name::to_f64(*self).fmt(f)
+            }
+        }
+    };
+}
+
+impl_fixed!(
+    /// A signed fixed point number in Q format stored in an `i32`, with `FRAC` fractional
+    /// bits. `FRAC` must leave at least one integer bit besides the sign.
+    ///
+    /// For example `Fixed<16>` is Q15.16 with a range of about ±32768 and a resolution of
+    /// `2^-16`. All arithmetic saturates at `MIN` and `MAX` instead of overflowing and never
+    /// panics, which makes it suitable for filters on targets without a floating point unit.
+    /// ```
+    /// # use land::{mat, Fixed};
+    /// # fn main() {
+    /// let a = mat![[0.5, 0.25], [-1.0, 2.0]].cast::<Fixed<16>>().unwrap();
+    /// let x = mat![Fixed::from_f64(2.0), Fixed::from_f64(4.0)];
+    ///
+    /// assert_eq!(&a * &x, mat![Fixed::from_int(2), Fixed::from_int(6)]);
+    /// # }
+    /// ```
+    ///
+    /// A `FRAC` without an integer bit fails to compile.
+    /// ```compile_fail
+    /// # use land::Fixed;
+    /// let _ = Fixed::<31>::from_int(1);
+    /// ```
+    Fixed,
+    i32,
+    i64
+);
+
+impl_fixed!(
+    /// A signed fixed point number in Q format stored in an `i64`, with `FRAC` fractional
+    /// bits. See `Fixed` for details.
+    Fixed64,
+    i64,
+    i128
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{matrix::Matrix, traits::Scalar, vector::Vector};
+
+    type Q16 = Fixed<16>;
+
+    fn q(value: f64) -> Q16 {
+        Q16::from_f64(value)
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(q(1.5) + q(2.25), q(3.75));
+        assert_eq!(q(1.5) - q(2.25), q(-0.75));
+        assert_eq!(q(1.5) * q(-2.25), q(-3.375));
+        assert_eq!(q(-3.375) / q(1.5), q(-2.25));
+        assert_eq!(q(5.5) % q(2.0), q(1.5));
+        assert_eq!(Q16::EPSILON.to_f64(), 1.0 / 65536.0);
+    }
+
+    #[test]
+    fn saturates() {
+        assert_eq!(Q16::MAX + q(1.0), Q16::MAX);
+        assert_eq!(Q16::MIN - q(1.0), Q16::MIN);
+        assert_eq!(q(300.0) * q(300.0), Q16::MAX);
+        assert_eq!(q(-300.0) * q(300.0), Q16::MIN);
+        assert_eq!(-Q16::MIN, Q16::MAX);
+        assert_eq!(q(1.0) / Q16::zero(), Q16::MAX);
+        assert_eq!(Q16::from_int(40000), Q16::MAX);
+        assert_eq!(Q16::from_f64(-1e9), Q16::MIN);
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(q(3.75).to_i64(), Some(3));
+        assert_eq!(q(-3.75).to_i64(), Some(-3));
+        assert_eq!(<Q16 as NumCast>::from(1e9), None);
+        assert_eq!(<Q16 as NumCast>::from(-2), Some(q(-2.0)));
+        assert_eq!(Q16::from_str_radix("-1.25", 10).unwrap(), q(-1.25));
+        assert_eq!(format!("{}", q(-1.25)), "-1.25");
+        assert_eq!(format!("{:?}", q(0.5)), "Fixed<16>(0.5)");
+    }
+
+    #[test]
+    fn wide_storage() {
+        let a = Fixed64::<32>::from_f64(1e6);
+        let b = Fixed64::<32>::from_f64(0.125);
+
+        assert_eq!((a * b).to_f64(), 125000.0);
+        assert_eq!((a / b).to_f64(), 8e6);
+    }
+
+    /// One step of a generic first order filter `x' = A x + B u`.
+    fn filter_step<S: Scalar>(a: &Matrix<S>, b: &Vector<S>, x: &Vector<S>, u: S) -> Vector<S> {
+        a * x + b.scale(u)
+    }
+
+    #[test]
+    fn filter_matches_floating_point() {
+        let a = mat![[0.9, 0.05], [-0.1, 0.8]];
+        let b = mat![0.1, 0.2];
+
+        let a_fixed = a.cast::<Q16>().unwrap();
+        let b_fixed: Vector<Q16> = b.iter().map(|&e| q(e)).collect::<Vec<_>>().into();
+
+        let mut x = Vector::zeros(2);
+        let mut x_fixed = Vector::zeros(2);
+        for step in 0..50 {
+            let u = if step % 10 < 5 { 1.0 } else { -0.5 };
+            x = filter_step(&a, &b, &x, u);
+            x_fixed = filter_step(&a_fixed, &b_fixed, &x_fixed, q(u));
+        }
+
+        for (exact, &fixed) in x.iter().zip(x_fixed.iter()) {
+            assert!((exact - fixed.to_f64()).abs() < 1e-3);
+        }
+        assert_eq!(
+            x_fixed.dot(&x_fixed),
+            x_fixed[0] * x_fixed[0] + x_fixed[1] * x_fixed[1]
+        );
+    }
+}
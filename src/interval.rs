@@ -0,0 +1,340 @@
+mod linear;
+
+use crate::traits::FloatScalar;
+use num::{Num, One, Zero};
+use std::{fmt, ops::*};
+
+/// A closed interval `[lo, hi]` of real numbers, used for verified computation.
+///
+/// Every operation returns an interval containing all possible results for operands in
+/// the input intervals. The bounds are rounded outward after each operation, so rounding
+/// errors can only make the interval wider, never exclude the exact result.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Interval<F> {
+    lo: F,
+    hi: F,
+}
+
+impl<F> Interval<F>
+where
+    F: FloatScalar,
+{
+    /// Create the interval `[lo, hi]`.
+    pub fn new(lo: F, hi: F) -> Interval<F> {
+        assert!(
+            lo <= hi,
+            "Lower bound of interval must not exceed upper bound. Bounds were {:?} and {:?}",
+            lo,
+            hi
+        );
+
+        Interval { lo, hi }
+    }
+
+    /// The interval containing exactly one number.
+    pub fn point(value: F) -> Interval<F> {
+        Interval {
+            lo: value,
+            hi: value,
+        }
+    }
+
+    /// The interval containing every real number.
+    pub fn entire() -> Interval<F> {
+        Interval {
+            lo: F::neg_infinity(),
+            hi: F::infinity(),
+        }
+    }
+
+    /// The smallest interval containing both bounds, rounded outward.
+    fn rounded(lo: F, hi: F) -> Interval<F> {
+        Interval {
+            lo: round_down(lo),
+            hi: round_up(hi),
+        }
+    }
+
+    /// The lower bound
+    pub fn lo(self) -> F {
+        self.lo
+    }
+
+    /// The upper bound
+    pub fn hi(self) -> F {
+        self.hi
+    }
+
+    /// The midpoint of the interval (not rounded)
+    pub fn mid(self) -> F {
+        if self.lo.is_infinite() || self.hi.is_infinite() {
+            (self.lo + self.hi) / (F::one() + F::one())
+        } else {
+            self.lo + (self.hi - self.lo) / (F::one() + F::one())
+        }
+    }
+
+    /// An upper bound of the width `hi - lo`.
+    pub fn width(self) -> F {
+        round_up(self.hi - self.lo)
+    }
+
+    /// The magnitude: the largest absolute value of any number in the interval.
+    pub fn mag(self) -> F {
+        self.lo.abs().max(self.hi.abs())
+    }
+
+    /// Returns `true` if the number lies within the interval.
+    pub fn contains(self, value: F) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    /// Returns `true` if every number in `other` lies within this interval.
+    pub fn contains_interval(self, other: Interval<F>) -> bool {
+        self.lo <= other.lo && other.hi <= self.hi
+    }
+
+    /// The intersection of two intervals, or `None` if they are disjoint.
+    pub fn intersect(self, other: Interval<F>) -> Option<Interval<F>> {
+        let lo = self.lo.max(other.lo);
+        let hi = self.hi.min(other.hi);
+
+        if lo <= hi {
+            Some(Interval { lo, hi })
+        } else {
+            None
+        }
+    }
+
+    /// The smallest interval containing both intervals.
+    pub fn hull(self, other: Interval<F>) -> Interval<F> {
+        Interval {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi),
+        }
+    }
+
+    /// An enclosure of the square root of every non-negative number in the interval.
+    pub fn sqrt(self) -> Interval<F> {
+        assert!(
+            self.hi >= F::zero(),
+            "Square root of negative interval {:?}",
+            self
+        );
+
+        Interval::rounded(self.lo.max(F::zero()).sqrt(), self.hi.sqrt())
+    }
+}
+
+/// A number at most `x`, at least one unit in the last place below it.
+fn round_down<F: FloatScalar>(x: F) -> F {
+    if x.is_infinite() || x.is_nan() {
+        x
+    } else {
+        x - (x.abs() * F::epsilon() + F::min_positive_value())
+    }
+}
+
+/// A number at least `x`, at least one unit in the last place above it.
+fn round_up<F: FloatScalar>(x: F) -> F {
+    -round_down(-x)
+}
+
+impl<F> From<F> for Interval<F>
+where
+    F: FloatScalar,
+{
+    fn from(value: F) -> Interval<F> {
+        Interval::point(value)
+    }
+}
+
+impl<F> Zero for Interval<F>
+where
+    F: FloatScalar,
+{
+    fn zero() -> Interval<F> {
+        Interval::point(F::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.lo.is_zero() && self.hi.is_zero()
+    }
+}
+
+impl<F> One for Interval<F>
+where
+    F: FloatScalar,
+{
+    fn one() -> Interval<F> {
+        Interval::point(F::one())
+    }
+}
+
+impl<F> Num for Interval<F>
+where
+    F: FloatScalar,
+{
+    type FromStrRadixErr = F::FromStrRadixErr;
+
+    /// Parse a number into an interval that also contains the exact decimal value, which
+    /// may not be representable.
+    fn from_str_radix(s: &str, radix: u32) -> Result<Interval<F>, Self::FromStrRadixErr> {
+        F::from_str_radix(s, radix).map(|value| Interval::rounded(value, value))
+    }
+}
+
+impl<F> Add for Interval<F>
+where
+    F: FloatScalar,
+{
+    type Output = Interval<F>;
+
+    fn add(self, rhs: Interval<F>) -> Interval<F> {
+        Interval::rounded(self.lo + rhs.lo, self.hi + rhs.hi)
+    }
+}
+
+impl<F> Sub for Interval<F>
+where
+    F: FloatScalar,
+{
+    type Output = Interval<F>;
+
+    fn sub(self, rhs: Interval<F>) -> Interval<F> {
+        Interval::rounded(self.lo - rhs.hi, self.hi - rhs.lo)
+    }
+}
+
+impl<F> Mul for Interval<F>
+where
+    F: FloatScalar,
+{
+    type Output = Interval<F>;
+
+    fn mul(self, rhs: Interval<F>) -> Interval<F> {
+        let products = [
+            self.lo * rhs.lo,
+            self.lo * rhs.hi,
+            self.hi * rhs.lo,
+            self.hi * rhs.hi,
+        ];
+
+        let lo = products.iter().cloned().fold(F::infinity(), F::min);
+        let hi = products.iter().cloned().fold(F::neg_infinity(), F::max);
+
+        Interval::rounded(lo, hi)
+    }
+}
+
+impl<F> Div for Interval<F>
+where
+    F: FloatScalar,
+{
+    type Output = Interval<F>;
+
+    /// Division by an interval containing zero gives the entire real line.
+    fn div(self, rhs: Interval<F>) -> Interval<F> {
+        if rhs.contains(F::zero()) {
+            return Interval::entire();
+        }
+
+        let recip = Interval::rounded(rhs.hi.recip(), rhs.lo.recip());
+        self.mul(recip)
+    }
+}
+
+impl<F> Rem for Interval<F>
+where
+    F: FloatScalar,
+{
+    type Output = Interval<F>;
+
+    /// The remainder is smaller than the divisor in magnitude and has the sign of the
+    /// dividend, which gives a simple (but wide) enclosure.
+    fn rem(self, rhs: Interval<F>) -> Interval<F> {
+        let bound = rhs.mag();
+
+        if self.lo >= F::zero() {
+            Interval::new(F::zero(), bound.min(self.hi))
+        } else if self.hi <= F::zero() {
+            Interval::new((-bound).max(self.lo), F::zero())
+        } else {
+            Interval::new(-bound, bound)
+        }
+    }
+}
+
+impl<F> Neg for Interval<F>
+where
+    F: FloatScalar,
+{
+    type Output = Interval<F>;
+
+    fn neg(self) -> Interval<F> {
+        Interval {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+impl_assign_operators!([F] Interval<F> where F: FloatScalar);
+
+impl<F> fmt::Display for Interval<F>
+where
+    F: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[")?;
+        self.lo.fmt(f)?;
+        write!(f, ", ")?;
+        self.hi.fmt(f)?;
+        write!(f, "]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Matrix;
+
+    fn i(lo: f64, hi: f64) -> Interval<f64> {
+        Interval::new(lo, hi)
+    }
+
+    #[test]
+    fn arithmetic_encloses_result() {
+        let a = i(1.0, 2.0);
+        let b = i(-3.0, 4.0);
+
+        assert!((a + b).contains_interval(i(-2.0, 6.0)));
+        assert!((a - b).contains_interval(i(-3.0, 5.0)));
+        assert!((a * b).contains_interval(i(-6.0, 8.0)));
+        assert!((b / a).contains_interval(i(-3.0, 4.0)));
+        assert_eq!(a / b, Interval::entire());
+    }
+
+    #[test]
+    fn outward_rounding() {
+        let tenth: Interval<f64> = Num::from_str_radix("0.1", 10).unwrap();
+        let sum = (0..10).fold(Interval::zero(), |acc, _| acc + tenth);
+
+        assert!(sum.contains(1.0));
+        assert!(sum.lo() < sum.hi());
+        assert!(sum.width() < 1e-14);
+    }
+
+    #[test]
+    fn matrix_vector_enclosure() {
+        let a = Matrix::new(vec![vec![0.1, 0.2], vec![0.3, 0.4]]).map(Interval::point);
+        let x = mat![i(0.9, 1.1), i(-1.0, 1.0)];
+
+        let y = &a * &x;
+
+        for &(x0, x1) in &[(0.9, -1.0), (1.1, 1.0), (1.0, 0.5)] {
+            assert!(y[0].contains(0.1 * x0 + 0.2 * x1));
+            assert!(y[1].contains(0.3 * x0 + 0.4 * x1));
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use super::*;
+use crate::{matrix::Matrix, vector::Vector};
+
+/// The maximum number of Krawczyk iterations used to tighten an enclosure.
+const MAX_ITERATIONS: usize = 32;
+
+impl<F> Matrix<Interval<F>>
+where
+    F: FloatScalar,
+{
+    /// The matrix of midpoints of every interval.
+    pub fn mid(&self) -> Matrix<F> {
+        self.clone().map(Interval::mid)
+    }
+
+    /// Find an enclosure of the solution of `self * x == b` for every matrix and vector
+    /// within the intervals, using the Krawczyk method.
+    ///
+    /// Returns `None` if the enclosure could not be verified, which happens when the
+    /// matrix is singular or too ill-conditioned compared to the widths of the intervals.
+    /// If `Some` is returned, the solution is guaranteed to lie within the intervals.
+    /// ```
+    /// # use land::{mat, Interval, Matrix};
+    /// # fn main() {
+    /// let a = mat![[4.0, 1.0], [1.0, 3.0]].map(Interval::point);
+    /// let b = mat![Interval::point(1.0), Interval::point(2.0)];
+    ///
+    /// let x = a.verified_solve(&b).unwrap();
+    ///
+    /// assert!(x[0].contains(1.0 / 11.0));
+    /// assert!(x[1].contains(7.0 / 11.0));
+    /// # }
+    /// ```
+    pub fn verified_solve(&self, b: &Vector<Interval<F>>) -> Option<Vector<Interval<F>>> {
+        assert!(
+            self.dim().rows == self.dim().cols,
+            "Matrix must be square. Matrix is of size {}",
+            self.dim()
+        );
+        assert!(
+            self.dim().rows == b.len(),
+            "Matrix dimensions must agree. Left hand side is {} and right hand side has length {}",
+            self.dim(),
+            b.len(),
+        );
+
+        let n = b.len();
+
+        // Approximate inverse of the midpoint matrix, used as preconditioner
+        let r_approx = approximate_inverse(self.mid())?;
+        let r = r_approx.clone().map(Interval::point);
+
+        // Approximate solution
+        let b_mid: Vector<F> = b.iter().map(|e| e.mid()).collect::<Vec<_>>().into();
+        let x_approx: Vector<Interval<F>> = (&r_approx * &b_mid)
+            .iter()
+            .map(|&e| Interval::point(e))
+            .collect::<Vec<_>>()
+            .into();
+
+        // The error e = x - x_approx satisfies e = z + C e
+        let z = &r * &(b - &(self * &x_approx));
+        let c = &Matrix::identity(n) - &(&r * self);
+
+        // An initial enclosure [-radius, radius] exists if C is a contraction
+        let c_norm = (0..n)
+            .map(|row| {
+                c[row]
+                    .iter()
+                    .fold(Interval::zero(), |acc, e| acc + Interval::point(e.mag()))
+            })
+            .fold(F::zero(), |acc, sum| acc.max(sum.hi()));
+        let z_norm = z.iter().fold(F::zero(), |acc, e| acc.max(e.mag()));
+
+        if c_norm >= F::one() {
+            return None;
+        }
+
+        let radius = (Interval::point(z_norm) / (Interval::one() - Interval::point(c_norm))).hi();
+        let mut error = Vector::filled(Interval::new(-radius, radius), n);
+
+        for _ in 0..MAX_ITERATIONS {
+            let next = &z + &(&c * &error);
+
+            let mut tightened = Vec::with_capacity(n);
+            for (new, old) in next.iter().zip(error.iter()) {
+                tightened.push(new.intersect(*old)?);
+            }
+
+            let tightened: Vector<Interval<F>> = tightened.into();
+            if tightened == error {
+                break;
+            }
+            error = tightened;
+        }
+
+        Some(x_approx + error)
+    }
+}
+
+/// Invert a floating point matrix using Gauss–Jordan elimination with partial pivoting.
+fn approximate_inverse<F>(matrix: Matrix<F>) -> Option<Matrix<F>>
+where
+    F: FloatScalar,
+{
+    let n = matrix.dim().rows;
+    let mut augmented = matrix.augment(&Matrix::identity(n));
+
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&a, &b| {
+            augmented[a][col]
+                .abs()
+                .partial_cmp(&augmented[b][col].abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+
+        if augmented[pivot][col].is_zero() {
+            return None;
+        }
+
+        augmented.swap_rows(col, pivot);
+        let leading = augmented[col][col];
+        augmented.scale_row(col, leading.recip());
+
+        for row in 0..n {
+            let factor = augmented[row][col];
+            if row != col && !factor.is_zero() {
+                augmented.add_row_multiple(row, col, -factor);
+            }
+        }
+    }
+
+    Some(augmented.columns(n, 2 * n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encloses_hilbert_solution() {
+        // The Hilbert matrix is badly conditioned; the exact solution for b = H * 1 is 1
+        let n = 6;
+        let mut h = Matrix::zeros([n, n].into());
+        for row in 0..n {
+            for col in 0..n {
+                h[row][col] = Interval::one() / Interval::point((row + col + 1) as f64);
+            }
+        }
+        let ones = Vector::filled(Interval::one(), n);
+        let b = &h * &ones;
+
+        let x = h.verified_solve(&b).unwrap();
+
+        for e in x.iter() {
+            assert!(e.contains(1.0));
+            assert!(e.width() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn encloses_solution_set_of_interval_system() {
+        let a = Matrix::new(vec![
+            vec![Interval::new(3.9, 4.1), Interval::new(0.9, 1.1)],
+            vec![Interval::new(0.9, 1.1), Interval::new(2.9, 3.1)],
+        ]);
+        let b = mat![Interval::point(1.0), Interval::point(2.0)];
+
+        let x = a.verified_solve(&b).unwrap();
+
+        for &(a00, a01, a11) in &[(3.9, 0.9, 2.9), (4.1, 1.1, 3.1), (4.0, 1.0, 3.0)] {
+            let det = a00 * a11 - a01 * a01;
+            assert!(x[0].contains((a11 - 2.0 * a01) / det));
+            assert!(x[1].contains((2.0 * a00 - a01) / det));
+        }
+    }
+
+    #[test]
+    fn singular_is_not_verified() {
+        let a = mat![[1.0, 2.0], [2.0, 4.0]].map(Interval::point);
+        let b = mat![Interval::point(1.0), Interval::point(2.0)];
+
+        assert!(a.verified_solve(&b).is_none());
+    }
+}
@@ -0,0 +1,61 @@
+//! Reading and writing matrices in common file formats.
+
+pub mod csv;
+pub mod image;
+pub mod mat;
+pub mod matrix_market;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod native;
+pub mod npy;
+#[cfg(feature = "npz")]
+pub mod npz;
+
+use std::fmt;
+
+/// An error while reading or writing a matrix file.
+#[derive(Debug)]
+pub enum Error {
+    /// The underlying reader or writer failed.
+    Io(std::io::Error),
+    /// The contents are malformed. Lines are numbered from 1.
+    Parse { line: usize, message: String },
+    /// The contents of a binary file are malformed or unsupported.
+    Invalid(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub(crate) fn parse(line: usize, message: impl Into<String>) -> Error {
+        Error::Parse {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Error {
+        Error::Io(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(error) => error.fmt(f),
+            Error::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            Error::Invalid(message) => message.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Parse { .. } | Error::Invalid(_) => None,
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! Comma separated values and other delimited text.
+//! ```
+//! # use land::{io::csv::CsvOptions, mat, Matrix};
+//! # fn main() {
+//! let file = "\
+//! ## measurements
+//! time,speed
+//! 0.0,1.5
+//! 0.5,
+//! ";
+//! let options = CsvOptions {
+//!     header: true,
+//!     missing_as_nan: true,
+//!     ..CsvOptions::default()
+//! };
+//!
+//! let (data, columns) = Matrix::<f64>::from_csv(file.as_bytes(), &options).unwrap();
+//!
+//! assert_eq!(columns.unwrap(), vec!["time", "speed"]);
+//! assert_eq!(data[0], [0.0, 1.5]);
+//! assert!(data[1][1].is_nan());
+//! # }
+//! ```
+
+use super::{Error, Result};
+use crate::matrix::{Dimensions, Matrix};
+use std::{
+    fmt::Display,
+    io::{BufRead, Write},
+    str::FromStr,
+};
+
+/// Options for reading and writing delimited text.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// The character separating values on a line.
+    pub delimiter: char,
+    /// Whether the first line (after comments) holds the names of the columns.
+    pub header: bool,
+    /// Lines starting with this character are skipped when reading.
+    pub comment: Option<char>,
+    /// Read empty values as `NaN` instead of failing. Only valid for element types that
+    /// can parse `"NaN"`, such as `f32` and `f64`.
+    pub missing_as_nan: bool,
+    /// The column names written as header when `header` is set. Ignored when reading.
+    pub column_names: Vec<String>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> CsvOptions {
+        CsvOptions {
+            delimiter: ',',
+            header: false,
+            comment: Some('#'),
+            missing_as_nan: false,
+            column_names: Vec::new(),
+        }
+    }
+}
+
+/// Split a line into fields. Fields may be quoted with `"`, where `""` is a literal quote.
+/// Whitespace around a field is dropped, but kept inside the quotes of a quoted field.
+fn split_fields(line: &str, delimiter: char) -> std::result::Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        let mut field = String::new();
+
+        while chars
+            .peek()
+            .is_some_and(|&c| c != delimiter && c.is_whitespace())
+        {
+            chars.next();
+        }
+
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => {
+                        return Err(format!("unterminated quote in column {}", fields.len() + 1))
+                    }
+                }
+            }
+            while chars.peek().is_some_and(|&c| c != delimiter) {
+                chars.next();
+            }
+            fields.push(field);
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c == delimiter {
+                    break;
+                }
+                field.push(c);
+                chars.next();
+            }
+            fields.push(field.trim_end().to_owned());
+        }
+
+        if chars.next().is_none() {
+            return Ok(fields);
+        }
+    }
+}
+
+/// Quote a field if it contains the delimiter or quotes, or has whitespace around it.
+fn quote_field(field: &str, delimiter: char) -> String {
+    if field.contains(delimiter)
+        || field.contains('"')
+        || field.contains('\n')
+        || field.trim() != field
+    {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+impl<S> Matrix<S>
+where
+    S: FromStr,
+    S::Err: Display,
+{
+    /// Read a matrix from delimited text, where every line is a row.
+    ///
+    /// Returns the matrix together with the column names if `options.header` is set.
+    /// Blank lines are skipped. Errors point out the line and column (both numbered from
+    /// 1) of the offending value.
+    pub fn from_csv<R: BufRead>(
+        reader: R,
+        options: &CsvOptions,
+    ) -> Result<(Matrix<S>, Option<Vec<String>>)> {
+        let mut header = None;
+        let mut elements = Vec::new();
+        let mut dimensions = Dimensions { rows: 0, cols: 0 };
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let number = index + 1;
+
+            let trimmed = line.trim();
+            let is_comment = options
+                .comment
+                .is_some_and(|comment| trimmed.starts_with(comment));
+            if trimmed.is_empty() || is_comment {
+                continue;
+            }
+
+            let fields =
+                split_fields(&line, options.delimiter).map_err(|e| Error::parse(number, e))?;
+
+            if options.header && header.is_none() {
+                dimensions.cols = fields.len();
+                header = Some(fields);
+                continue;
+            }
+
+            if dimensions.rows == 0 && header.is_none() {
+                dimensions.cols = fields.len();
+            }
+            if fields.len() != dimensions.cols {
+                return Err(Error::parse(
+                    number,
+                    format!(
+                        "expected {} values, found {}",
+                        dimensions.cols,
+                        fields.len()
+                    ),
+                ));
+            }
+
+            for (col, field) in fields.iter().enumerate() {
+                let field = match field.as_str() {
+                    "" if options.missing_as_nan => "NaN",
+                    "" => {
+                        return Err(Error::parse(
+                            number,
+                            format!("missing value in column {}", col + 1),
+                        ))
+                    }
+                    field => field,
+                };
+
+                let value = field.parse().map_err(|e| {
+                    Error::parse(
+                        number,
+                        format!("invalid value '{}' in column {}: {}", field, col + 1, e),
+                    )
+                })?;
+                elements.push(value);
+            }
+
+            dimensions.rows += 1;
+        }
+
+        Ok((Matrix::from_row_major(dimensions, elements), header))
+    }
+}
+
+impl<S> Matrix<S>
+where
+    S: Display,
+{
+    /// Write the matrix as delimited text, one row per line.
+    ///
+    /// Panics if `options.header` is set and the number of column names does not match
+    /// the number of columns.
+    pub fn to_csv<W: Write>(&self, mut writer: W, options: &CsvOptions) -> Result<()> {
+        let delimiter = options.delimiter.to_string();
+
+        if options.header {
+            assert!(
+                options.column_names.len() == self.dim().cols,
+                "Number of column names must match the number of columns. There are {} names and {} columns",
+                options.column_names.len(),
+                self.dim().cols
+            );
+
+            let names: Vec<String> = options
+                .column_names
+                .iter()
+                .map(|name| quote_field(name, options.delimiter))
+                .collect();
+            writeln!(writer, "{}", names.join(&delimiter))?;
+        }
+
+        for row in 0..self.dim().rows {
+            let values: Vec<String> = self.row(row).iter().map(ToString::to_string).collect();
+            writeln!(writer, "{}", values.join(&delimiter))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read<S>(file: &str, options: &CsvOptions) -> Result<(Matrix<S>, Option<Vec<String>>)>
+    where
+        S: FromStr,
+        S::Err: Display,
+    {
+        Matrix::from_csv(file.as_bytes(), options)
+    }
+
+    #[test]
+    fn delimiter_and_quotes() {
+        let options = CsvOptions {
+            delimiter: ';',
+            header: true,
+            ..CsvOptions::default()
+        };
+
+        let (a, columns) =
+            read::<i32>("\"a;b\";\"say \"\"hi\"\"\"\n1; 2\n\n3;4\n", &options).unwrap();
+
+        assert_eq!(a, mat![[1, 2], [3, 4]]);
+        assert_eq!(columns.unwrap(), vec!["a;b", "say \"hi\""]);
+    }
+
+    #[test]
+    fn whitespace_inside_quotes_is_kept() {
+        let options = CsvOptions {
+            header: true,
+            ..CsvOptions::default()
+        };
+
+        let (a, columns) = read::<i32>(" x , \" y \" ,\"\tz\"\n 1 ,2, 3\n", &options).unwrap();
+
+        assert_eq!(a, mat![[1, 2, 3]]);
+        assert_eq!(columns.unwrap(), vec!["x", " y ", "\tz"]);
+    }
+
+    #[test]
+    fn errors_point_to_value() {
+        let message = |file: &str| match read::<f64>(file, &CsvOptions::default()) {
+            Err(Error::Parse { line, message }) => format!("{}: {}", line, message),
+            other => panic!("expected parse error, got {:?}", other),
+        };
+
+        assert_eq!(message("1,2\n# c\n3,\n"), "3: missing value in column 2");
+        assert_eq!(
+            message("1,2\n3,x\n"),
+            "2: invalid value 'x' in column 2: invalid float literal"
+        );
+        assert_eq!(message("1,2\n3\n"), "2: expected 2 values, found 1");
+    }
+
+    #[test]
+    fn nan_for_missing_integers_fails() {
+        let options = CsvOptions {
+            missing_as_nan: true,
+            ..CsvOptions::default()
+        };
+
+        assert!(read::<i32>("1,\n", &options).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let a = mat![[1.5, -2.0, 0.1], [1e-10, 3.0, 4.25]];
+        let options = CsvOptions {
+            delimiter: '\t',
+            header: true,
+            column_names: vec!["x".into(), " y".into(), "z\tw".into()],
+            ..CsvOptions::default()
+        };
+
+        let mut file = Vec::new();
+        a.to_csv(&mut file, &options).unwrap();
+        let file = String::from_utf8(file).unwrap();
+
+        assert_eq!(
+            file,
+            "x\t\" y\"\t\"z\tw\"\n1.5\t-2\t0.1\n0.0000000001\t3\t4.25\n"
+        );
+
+        let (b, columns) = read::<f64>(&file, &options).unwrap();
+        assert_eq!(b, a);
+        assert_eq!(columns.unwrap(), options.column_names);
+    }
+}
@@ -0,0 +1,351 @@
+//! Heat maps and sparsity plots of matrices as netpbm (PGM, PPM) or SVG images.
+//!
+//! Every element becomes one pixel, with row 0 at the top.
+//! ```
+//! # use land::{io::image::{Colormap, ImageFormat}, mat};
+//! # fn main() {
+//! let jacobian = mat![[2.0, -1.0, 0.0], [-1.0, 2.0, -1.0], [0.0, -1.0, 2.0]];
+//!
+//! let mut image = Vec::new();
+//! jacobian
+//!     .write_heatmap_to(&mut image, ImageFormat::Ppm, Colormap::CoolWarm)
+//!     .unwrap();
+//! assert!(image.starts_with(b"P6\n3 3\n255\n"));
+//!
+//! let mut svg = Vec::new();
+//! jacobian.write_spy_to(&mut svg, ImageFormat::Svg).unwrap();
+//! assert_eq!(String::from_utf8(svg).unwrap().matches("<rect").count(), 1 + 7);
+//! # }
+//! ```
+
+use super::{Error, Result};
+use crate::{
+    matrix::{Dimensions, Matrix},
+    traits::Scalar,
+};
+use num::ToPrimitive;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+type Rgb = [u8; 3];
+
+const WHITE: Rgb = [255, 255, 255];
+const BLACK: Rgb = [0, 0, 0];
+
+/// An image file format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Binary greyscale netpbm, storing the luminance of every colour.
+    Pgm,
+    /// Binary colour netpbm.
+    Ppm,
+    /// Scalable vector graphics with one rectangle per element.
+    Svg,
+}
+
+impl ImageFormat {
+    /// The format for a path with the extension `pgm`, `ppm` or `svg`.
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+
+        match extension.as_str() {
+            "pgm" => Some(ImageFormat::Pgm),
+            "ppm" => Some(ImageFormat::Ppm),
+            "svg" => Some(ImageFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
+/// Maps values to colours.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Colormap {
+    /// From black at the smallest value to white at the largest.
+    Gray,
+    /// The perceptually uniform map from matplotlib, dark blue to yellow.
+    Viridis,
+    /// A diverging map centred on zero, blue for negative and red for positive values.
+    /// Useful for Jacobians and covariance matrices.
+    CoolWarm,
+}
+
+const VIRIDIS: [Rgb; 9] = [
+    [68, 1, 84],
+    [72, 40, 120],
+    [62, 73, 137],
+    [49, 104, 142],
+    [38, 130, 142],
+    [31, 158, 137],
+    [53, 183, 121],
+    [110, 206, 88],
+    [253, 231, 37],
+];
+
+const COOL_WARM: [Rgb; 5] = [
+    [59, 76, 192],
+    [141, 176, 254],
+    [221, 221, 221],
+    [244, 154, 123],
+    [180, 4, 38],
+];
+
+/// Linear interpolation between evenly spaced colours.
+fn interpolate(colors: &[Rgb], t: f64) -> Rgb {
+    let position = t * (colors.len() - 1) as f64;
+    let index = (position.floor() as usize).min(colors.len() - 2);
+    let fraction = position - index as f64;
+
+    let mut color = [0; 3];
+    for channel in 0..3 {
+        let from = f64::from(colors[index][channel]);
+        let to = f64::from(colors[index + 1][channel]);
+        color[channel] = (from + (to - from) * fraction).round() as u8;
+    }
+    color
+}
+
+impl Colormap {
+    /// The colour for `t` between 0 and 1. Values outside are clamped, and NaN maps to 0.
+    pub fn color(&self, t: f64) -> [u8; 3] {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+
+        match self {
+            Colormap::Gray => [(t * 255.0).round() as u8; 3],
+            Colormap::Viridis => interpolate(&VIRIDIS, t),
+            Colormap::CoolWarm => interpolate(&COOL_WARM, t),
+        }
+    }
+}
+
+fn luminance([r, g, b]: Rgb) -> u8 {
+    (0.299 * f64::from(r) + 0.587 * f64::from(g) + 0.114 * f64::from(b)).round() as u8
+}
+
+/// Write an image with a pixel for every element.
+///
+/// Pixels equal to `background` are left out of SVG images, which keeps sparsity plots of
+/// large sparse matrices small.
+fn write_image<W, F>(
+    mut writer: W,
+    format: ImageFormat,
+    dimensions: Dimensions,
+    background: Option<Rgb>,
+    pixel: F,
+) -> Result<()>
+where
+    W: Write,
+    F: Fn(usize, usize) -> Rgb,
+{
+    let Dimensions { rows, cols } = dimensions;
+
+    match format {
+        ImageFormat::Pgm | ImageFormat::Ppm => {
+            let magic = if format == ImageFormat::Pgm {
+                "P5"
+            } else {
+                "P6"
+            };
+            write!(writer, "{}\n{} {}\n255\n", magic, cols, rows)?;
+
+            let mut line = Vec::new();
+            for row in 0..rows {
+                line.clear();
+                for col in 0..cols {
+                    let color = pixel(row, col);
+                    match format {
+                        ImageFormat::Pgm => line.push(luminance(color)),
+                        _ => line.extend_from_slice(&color),
+                    }
+                }
+                writer.write_all(&line)?;
+            }
+        }
+        ImageFormat::Svg => {
+            // Scale small matrices up so they are visible at their natural size
+            let scale = (512 / rows.max(cols).max(1)).max(1);
+            writeln!(
+                writer,
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">",
+                cols * scale,
+                rows * scale,
+                cols,
+                rows
+            )?;
+
+            let fill = |[r, g, b]: Rgb| format!("#{:02x}{:02x}{:02x}", r, g, b);
+            if let Some(background) = background {
+                writeln!(
+                    writer,
+                    "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    cols,
+                    rows,
+                    fill(background)
+                )?;
+            }
+
+            for row in 0..rows {
+                for col in 0..cols {
+                    let color = pixel(row, col);
+                    if Some(color) != background {
+                        writeln!(
+                            writer,
+                            "<rect x=\"{}\" y=\"{}\" width=\"1\" height=\"1\" fill=\"{}\"/>",
+                            col,
+                            row,
+                            fill(color)
+                        )?;
+                    }
+                }
+            }
+            writeln!(writer, "</svg>")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Create a file for an image, choosing the format from the extension.
+fn create(path: &Path) -> Result<(BufWriter<File>, ImageFormat)> {
+    let format = ImageFormat::from_path(path).ok_or_else(|| {
+        Error::Invalid(format!(
+            "unsupported image format for '{}', expected a .pgm, .ppm or .svg file",
+            path.display()
+        ))
+    })?;
+
+    Ok((BufWriter::new(File::create(path)?), format))
+}
+
+impl<S> Matrix<S>
+where
+    S: ToPrimitive,
+{
+    /// Write a heat map of the matrix to a `.pgm`, `.ppm` or `.svg` file.
+    ///
+    /// The colormap spans the smallest to the largest element, except `CoolWarm`, which is
+    /// centred on zero and spans the largest absolute value. NaN is drawn like the smallest
+    /// value.
+    pub fn write_heatmap<P: AsRef<Path>>(&self, path: P, colormap: Colormap) -> Result<()> {
+        let (mut writer, format) = create(path.as_ref())?;
+        self.write_heatmap_to(&mut writer, format, colormap)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Write a heat map of the matrix in the given format. See `write_heatmap`.
+    pub fn write_heatmap_to<W: Write>(
+        &self,
+        writer: W,
+        format: ImageFormat,
+        colormap: Colormap,
+    ) -> Result<()> {
+        let values: Vec<f64> = self
+            .iter_rows()
+            .map(|element| element.to_f64().unwrap_or(f64::NAN))
+            .collect();
+
+        let (lo, hi) = match colormap {
+            Colormap::CoolWarm => {
+                let max = values
+                    .iter()
+                    .fold(0.0f64, |max, value| max.max(value.abs()));
+                (-max, max)
+            }
+            _ => values
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &value| {
+                    (lo.min(value), hi.max(value))
+                }),
+        };
+
+        let cols = self.dim().cols;
+        write_image(writer, format, self.dim(), None, |row, col| {
+            let value = values[row * cols + col];
+            let t = if hi > lo {
+                (value - lo) / (hi - lo)
+            } else {
+                0.5
+            };
+            colormap.color(t)
+        })
+    }
+}
+
+impl<S> Matrix<S>
+where
+    S: Scalar,
+{
+    /// Write a sparsity plot of the matrix to a `.pgm`, `.ppm` or `.svg` file, with nonzero
+    /// elements in black on white.
+    pub fn write_spy<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let (mut writer, format) = create(path.as_ref())?;
+        self.write_spy_to(&mut writer, format)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Write a sparsity plot of the matrix in the given format. See `write_spy`.
+    pub fn write_spy_to<W: Write>(&self, writer: W, format: ImageFormat) -> Result<()> {
+        write_image(writer, format, self.dim(), Some(WHITE), |row, col| {
+            if self.row(row)[col].is_zero() {
+                WHITE
+            } else {
+                BLACK
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colormaps() {
+        assert_eq!(Colormap::Gray.color(0.5), [128; 3]);
+        assert_eq!(Colormap::Viridis.color(0.0), VIRIDIS[0]);
+        assert_eq!(Colormap::Viridis.color(1.0), VIRIDIS[8]);
+        assert_eq!(Colormap::Viridis.color(0.0625), [70, 21, 102]);
+        assert_eq!(Colormap::CoolWarm.color(0.5), [221; 3]);
+        assert_eq!(Colormap::CoolWarm.color(f64::NAN), COOL_WARM[0]);
+        assert_eq!(Colormap::CoolWarm.color(2.0), COOL_WARM[4]);
+    }
+
+    #[test]
+    fn heatmap_pixels() {
+        let a = mat![[0.0, 1.0, 2.0], [3.0, 4.0, f64::NAN]];
+
+        let mut image = Vec::new();
+        a.write_heatmap_to(&mut image, ImageFormat::Pgm, Colormap::Gray)
+            .unwrap();
+
+        assert_eq!(image, b"P5\n3 2\n255\n\x00\x40\x80\xbf\xff\x00");
+
+        let mut image = Vec::new();
+        mat![[-1.0, 0.5]]
+            .write_heatmap_to(&mut image, ImageFormat::Ppm, Colormap::CoolWarm)
+            .unwrap();
+
+        assert_eq!(&image[11..14], &COOL_WARM[0]);
+        assert_eq!(&image[14..], &Colormap::CoolWarm.color(0.75));
+    }
+
+    #[test]
+    fn spy_files() {
+        let a = mat![[1, 0, 0], [0, 0, 5]];
+        let directory = std::env::temp_dir();
+
+        let path = directory.join(format!("land-spy-{}.pgm", std::process::id()));
+        a.write_spy(&path).unwrap();
+        let image = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(image, b"P5\n3 2\n255\n\x00\xff\xff\xff\xff\x00");
+
+        let error = a.write_spy(directory.join("spy.png")).unwrap_err();
+        assert!(error.to_string().starts_with("unsupported image format"));
+    }
+}
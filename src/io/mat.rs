@@ -0,0 +1,723 @@
+//! MATLAB Level 5 `.mat` files, as written by `save -v6` in MATLAB or `save -mat` in
+//! Octave.
+//!
+//! Numeric arrays of class double, single, int8, int16, int32 and int64 can be read, real or
+//! complex. Compressed (v7) and HDF5 based (v7.3) files are not supported. MATLAB stores
+//! arrays in column major order, which is converted to and from the row major `Matrix`.
+//! See <https://www.mathworks.com/help/pdf_doc/matlab/matfile_format.pdf>.
+//! ```
+//! # use land::{io::mat::{MatReader, MatWriter}, mat, Matrix};
+//! # use num::Complex;
+//! # fn main() {
+//! let a = mat![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+//! let z = mat![[Complex::new(1.0f32, -1.0)]];
+//!
+//! let mut writer = MatWriter::new(Vec::new()).unwrap();
+//! writer.add_matrix("A", &a).unwrap();
+//! writer.add_matrix("z", &z).unwrap();
+//! let file = writer.finish().unwrap();
+//!
+//! let reader = MatReader::new(file.as_slice()).unwrap();
+//! assert_eq!(reader.names(), vec!["A", "z"]);
+//! assert_eq!(reader.matrix::<f64>("A").unwrap(), a);
+//! assert_eq!(reader.matrix::<Complex<f32>>("z").unwrap(), z);
+//! # }
+//! ```
+
+use super::{Error, Result};
+use crate::{
+    matrix::{Dimensions, Matrix},
+    traits::Scalar,
+    vector::Vector,
+};
+use num::{Complex, NumCast, Zero};
+use std::{
+    convert::TryInto,
+    io::{Read, Write},
+};
+
+const HEADER_LEN: usize = 128;
+
+// Data types of data elements
+const MI_INT8: u32 = 1;
+const MI_UINT8: u32 = 2;
+const MI_INT16: u32 = 3;
+const MI_UINT16: u32 = 4;
+const MI_INT32: u32 = 5;
+const MI_UINT32: u32 = 6;
+const MI_SINGLE: u32 = 7;
+const MI_DOUBLE: u32 = 9;
+const MI_INT64: u32 = 12;
+const MI_UINT64: u32 = 13;
+const MI_MATRIX: u32 = 14;
+const MI_COMPRESSED: u32 = 15;
+
+/// The bit of the array flags marking complex arrays.
+const COMPLEX_FLAG: u32 = 0x0800;
+
+/// The class of a MATLAB array.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Class {
+    Double,
+    Single,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    /// Any other class, such as char, cell, struct, sparse or the unsigned integers,
+    /// holding the class number from the file.
+    Other(u8),
+}
+
+impl Class {
+    fn from_number(number: u8) -> Class {
+        match number {
+            6 => Class::Double,
+            7 => Class::Single,
+            8 => Class::Int8,
+            10 => Class::Int16,
+            12 => Class::Int32,
+            14 => Class::Int64,
+            number => Class::Other(number),
+        }
+    }
+
+    fn number(self) -> u8 {
+        match self {
+            Class::Double => 6,
+            Class::Single => 7,
+            Class::Int8 => 8,
+            Class::Int16 => 10,
+            Class::Int32 => 12,
+            Class::Int64 => 14,
+            Class::Other(number) => number,
+        }
+    }
+}
+
+/// A real number type that MATLAB arrays can hold.
+pub trait Real: Scalar + Copy + NumCast {
+    const CLASS: Class;
+
+    /// The data type used when writing.
+    const DATA_TYPE: u32;
+
+    /// Append the little endian bytes of the number.
+    fn extend_le_bytes(self, out: &mut Vec<u8>);
+}
+
+macro_rules! impl_real {
+    ($($scalar:ty => $class:ident, $data_type:expr);+) => {
+        $(
+            impl Real for $scalar {
+                const CLASS: Class = Class::$class;
+                const DATA_TYPE: u32 = $data_type;
+
+                fn extend_le_bytes(self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+        )+
+    };
+}
+
+impl_real!(
+    f64 => Double, MI_DOUBLE;
+    f32 => Single, MI_SINGLE;
+    i8 => Int8, MI_INT8;
+    i16 => Int16, MI_INT16;
+    i32 => Int32, MI_INT32;
+    i64 => Int64, MI_INT64
+);
+
+/// A type that can be read from and written to a `.mat` file: a real number or a complex
+/// number with real parts.
+pub trait Element: Scalar {
+    type Real: Real;
+
+    const COMPLEX: bool;
+
+    fn from_parts(re: Self::Real, im: Self::Real) -> Self;
+
+    fn parts(&self) -> (Self::Real, Self::Real);
+}
+
+macro_rules! impl_real_element {
+    ($($scalar:ty),+) => {
+        $(
+            impl Element for $scalar {
+                type Real = $scalar;
+
+                const COMPLEX: bool = false;
+
+                fn from_parts(re: $scalar, _: $scalar) -> $scalar {
+                    re
+                }
+
+                fn parts(&self) -> ($scalar, $scalar) {
+                    (*self, <$scalar>::zero())
+                }
+            }
+        )+
+    };
+}
+
+impl_real_element!(f64, f32, i8, i16, i32, i64);
+
+impl<T> Element for Complex<T>
+where
+    T: Real,
+{
+    type Real = T;
+
+    const COMPLEX: bool = true;
+
+    fn from_parts(re: T, im: T) -> Complex<T> {
+        Complex::new(re, im)
+    }
+
+    fn parts(&self) -> (T, T) {
+        (self.re, self.im)
+    }
+}
+
+/// The raw contents of a numeric data element.
+struct Data {
+    data_type: u32,
+    bytes: Vec<u8>,
+}
+
+/// A variable in a `.mat` file.
+struct Variable {
+    name: String,
+    class: Class,
+    complex: bool,
+    shape: Vec<usize>,
+    real: Option<Data>,
+    imag: Option<Data>,
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::Invalid(message.into())
+}
+
+/// Reads data elements from a byte slice.
+struct Elements<'a> {
+    bytes: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Elements<'a> {
+    fn u32(&self, bytes: &[u8]) -> u32 {
+        let bytes = bytes.try_into().unwrap();
+        if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        }
+    }
+
+    /// The next data element, as its data type and contents.
+    fn next(&mut self) -> Result<Option<(u32, &'a [u8])>> {
+        if self.bytes.is_empty() {
+            return Ok(None);
+        }
+        if self.bytes.len() < 8 {
+            return Err(invalid("MAT file ends in the middle of a data element"));
+        }
+
+        let tag = self.u32(&self.bytes[..4]);
+        let (data_type, len, start, padded) = if tag >> 16 != 0 {
+            // Small data element format, with up to 4 bytes of data in the tag
+            (tag & 0xffff, (tag >> 16) as usize, 4, 8)
+        } else {
+            let len = self.u32(&self.bytes[4..8]) as usize;
+            (tag, len, 8, 8 + len + (8 - len % 8) % 8)
+        };
+
+        if start + len > self.bytes.len() {
+            return Err(invalid("MAT file ends in the middle of a data element"));
+        }
+
+        let data = &self.bytes[start..start + len];
+        self.bytes = &self.bytes[padded.min(self.bytes.len())..];
+        Ok(Some((data_type, data)))
+    }
+
+    fn expect(&mut self, what: &str) -> Result<(u32, &'a [u8])> {
+        self.next()?
+            .ok_or_else(|| invalid(format!("MAT array is missing its {}", what)))
+    }
+}
+
+/// Decode the numbers of a data element into the real type `R`.
+fn decode<R: Real>(data: &Data, big_endian: bool) -> Result<Vec<R>> {
+    macro_rules! decode {
+        ($($data_type:ident => $stored:ty),+) => {
+            match data.data_type {
+                $(
+                    $data_type => {
+                        let numbers = data.bytes.chunks_exact(std::mem::size_of::<$stored>());
+                        if !numbers.remainder().is_empty() {
+                            return Err(invalid("MAT data element has a partial number"));
+                        }
+
+                        numbers
+                            .map(|bytes| {
+                                let bytes = bytes.try_into().unwrap();
+                                let value = if big_endian {
+                                    <$stored>::from_be_bytes(bytes)
+                                } else {
+                                    <$stored>::from_le_bytes(bytes)
+                                };
+                                <R as NumCast>::from(value).ok_or_else(|| {
+                                    invalid(format!("MAT value {} does not fit in {:?}", value, R::CLASS))
+                                })
+                            })
+                            .collect()
+                    }
+                )+
+                data_type => Err(invalid(format!("unsupported MAT data type {}", data_type))),
+            }
+        };
+    }
+
+    decode!(
+        MI_INT8 => i8,
+        MI_UINT8 => u8,
+        MI_INT16 => i16,
+        MI_UINT16 => u16,
+        MI_INT32 => i32,
+        MI_UINT32 => u32,
+        MI_INT64 => i64,
+        MI_UINT64 => u64,
+        MI_SINGLE => f32,
+        MI_DOUBLE => f64
+    )
+}
+
+fn parse_variable(contents: &[u8], big_endian: bool) -> Result<Variable> {
+    let mut elements = Elements {
+        bytes: contents,
+        big_endian,
+    };
+
+    let (_, flags) = elements.expect("array flags")?;
+    if flags.len() < 4 {
+        return Err(invalid("MAT array has invalid array flags"));
+    }
+    let flags = elements.u32(&flags[..4]);
+
+    let (_, shape) = elements.expect("dimensions")?;
+    let shape = shape
+        .chunks_exact(4)
+        .map(|size| elements.u32(size) as usize)
+        .collect();
+
+    let (_, name) = elements.expect("name")?;
+    let name = String::from_utf8_lossy(name).into_owned();
+
+    let class = Class::from_number(flags as u8);
+    let complex = flags & COMPLEX_FLAG != 0;
+
+    let mut part = |what| -> Result<Option<Data>> {
+        let (data_type, bytes) = elements.expect(what)?;
+        Ok(Some(Data {
+            data_type,
+            bytes: bytes.to_vec(),
+        }))
+    };
+
+    let (real, imag) = match class {
+        Class::Other(_) => (None, None),
+        _ if complex => (part("real part")?, part("imaginary part")?),
+        _ => (part("real part")?, None),
+    };
+
+    Ok(Variable {
+        name,
+        class,
+        complex,
+        shape,
+        real,
+        imag,
+    })
+}
+
+/// Reads the variables of a `.mat` file.
+pub struct MatReader {
+    variables: Vec<Variable>,
+    big_endian: bool,
+}
+
+impl MatReader {
+    /// Read every variable in the file.
+    pub fn new<R: Read>(mut reader: R) -> Result<MatReader> {
+        let mut file = Vec::new();
+        reader.read_to_end(&mut file)?;
+
+        if file.len() < HEADER_LEN {
+            return Err(invalid("not a MAT file"));
+        }
+        if file.starts_with(b"MATLAB 7.3") {
+            return Err(invalid("MAT v7.3 files are HDF5 and not supported"));
+        }
+        let big_endian = match &file[126..128] {
+            b"IM" => false,
+            b"MI" => true,
+            _ => return Err(invalid("not a MAT v5 file")),
+        };
+
+        let mut elements = Elements {
+            bytes: &file[HEADER_LEN..],
+            big_endian,
+        };
+        let mut variables = Vec::new();
+
+        while let Some((data_type, contents)) = elements.next()? {
+            match data_type {
+                MI_MATRIX if !contents.is_empty() => {
+                    variables.push(parse_variable(contents, big_endian)?)
+                }
+                MI_COMPRESSED => {
+                    return Err(invalid(
+                        "compressed MAT files are not supported, save with '-v6'",
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        Ok(MatReader {
+            variables,
+            big_endian,
+        })
+    }
+
+    /// The names of every variable, in the order they appear in the file.
+    pub fn names(&self) -> Vec<&str> {
+        self.variables
+            .iter()
+            .map(|variable| variable.name.as_str())
+            .collect()
+    }
+
+    /// The class of a variable.
+    pub fn class(&self, name: &str) -> Option<Class> {
+        self.variables
+            .iter()
+            .find(|variable| variable.name == name)
+            .map(|variable| variable.class)
+    }
+
+    /// Read a two dimensional numeric array.
+    ///
+    /// The class of the array must match the element type, so a `double` array can only be
+    /// read as `f64` or `Complex<f64>`. Real arrays can be read as complex.
+    pub fn matrix<S: Element>(&self, name: &str) -> Result<Matrix<S>> {
+        let variable = self
+            .variables
+            .iter()
+            .find(|variable| variable.name == name)
+            .ok_or_else(|| invalid(format!("MAT file has no variable named '{}'", name)))?;
+
+        if variable.class != S::Real::CLASS {
+            return Err(invalid(format!(
+                "MAT variable '{}' has class {:?}, expected {:?}",
+                name,
+                variable.class,
+                S::Real::CLASS
+            )));
+        }
+        if variable.complex && !S::COMPLEX {
+            return Err(invalid(format!(
+                "MAT variable '{}' is complex and cannot be read into a real matrix",
+                name
+            )));
+        }
+
+        let (rows, cols) = match variable.shape.as_slice() {
+            [rows, cols, rest @ ..] if rest.iter().all(|&size| size == 1) => (*rows, *cols),
+            shape => {
+                return Err(invalid(format!(
+                    "MAT variable '{}' has shape {:?}, expected two dimensions",
+                    name, shape
+                )))
+            }
+        };
+
+        let real: Vec<S::Real> = match &variable.real {
+            Some(data) => decode(data, self.big_endian)?,
+            None => Vec::new(),
+        };
+        let imag: Vec<S::Real> = match &variable.imag {
+            Some(data) => decode(data, self.big_endian)?,
+            None => vec![Zero::zero(); real.len()],
+        };
+        if rows.checked_mul(cols) != Some(real.len()) || imag.len() != real.len() {
+            return Err(invalid(format!(
+                "MAT variable '{}' of size {}x{} has {} elements",
+                name,
+                rows,
+                cols,
+                real.len()
+            )));
+        }
+
+        // Convert from column major order
+        let mut elements = Vec::with_capacity(real.len());
+        for row in 0..rows {
+            for col in 0..cols {
+                let index = col * rows + row;
+                elements.push(S::from_parts(real[index], imag[index]));
+            }
+        }
+
+        Ok(Matrix::from_row_major(Dimensions { rows, cols }, elements))
+    }
+
+    /// Read a row or column vector.
+    pub fn vector<S: Element>(&self, name: &str) -> Result<Vector<S>> {
+        let matrix = self.matrix::<S>(name)?;
+
+        let Dimensions { rows, cols } = matrix.dim();
+        if rows != 1 && cols != 1 {
+            return Err(invalid(format!(
+                "MAT variable '{}' of size {}x{} is not a vector",
+                name, rows, cols
+            )));
+        }
+
+        Ok(Vector::new(matrix.into_iter_rows().collect()))
+    }
+}
+
+/// The length tag of a data element of `len` bytes. MAT v5 stores it in 32 bits, so larger
+/// elements cannot be written.
+fn element_len(len: usize) -> Result<u32> {
+    len.try_into().map_err(|_| {
+        invalid(format!(
+            "MAT data element of {} bytes is larger than the limit of {} bytes",
+            len,
+            u32::MAX
+        ))
+    })
+}
+
+/// Append a data element, padded to 8 bytes.
+fn push_element(out: &mut Vec<u8>, data_type: u32, data: &[u8]) -> Result<()> {
+    let len = element_len(data.len())?;
+    out.extend_from_slice(&data_type.to_le_bytes());
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(data);
+    out.resize(out.len() + (8 - data.len() % 8) % 8, 0);
+    Ok(())
+}
+
+/// Writes variables to a `.mat` file.
+pub struct MatWriter<W: Write> {
+    writer: W,
+}
+
+impl<W> MatWriter<W>
+where
+    W: Write,
+{
+    /// Start a file by writing its header.
+    pub fn new(mut writer: W) -> Result<MatWriter<W>> {
+        let mut header = format!(
+            "MATLAB 5.0 MAT-file, Platform: {}, Created by: land {}",
+            std::env::consts::OS,
+            env!("CARGO_PKG_VERSION")
+        )
+        .into_bytes();
+        header.resize(116, b' ');
+        header.extend_from_slice(&[0; 8]);
+        header.extend_from_slice(&0x0100u16.to_le_bytes());
+        header.extend_from_slice(b"IM");
+
+        writer.write_all(&header)?;
+        Ok(MatWriter { writer })
+    }
+
+    /// Add a matrix as a two dimensional array.
+    ///
+    /// Names must start with a letter, followed by at most 62 letters, digits or
+    /// underscores.
+    pub fn add_matrix<S: Element>(&mut self, name: &str, matrix: &Matrix<S>) -> Result<()> {
+        let valid = name.len() <= 63
+            && name.starts_with(|c: char| c.is_ascii_alphabetic())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(invalid(format!("invalid MATLAB variable name '{}'", name)));
+        }
+
+        let Dimensions { rows, cols } = matrix.dim();
+        let size = |size: usize| {
+            TryInto::<i32>::try_into(size)
+                .map_err(|_| invalid(format!("matrix of size {} is too large", matrix.dim())))
+        };
+
+        let mut contents = Vec::new();
+
+        let mut flags = <u32 as From<u8>>::from(S::Real::CLASS.number());
+        if S::COMPLEX {
+            flags |= COMPLEX_FLAG;
+        }
+        let flags: Vec<u8> = [flags, 0].iter().flat_map(|x| x.to_le_bytes()).collect();
+        push_element(&mut contents, MI_UINT32, &flags)?;
+
+        let shape: Vec<u8> = [size(rows)?, size(cols)?]
+            .iter()
+            .flat_map(|size| size.to_le_bytes())
+            .collect();
+        push_element(&mut contents, MI_INT32, &shape)?;
+        push_element(&mut contents, MI_INT8, name.as_bytes())?;
+
+        // Convert to column major order
+        let mut real = Vec::new();
+        let mut imag = Vec::new();
+        for col in 0..cols {
+            for row in 0..rows {
+                let (re, im) = matrix[row][col].parts();
+                re.extend_le_bytes(&mut real);
+                im.extend_le_bytes(&mut imag);
+            }
+        }
+        push_element(&mut contents, S::Real::DATA_TYPE, &real)?;
+        if S::COMPLEX {
+            push_element(&mut contents, S::Real::DATA_TYPE, &imag)?;
+        }
+
+        let mut element = Vec::with_capacity(contents.len() + 8);
+        push_element(&mut element, MI_MATRIX, &contents)?;
+        self.writer.write_all(&element)?;
+        Ok(())
+    }
+
+    /// Add a vector as a column vector.
+    pub fn add_vector<S: Element>(&mut self, name: &str, vector: &Vector<S>) -> Result<()> {
+        let elements = vector.iter().cloned().collect();
+        let matrix = Matrix::from_row_major((vector.len(), 1).into(), elements);
+        self.add_matrix(name, &matrix)
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The header of a file written on a big or little endian machine.
+    fn header(big_endian: bool) -> Vec<u8> {
+        let mut header = b"MATLAB 5.0 MAT-file".to_vec();
+        header.resize(124, b' ');
+        header.extend_from_slice(if big_endian {
+            b"\x01\x00MI"
+        } else {
+            b"\x00\x01IM"
+        });
+        header
+    }
+
+    #[test]
+    fn compact_storage_and_small_elements() {
+        // `x = [1 2 3; 4 5 300]` as MATLAB writes it: the doubles are stored as the
+        // smallest integer type that holds them, and the name uses the small element format
+        let mut file = header(false);
+        let contents: &[&[u8]] = &[
+            b"\x06\x00\x00\x00\x08\x00\x00\x00\x06\x00\x00\x00\x00\x00\x00\x00",
+            b"\x05\x00\x00\x00\x08\x00\x00\x00\x02\x00\x00\x00\x03\x00\x00\x00",
+            b"\x01\x00\x01\x00x\x00\x00\x00",
+            b"\x04\x00\x00\x00\x0c\x00\x00\x00\x01\x00\x04\x00\x02\x00\x05\x00\x03\x00\x2c\x01\x00\x00\x00\x00",
+        ];
+        let contents = contents.concat();
+        file.extend_from_slice(&MI_MATRIX.to_le_bytes());
+        file.extend_from_slice(&(contents.len() as u32).to_le_bytes());
+        file.extend_from_slice(&contents);
+
+        let reader = MatReader::new(file.as_slice()).unwrap();
+        assert_eq!(
+            reader.matrix::<f64>("x").unwrap(),
+            mat![[1.0, 2.0, 3.0], [4.0, 5.0, 300.0]]
+        );
+        assert_eq!(
+            reader.matrix::<i32>("x").unwrap_err().to_string(),
+            "MAT variable 'x' has class Double, expected Int32"
+        );
+    }
+
+    #[test]
+    fn big_endian() {
+        let mut file = header(true);
+        let contents: &[&[u8]] = &[
+            b"\x00\x00\x00\x06\x00\x00\x00\x08\x00\x00\x00\x0c\x00\x00\x00\x00",
+            b"\x00\x00\x00\x05\x00\x00\x00\x08\x00\x00\x00\x01\x00\x00\x00\x02",
+            b"\x00\x01\x00\x01v\x00\x00\x00",
+            b"\x00\x00\x00\x05\x00\x00\x00\x08\xff\xff\xff\xfe\x00\x00\x00\x07",
+        ];
+        let contents = contents.concat();
+        file.extend_from_slice(&MI_MATRIX.to_be_bytes());
+        file.extend_from_slice(&(contents.len() as u32).to_be_bytes());
+        file.extend_from_slice(&contents);
+
+        let reader = MatReader::new(file.as_slice()).unwrap();
+        assert_eq!(reader.vector::<i32>("v").unwrap(), mat![-2, 7]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let a = mat![[1i16, -2], [3, 4], [5, 6]];
+        let z = mat![
+            [Complex::new(1.0, 2.0), Complex::new(3.0, -4.0)],
+            [Complex::new(-5.0, 0.5), Complex::new(0.0, 0.0)]
+        ];
+        let v = mat![1.5f32, 2.5, 3.5];
+
+        let mut writer = MatWriter::new(Vec::new()).unwrap();
+        writer.add_matrix("a", &a).unwrap();
+        writer.add_matrix("z", &z).unwrap();
+        writer.add_vector("v_1", &v).unwrap();
+        let file = writer.finish().unwrap();
+
+        assert_eq!(file.len() % 8, 0);
+        let reader = MatReader::new(file.as_slice()).unwrap();
+
+        assert_eq!(reader.names(), vec!["a", "z", "v_1"]);
+        assert_eq!(reader.class("z"), Some(Class::Double));
+        assert_eq!(reader.matrix::<i16>("a").unwrap(), a);
+        assert_eq!(reader.matrix::<Complex<f64>>("z").unwrap(), z);
+        assert_eq!(reader.matrix::<f32>("v_1").unwrap().dim(), (3, 1).into());
+        assert_eq!(reader.vector::<f32>("v_1").unwrap(), v);
+        assert_eq!(
+            reader.vector::<Complex<i16>>("a").unwrap_err().to_string(),
+            "MAT variable 'a' of size 3x2 is not a vector"
+        );
+        assert_eq!(
+            reader.matrix::<f64>("z2").unwrap_err().to_string(),
+            "MAT file has no variable named 'z2'"
+        );
+        assert_eq!(
+            reader.matrix::<f64>("z").unwrap_err().to_string(),
+            "MAT variable 'z' is complex and cannot be read into a real matrix"
+        );
+
+        let mut writer = MatWriter::new(Vec::new()).unwrap();
+        assert!(writer.add_matrix("2a", &a).is_err());
+    }
+
+    #[test]
+    fn element_size_limit() {
+        // A 30000x30000 matrix of doubles is 7.2 GB, which does not fit the 32-bit length
+        assert_eq!(element_len(u32::MAX as usize).unwrap(), u32::MAX);
+        assert_eq!(
+            element_len(30000 * 30000 * 8).unwrap_err().to_string(),
+            "MAT data element of 7200000000 bytes is larger than the limit of 4294967295 bytes"
+        );
+    }
+}
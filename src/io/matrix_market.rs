@@ -0,0 +1,654 @@
+//! The Matrix Market exchange format (`.mtx`), used by the SuiteSparse matrix collection
+//! among others.
+//!
+//! Both the dense `array` and sparse `coordinate` formats are read into a dense `Matrix`.
+//! See <https://math.nist.gov/MatrixMarket/formats.html> for a description of the format.
+//! ```
+//! # use land::{io::matrix_market, mat, Matrix};
+//! # fn main() {
+//! let file = "\
+//! %%MatrixMarket matrix coordinate real symmetric
+//! % A comment
+//! 3 3 4
+//! 1 1 2.0
+//! 2 1 -1.0
+//! 2 2 2.0
+//! 3 3 1.5
+//! ";
+//!
+//! let a: Matrix<f64> = matrix_market::read(file.as_bytes()).unwrap();
+//!
+//! assert_eq!(a, mat![[2.0, -1.0, 0.0], [-1.0, 2.0, 0.0], [0.0, 0.0, 1.5]]);
+//! # }
+//! ```
+
+use super::{Error, Result};
+use crate::{
+    matrix::{Dimensions, Matrix},
+    traits::Scalar,
+};
+use num::Complex;
+use std::{
+    io::{BufRead, Write},
+    str::SplitWhitespace,
+};
+
+/// How the entries are stored.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// Every entry is listed in column major order.
+    Array,
+    /// Only non-zero entries are listed, together with their row and column.
+    Coordinate,
+}
+
+/// The type of the entries.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Field {
+    Real,
+    Integer,
+    /// A real and imaginary part.
+    Complex,
+    /// Entries have no value; listed entries are one. Only used with `Format::Coordinate`.
+    Pattern,
+}
+
+/// Which entries are stored.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Symmetry {
+    /// Every entry is stored.
+    General,
+    /// Only the lower triangle is stored, and `a[i][j] == a[j][i]`.
+    Symmetric,
+    /// Only the strictly lower triangle is stored, and `a[i][j] == -a[j][i]`.
+    SkewSymmetric,
+    /// Only the lower triangle is stored, and `a[i][j] == conj(a[j][i])`.
+    Hermitian,
+}
+
+/// A type that can be read from and written to a Matrix Market file.
+pub trait Element: Scalar {
+    /// The field used when writing.
+    const FIELD: Field;
+
+    /// Parse the value of an entry from a field.
+    fn parse(field: Field, tokens: &mut SplitWhitespace) -> std::result::Result<Self, String>;
+
+    /// Write the value of an entry.
+    fn write<W: Write>(&self, writer: &mut W) -> std::io::Result<()>;
+
+    /// The complex conjugate, used for hermitian matrices.
+    fn conj(&self) -> Self {
+        self.clone()
+    }
+}
+
+fn next_token<'a>(tokens: &mut SplitWhitespace<'a>) -> std::result::Result<&'a str, String> {
+    tokens
+        .next()
+        .ok_or_else(|| String::from("missing value of entry"))
+}
+
+fn parse_token<T>(tokens: &mut SplitWhitespace) -> std::result::Result<T, String>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    let token = next_token(tokens)?;
+    token
+        .parse()
+        .map_err(|e| format!("invalid value '{}': {}", token, e))
+}
+
+macro_rules! impl_real_element {
+    ($($float:ty),+) => {
+        $(
+            impl Element for $float {
+                const FIELD: Field = Field::Real;
+
+                fn parse(field: Field, tokens: &mut SplitWhitespace) -> std::result::Result<$float, String> {
+                    match field {
+                        Field::Real | Field::Integer => parse_token(tokens),
+                        Field::Pattern => Ok(1.0),
+                        Field::Complex => Err(String::from("complex entries cannot be read into a real matrix")),
+                    }
+                }
+
+                fn write<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+                    write!(writer, "{:e}", self)
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! impl_integer_element {
+    ($($int:ty),+) => {
+        $(
+            impl Element for $int {
+                const FIELD: Field = Field::Integer;
+
+                fn parse(field: Field, tokens: &mut SplitWhitespace) -> std::result::Result<$int, String> {
+                    match field {
+                        Field::Integer => parse_token(tokens),
+                        Field::Pattern => Ok(1),
+                        Field::Real | Field::Complex => Err(format!(
+                            "{} entries cannot be read into an integer matrix",
+                            if field == Field::Real { "real" } else { "complex" }
+                        )),
+                    }
+                }
+
+                fn write<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+                    write!(writer, "{}", self)
+                }
+            }
+        )+
+    };
+}
+
+impl_real_element!(f32, f64);
+impl_integer_element!(i32, i64);
+
+impl<T> Element for Complex<T>
+where
+    T: Element + num::Float,
+{
+    const FIELD: Field = Field::Complex;
+
+    fn parse(field: Field, tokens: &mut SplitWhitespace) -> std::result::Result<Self, String> {
+        match field {
+            Field::Complex => Ok(Complex::new(
+                T::parse(Field::Real, tokens)?,
+                T::parse(Field::Real, tokens)?,
+            )),
+            _ => T::parse(field, tokens).map(Complex::from),
+        }
+    }
+
+    fn write<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.re.write(writer)?;
+        write!(writer, " ")?;
+        self.im.write(writer)
+    }
+
+    fn conj(&self) -> Self {
+        Complex::conj(self)
+    }
+}
+
+/// Lines of a file without comments and blank lines, with their line numbers.
+struct Lines<R> {
+    lines: std::io::Lines<R>,
+    number: usize,
+}
+
+impl<R: BufRead> Lines<R> {
+    /// The next line with content, or `None` at the end of the file.
+    fn next(&mut self) -> Result<Option<(usize, String)>> {
+        for line in &mut self.lines {
+            let line = line?;
+            self.number += 1;
+
+            let trimmed = line.trim();
+            if !trimmed.is_empty() && !trimmed.starts_with('%') {
+                return Ok(Some((self.number, trimmed.to_owned())));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// The next line with content, which must exist.
+    fn expect(&mut self, what: &str) -> Result<(usize, String)> {
+        let number = self.number;
+        self.next()?.ok_or_else(|| {
+            Error::parse(
+                number + 1,
+                format!("unexpected end of file, expected {}", what),
+            )
+        })
+    }
+}
+
+fn parse_header(line: &str) -> std::result::Result<(Format, Field, Symmetry), String> {
+    let words: Vec<String> = line.split_whitespace().map(str::to_lowercase).collect();
+
+    if words.len() != 5 || words[0] != "%%matrixmarket" {
+        return Err(String::from(
+            "expected header '%%MatrixMarket matrix <format> <field> <symmetry>'",
+        ));
+    }
+    if words[1] != "matrix" {
+        return Err(format!("unsupported object '{}'", words[1]));
+    }
+
+    let format = match words[2].as_str() {
+        "array" => Format::Array,
+        "coordinate" => Format::Coordinate,
+        other => return Err(format!("unknown format '{}'", other)),
+    };
+    let field = match words[3].as_str() {
+        "real" | "double" => Field::Real,
+        "integer" => Field::Integer,
+        "complex" => Field::Complex,
+        "pattern" => Field::Pattern,
+        other => return Err(format!("unknown field '{}'", other)),
+    };
+    let symmetry = match words[4].as_str() {
+        "general" => Symmetry::General,
+        "symmetric" => Symmetry::Symmetric,
+        "skew-symmetric" => Symmetry::SkewSymmetric,
+        "hermitian" => Symmetry::Hermitian,
+        other => return Err(format!("unknown symmetry '{}'", other)),
+    };
+
+    if format == Format::Array && field == Field::Pattern {
+        return Err(String::from("pattern field requires coordinate format"));
+    }
+
+    Ok((format, field, symmetry))
+}
+
+fn parse_sizes(line: &str, count: usize) -> std::result::Result<Vec<usize>, String> {
+    let sizes = line
+        .split_whitespace()
+        .map(|word| word.parse().map_err(|_| format!("invalid size '{}'", word)))
+        .collect::<std::result::Result<Vec<usize>, String>>()?;
+
+    if sizes.len() != count {
+        return Err(format!("expected {} sizes, found {}", count, sizes.len()));
+    }
+
+    Ok(sizes)
+}
+
+/// Store an entry and its mirror image according to the symmetry.
+fn insert<S: Element>(
+    matrix: &mut Matrix<S>,
+    row: usize,
+    col: usize,
+    value: S,
+    symmetry: Symmetry,
+) {
+    let mirror = match symmetry {
+        _ if row == col => None,
+        Symmetry::General => None,
+        Symmetry::Symmetric => Some(value.clone()),
+        Symmetry::SkewSymmetric => Some(-value.clone()),
+        Symmetry::Hermitian => Some(value.conj()),
+    };
+
+    if let Some(mirror) = mirror {
+        matrix[col][row] = mirror;
+    }
+    matrix[row][col] = value;
+}
+
+/// Read a matrix in the Matrix Market format.
+///
+/// Symmetric, skew-symmetric and hermitian matrices are expanded to the full matrix and
+/// entries in a pattern matrix are one. Real values cannot be read into integer matrices
+/// and complex values can only be read into complex matrices.
+pub fn read<S, R>(reader: R) -> Result<Matrix<S>>
+where
+    S: Element,
+    R: BufRead,
+{
+    let mut lines = reader.lines();
+    let header = match lines.next() {
+        Some(line) => line?,
+        None => return Err(Error::parse(1, "empty file")),
+    };
+    let (format, field, symmetry) = parse_header(&header).map_err(|e| Error::parse(1, e))?;
+
+    let mut lines = Lines { lines, number: 1 };
+
+    let (number, line) = lines.expect("matrix size")?;
+    let sizes = match format {
+        Format::Array => parse_sizes(&line, 2),
+        Format::Coordinate => parse_sizes(&line, 3),
+    }
+    .map_err(|e| Error::parse(number, e))?;
+
+    let dimensions = Dimensions {
+        rows: sizes[0],
+        cols: sizes[1],
+    };
+    let len = match dimensions.rows.checked_mul(dimensions.cols) {
+        Some(len) => len,
+        None => {
+            return Err(Error::parse(
+                number,
+                format!("matrix size {} is too large", dimensions),
+            ))
+        }
+    };
+    if symmetry != Symmetry::General && dimensions.rows != dimensions.cols {
+        return Err(Error::parse(
+            number,
+            format!("matrix with symmetry must be square, found {}", dimensions),
+        ));
+    }
+    let size_line = number;
+
+    // The entries are read before the matrix is allocated, so that a header with a huge
+    // size fails on the missing entries instead of on the allocation
+    let mut entries = Vec::new();
+
+    match format {
+        Format::Array => {
+            for col in 0..dimensions.cols {
+                let first_row = match symmetry {
+                    Symmetry::General => 0,
+                    Symmetry::Symmetric | Symmetry::Hermitian => col,
+                    Symmetry::SkewSymmetric => col + 1,
+                };
+
+                for row in first_row..dimensions.rows {
+                    let (number, line) = lines.expect("matrix entry")?;
+                    let value = S::parse(field, &mut line.split_whitespace())
+                        .map_err(|e| Error::parse(number, e))?;
+                    entries.push((row, col, value));
+                }
+            }
+        }
+        Format::Coordinate => {
+            for _ in 0..sizes[2] {
+                let (number, line) = lines.expect("matrix entry")?;
+                let mut tokens = line.split_whitespace();
+
+                let mut index = |size: usize| {
+                    let token = next_token(&mut tokens)?;
+                    match token.parse::<usize>() {
+                        Ok(i) if i >= 1 && i <= size => Ok(i - 1),
+                        _ => Err(format!("index '{}' out of range 1..={}", token, size)),
+                    }
+                };
+                let row = index(dimensions.rows).map_err(|e| Error::parse(number, e))?;
+                let col = index(dimensions.cols).map_err(|e| Error::parse(number, e))?;
+
+                if symmetry != Symmetry::General && row < col {
+                    return Err(Error::parse(
+                        number,
+                        "entries above the diagonal are not allowed with symmetry",
+                    ));
+                }
+
+                let value = S::parse(field, &mut tokens).map_err(|e| Error::parse(number, e))?;
+                entries.push((row, col, value));
+            }
+        }
+    }
+
+    if let Some((number, _)) = lines.next()? {
+        return Err(Error::parse(number, "more entries than specified"));
+    }
+
+    let mut elements = Vec::new();
+    if elements.try_reserve_exact(len).is_err() {
+        return Err(Error::parse(
+            size_line,
+            format!("matrix of size {} does not fit in memory", dimensions),
+        ));
+    }
+    elements.resize(len, S::zero());
+
+    let mut matrix = Matrix::from_row_major(dimensions, elements);
+    for (row, col, value) in entries {
+        insert(&mut matrix, row, col, value, symmetry);
+    }
+
+    Ok(matrix)
+}
+
+/// Write a matrix in the `array` format with `general` symmetry.
+pub fn write<S, W>(writer: W, matrix: &Matrix<S>) -> Result<()>
+where
+    S: Element,
+    W: Write,
+{
+    write_with(writer, matrix, Format::Array, Symmetry::General)
+}
+
+/// Write a matrix in the given format, storing only the entries required by the symmetry.
+///
+/// The `coordinate` format only stores non-zero entries.
+///
+/// Panics if the matrix does not have the given symmetry.
+pub fn write_with<S, W>(
+    mut writer: W,
+    matrix: &Matrix<S>,
+    format: Format,
+    symmetry: Symmetry,
+) -> Result<()>
+where
+    S: Element,
+    W: Write,
+{
+    let dimensions = matrix.dim();
+
+    if symmetry != Symmetry::General {
+        assert!(
+            dimensions.rows == dimensions.cols,
+            "Matrix must be square. Matrix is of size {}",
+            dimensions
+        );
+        for row in 0..dimensions.rows {
+            for col in 0..=row {
+                let mirror = &matrix[col][row];
+                let expected = match symmetry {
+                    Symmetry::General => unreachable!(),
+                    Symmetry::Symmetric => mirror.clone(),
+                    Symmetry::SkewSymmetric => -mirror.clone(),
+                    Symmetry::Hermitian => mirror.conj(),
+                };
+                assert!(
+                    matrix[row][col] == expected,
+                    "Matrix is not {:?}: elements at ({}, {}) and ({}, {}) differ",
+                    symmetry,
+                    row,
+                    col,
+                    col,
+                    row
+                );
+            }
+        }
+    }
+
+    let first_row = |col: usize| match symmetry {
+        Symmetry::General => 0,
+        Symmetry::Symmetric | Symmetry::Hermitian => col,
+        Symmetry::SkewSymmetric => col + 1,
+    };
+    let stored = || {
+        (0..dimensions.cols)
+            .flat_map(move |col| (first_row(col)..dimensions.rows).map(move |row| (row, col)))
+    };
+
+    let format_name = match format {
+        Format::Array => "array",
+        Format::Coordinate => "coordinate",
+    };
+    let field_name = match S::FIELD {
+        Field::Real => "real",
+        Field::Integer => "integer",
+        Field::Complex => "complex",
+        Field::Pattern => "pattern",
+    };
+    let symmetry_name = match symmetry {
+        Symmetry::General => "general",
+        Symmetry::Symmetric => "symmetric",
+        Symmetry::SkewSymmetric => "skew-symmetric",
+        Symmetry::Hermitian => "hermitian",
+    };
+    writeln!(
+        writer,
+        "%%MatrixMarket matrix {} {} {}",
+        format_name, field_name, symmetry_name
+    )?;
+
+    match format {
+        Format::Array => {
+            writeln!(writer, "{} {}", dimensions.rows, dimensions.cols)?;
+            for (row, col) in stored() {
+                matrix[row][col].write(&mut writer)?;
+                writeln!(writer)?;
+            }
+        }
+        Format::Coordinate => {
+            let nonzero = stored()
+                .filter(|&(row, col)| !matrix[row][col].is_zero())
+                .count();
+            writeln!(
+                writer,
+                "{} {} {}",
+                dimensions.rows, dimensions.cols, nonzero
+            )?;
+
+            for (row, col) in stored().filter(|&(row, col)| !matrix[row][col].is_zero()) {
+                write!(writer, "{} {} ", row + 1, col + 1)?;
+                matrix[row][col].write(&mut writer)?;
+                writeln!(writer)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse<S: Element>(file: &str) -> Result<Matrix<S>> {
+        read(file.as_bytes())
+    }
+
+    fn to_string<S: Element>(matrix: &Matrix<S>, format: Format, symmetry: Symmetry) -> String {
+        let mut out = Vec::new();
+        write_with(&mut out, matrix, format, symmetry).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn array_is_column_major() {
+        let file = "%%MatrixMarket matrix array integer general\n2 3\n1\n4\n2\n5\n3\n6\n";
+
+        assert_eq!(parse::<i32>(file).unwrap(), mat![[1, 2, 3], [4, 5, 6]]);
+    }
+
+    #[test]
+    fn skew_symmetric_array() {
+        let file = "%%MatrixMarket matrix array real skew-symmetric\n3 3\n1.0\n2.0\n3.0\n";
+
+        assert_eq!(
+            parse::<f64>(file).unwrap(),
+            mat![[0.0, -1.0, -2.0], [1.0, 0.0, -3.0], [2.0, 3.0, 0.0]]
+        );
+    }
+
+    #[test]
+    fn pattern_and_complex() {
+        let pattern = "%%MatrixMarket matrix coordinate pattern general\n2 2 2\n1 2\n2 1\n";
+        assert_eq!(parse::<f64>(pattern).unwrap(), mat![[0.0, 1.0], [1.0, 0.0]]);
+
+        let hermitian = "%%MatrixMarket matrix coordinate complex hermitian\n\
+                         2 2 2\n1 1 1.0 0.0\n2 1 2.0 -1.5\n";
+        let i = |re, im| Complex::new(re, im);
+        assert_eq!(
+            parse::<Complex<f64>>(hermitian).unwrap(),
+            mat![[i(1.0, 0.0), i(2.0, 1.5)], [i(2.0, -1.5), i(0.0, 0.0)]]
+        );
+    }
+
+    #[test]
+    fn errors_have_line_numbers() {
+        let error = |file: &str| match parse::<i64>(file) {
+            Err(Error::Parse { line, message }) => (line, message),
+            other => panic!("expected parse error, got {:?}", other),
+        };
+
+        assert_eq!(
+            error("%%MatrixMarket matrix coordinate real general\n1 1 1\n1 1 1.5\n"),
+            (
+                3,
+                String::from("real entries cannot be read into an integer matrix")
+            )
+        );
+        assert_eq!(
+            error("%%MatrixMarket matrix coordinate integer general\n% c\n2 2 1\n3 1 4\n"),
+            (4, String::from("index '3' out of range 1..=2"))
+        );
+        assert_eq!(
+            error("%%MatrixMarket matrix array integer general\n2 1\n1\n"),
+            (
+                4,
+                String::from("unexpected end of file, expected matrix entry")
+            )
+        );
+        assert_eq!(error("%%MatrixMarket vector array real general\n").0, 1);
+    }
+
+    #[test]
+    fn huge_sizes() {
+        let error = |file: &str| match parse::<f64>(file) {
+            Err(Error::Parse { line, message }) => (line, message),
+            other => panic!("expected parse error, got {:?}", other),
+        };
+
+        assert_eq!(
+            error("%%MatrixMarket matrix coordinate real general\n4294967296 4294967296 1\n"),
+            (
+                2,
+                String::from("matrix size 4294967296x4294967296 is too large")
+            )
+        );
+
+        // The missing entry is found before the matrix would be allocated
+        assert_eq!(
+            error(
+                "%%MatrixMarket matrix coordinate real general\n100000000 100000000 2\n1 1 1.0\n"
+            ),
+            (
+                4,
+                String::from("unexpected end of file, expected matrix entry")
+            )
+        );
+        assert_eq!(
+            error(
+                "%%MatrixMarket matrix coordinate real general\n100000000 100000000 1\n1 1 1.0\n"
+            ),
+            (
+                2,
+                String::from("matrix of size 100000000x100000000 does not fit in memory")
+            )
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let a = mat![[4.0, -1.0, 0.0], [-1.0, 4.0, 0.1], [0.0, 0.1, 1e-300]];
+
+        for &format in &[Format::Array, Format::Coordinate] {
+            for &symmetry in &[Symmetry::General, Symmetry::Symmetric] {
+                let text = to_string(&a, format, symmetry);
+                assert_eq!(parse::<f64>(&text).unwrap(), a);
+            }
+        }
+
+        let skew = mat![[0, 2], [-2, 0]];
+        let text = to_string(&skew, Format::Coordinate, Symmetry::SkewSymmetric);
+        assert_eq!(
+            text,
+            "%%MatrixMarket matrix coordinate integer skew-symmetric\n2 2 1\n2 1 -2\n"
+        );
+        assert_eq!(parse::<i32>(&text).unwrap(), skew);
+    }
+
+    #[test]
+    #[should_panic]
+    fn write_checks_symmetry() {
+        to_string(&mat![[1, 2], [3, 4]], Format::Array, Symmetry::Symmetric);
+    }
+}
@@ -0,0 +1,449 @@
+//! Memory-mapped matrices for data larger than RAM.
+//!
+//! A raw matrix file is a 64 byte header followed by the elements in little endian, row
+//! major order, the same layout `Matrix` uses in memory. The header holds the magic string
+//! `LANDRAW\x01`, the NumPy style dtype (such as `<f8`) padded with zeros to 8 bytes, and
+//! the number of rows and columns as little endian `u64`.
+//! ```
+//! # use land::{io::mmap::MappedMatrix, mat, Matrix};
+//! # fn main() {
+//! # let path = std::env::temp_dir().join(format!("land-doc-{}.raw", std::process::id()));
+//! let mut a = MappedMatrix::<f64, _>::create(&path, (3, 2).into()).unwrap();
+//! a.row_mut(0).copy_from_slice(&[1.0, 2.0]);
+//! a.row_mut(2).copy_from_slice(&[5.0, 6.0]);
+//! a.flush().unwrap();
+//!
+//! let b = MappedMatrix::<f64>::open(&path).unwrap();
+//! assert_eq!(b.row(2), [5.0, 6.0]);
+//! assert_eq!(b.mul_vector(&mat![1.0, 1.0]), mat![3.0, 0.0, 11.0]);
+//! assert_eq!(b.to_matrix(), mat![[1.0, 2.0], [0.0, 0.0], [5.0, 6.0]]);
+//! # std::fs::remove_file(&path).unwrap();
+//! # }
+//! ```
+
+use super::{native::BLOCK, npy, Error, Result};
+use crate::{
+    matrix::{Dimensions, Matrix},
+    vector::{operations::dot, Vector},
+};
+use memmap2::{Mmap, MmapMut};
+use std::{
+    convert::TryInto,
+    fs::{File, OpenOptions},
+    io::Write,
+    marker::PhantomData,
+    ops::Deref,
+    path::Path,
+};
+
+const MAGIC: &[u8] = b"LANDRAW\x01";
+const HEADER_LEN: usize = 64;
+
+/// The number of rows and columns in the tiles of blocked products.
+const TILE: usize = 1024;
+
+/// A type that can be viewed directly in a memory-mapped file.
+///
+/// # Safety
+/// Every bit pattern of `SIZE` bytes must be a valid value, and the in-memory
+/// representation on a little endian host must match `DESCR`.
+pub unsafe trait Element: npy::Element {}
+
+unsafe impl Element for f32 {}
+unsafe impl Element for f64 {}
+unsafe impl Element for i32 {}
+unsafe impl Element for i64 {}
+
+fn header<S: Element>(dimensions: Dimensions) -> [u8; HEADER_LEN] {
+    let mut header = [0; HEADER_LEN];
+    header[..8].copy_from_slice(MAGIC);
+    header[8..8 + S::DESCR.len()].copy_from_slice(S::DESCR.as_bytes());
+    header[16..24].copy_from_slice(&(dimensions.rows as u64).to_le_bytes());
+    header[24..32].copy_from_slice(&(dimensions.cols as u64).to_le_bytes());
+    header
+}
+
+/// The length in bytes of a file holding a matrix of this size, or `None` on overflow.
+fn file_len<S: Element>(dimensions: Dimensions) -> Option<usize> {
+    dimensions
+        .rows
+        .checked_mul(dimensions.cols)
+        .and_then(|elements| elements.checked_mul(S::SIZE))
+        .and_then(|bytes| bytes.checked_add(HEADER_LEN))
+}
+
+fn parse_header<S: Element>(bytes: &[u8]) -> Result<Dimensions> {
+    if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+        return Err(Error::Invalid("not a raw matrix file".into()));
+    }
+
+    let descr = String::from_utf8_lossy(&bytes[8..16]);
+    let descr = descr.trim_end_matches('\0');
+    if descr != S::DESCR {
+        return Err(Error::Invalid(format!(
+            "raw matrix file has dtype '{}', expected '{}'",
+            descr,
+            S::DESCR
+        )));
+    }
+
+    let size = |range: std::ops::Range<usize>| {
+        let size = u64::from_le_bytes(bytes[range].try_into().unwrap());
+        size.try_into()
+            .map_err(|_| Error::Invalid(format!("raw matrix size {} is too large", size)))
+    };
+    let dimensions = Dimensions {
+        rows: size(16..24)?,
+        cols: size(24..32)?,
+    };
+
+    if file_len::<S>(dimensions) != Some(bytes.len()) {
+        return Err(Error::Invalid(format!(
+            "raw matrix file of size {} has {} bytes",
+            dimensions,
+            bytes.len()
+        )));
+    }
+
+    Ok(dimensions)
+}
+
+fn check_endian() -> Result<()> {
+    if cfg!(target_endian = "little") {
+        Ok(())
+    } else {
+        Err(Error::Invalid(
+            "memory-mapped matrices require a little endian host".into(),
+        ))
+    }
+}
+
+/// A matrix stored in a memory-mapped raw matrix file.
+///
+/// Mapped with `open` it is read-only, with `open_mut` or `create` it can also be written.
+/// The file must not be modified by other processes while it is mapped.
+pub struct MappedMatrix<S, M = Mmap> {
+    map: M,
+    dimensions: Dimensions,
+    element: PhantomData<S>,
+}
+
+impl<S> MappedMatrix<S>
+where
+    S: Element,
+{
+    /// Map an existing file read-only.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<MappedMatrix<S>> {
+        check_endian()?;
+        let file = File::open(path)?;
+        // Safety: the file is required not to change while mapped
+        let map = unsafe { Mmap::map(&file)? };
+        MappedMatrix::new(map)
+    }
+}
+
+impl<S> MappedMatrix<S, MmapMut>
+where
+    S: Element,
+{
+    /// Map an existing file for reading and writing.
+    pub fn open_mut<P: AsRef<Path>>(path: P) -> Result<MappedMatrix<S, MmapMut>> {
+        check_endian()?;
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        // Safety: the file is required not to change while mapped
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        MappedMatrix::new(map)
+    }
+
+    /// Create a file filled with zeros, replacing any existing file, and map it for reading
+    /// and writing.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        dimensions: Dimensions,
+    ) -> Result<MappedMatrix<S, MmapMut>> {
+        check_endian()?;
+        let len = file_len::<S>(dimensions).ok_or_else(|| {
+            Error::Invalid(format!("raw matrix size {} is too large", dimensions))
+        })?;
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.write_all(&header::<S>(dimensions))?;
+        file.set_len(len as u64)?;
+
+        // Safety: the file is required not to change while mapped
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        MappedMatrix::new(map)
+    }
+
+    /// Get a row of the matrix mutably
+    pub fn row_mut(&mut self, row: usize) -> &mut [S] {
+        let row_start = self.dimensions.row_major(row, 0);
+        let row_len = self.dimensions.cols;
+
+        &mut self.elements_mut()[row_start..row_start + row_len]
+    }
+
+    /// Write changes back to the file.
+    pub fn flush(&self) -> Result<()> {
+        self.map.flush()?;
+        Ok(())
+    }
+
+    fn elements_mut(&mut self) -> &mut [S] {
+        let len = self.dimensions.elements();
+        let data = &mut self.map[HEADER_LEN..];
+        // Safety: the length and alignment were checked in `new`, and `Element` guarantees
+        // that any bytes are a valid element
+        unsafe { std::slice::from_raw_parts_mut(data.as_mut_ptr() as *mut S, len) }
+    }
+}
+
+impl<S, M> MappedMatrix<S, M>
+where
+    S: Element,
+    M: Deref<Target = [u8]>,
+{
+    fn new(map: M) -> Result<MappedMatrix<S, M>> {
+        let dimensions = parse_header::<S>(&map)?;
+
+        if map[HEADER_LEN..]
+            .as_ptr()
+            .align_offset(std::mem::align_of::<S>())
+            != 0
+        {
+            return Err(Error::Invalid(
+                "raw matrix data is not aligned in memory".into(),
+            ));
+        }
+
+        Ok(MappedMatrix {
+            map,
+            dimensions,
+            element: PhantomData,
+        })
+    }
+
+    /// Get the dimensions of a matrix
+    pub fn dim(&self) -> Dimensions {
+        self.dimensions
+    }
+
+    fn elements(&self) -> &[S] {
+        let len = self.dimensions.elements();
+        let data = &self.map[HEADER_LEN..];
+        // Safety: the length and alignment were checked in `new`, and `Element` guarantees
+        // that any bytes are a valid element
+        unsafe { std::slice::from_raw_parts(data.as_ptr() as *const S, len) }
+    }
+
+    /// Get a row of the matrix
+    pub fn row(&self, row: usize) -> &[S] {
+        let row_start = self.dimensions.row_major(row, 0);
+        let row_len = self.dimensions.cols;
+
+        &self.elements()[row_start..row_start + row_len]
+    }
+
+    /// Copy the matrix into memory.
+    pub fn to_matrix(&self) -> Matrix<S> {
+        Matrix::from_row_major(self.dimensions, self.elements().to_vec())
+    }
+
+    /// Multiply the matrix by a vector, streaming through the file in tiles.
+    pub fn mul_vector(&self, rhs: &Vector<S>) -> Vector<S> {
+        self.mul_vector_blocked(rhs, Dimensions::square(TILE))
+    }
+
+    /// Multiply the matrix by a vector one tile at a time, so that only a tile of the
+    /// matrix and the matching part of the vector need to be in memory at once.
+    pub fn mul_vector_blocked(&self, rhs: &Vector<S>, tile: Dimensions) -> Vector<S> {
+        let Dimensions { rows, cols } = self.dimensions;
+        assert!(
+            cols == rhs.len(),
+            "Matrix dimensions must agree. Left hand side is {} and right hand side has length {}",
+            self.dimensions,
+            rhs.len(),
+        );
+        assert!(
+            tile.rows > 0 && tile.cols > 0,
+            "Tiles must not be empty. Tile size was {}",
+            tile
+        );
+
+        let mut out = vec![S::zero(); rows];
+
+        for row_start in (0..rows).step_by(tile.rows) {
+            let row_end = (row_start + tile.rows).min(rows);
+
+            for col_start in (0..cols).step_by(tile.cols) {
+                let col_end = (col_start + tile.cols).min(cols);
+                let rhs = &rhs.deref()[col_start..col_end];
+
+                for (row, out) in out[row_start..row_end].iter_mut().enumerate() {
+                    *out += dot(&self.row(row_start + row)[col_start..col_end], rhs);
+                }
+            }
+        }
+
+        out.into()
+    }
+
+    /// Multiply the transpose of the matrix by a vector, reading the file once from start
+    /// to end.
+    pub fn transpose_mul_vector(&self, rhs: &Vector<S>) -> Vector<S> {
+        let Dimensions { rows, cols } = self.dimensions;
+        assert!(
+            rows == rhs.len(),
+            "Matrix dimensions must agree. Left hand side is {} and right hand side has length {}",
+            self.dimensions.transpose(),
+            rhs.len(),
+        );
+
+        let mut out = vec![S::zero(); cols];
+
+        for (row, factor) in rhs.iter().enumerate() {
+            for (out, element) in out.iter_mut().zip(self.row(row)) {
+                *out += *element * *factor;
+            }
+        }
+
+        out.into()
+    }
+}
+
+impl<S> Matrix<S>
+where
+    S: Element,
+{
+    /// Write the matrix as a raw matrix file, which can then be memory-mapped.
+    pub fn write_raw<W: Write>(&self, mut writer: W) -> Result<()> {
+        writer.write_all(&header::<S>(self.dim()))?;
+
+        let mut elements = self.iter_rows();
+        let mut bytes = Vec::new();
+        let mut remaining = self.dim().elements();
+
+        while remaining > 0 {
+            let block = BLOCK.min(remaining);
+            remaining -= block;
+
+            bytes.clear();
+            for element in elements.by_ref().take(block) {
+                element.extend_le_bytes(&mut bytes);
+            }
+            writer.write_all(&bytes)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("land-{}-{}.raw", name, std::process::id()))
+    }
+
+    #[test]
+    fn blocked_products() {
+        let elements = (0..7 * 5).map(|i| (i % 11) as i64 - 5).collect();
+        let a = Matrix::from_row_major((7, 5).into(), elements);
+        let path = temp_path("blocked");
+
+        let mut file = Vec::new();
+        a.write_raw(&mut file).unwrap();
+        std::fs::write(&path, file).unwrap();
+        let mapped = MappedMatrix::<i64>::open(&path).unwrap();
+
+        let x = mat![1, -2, 3, 0, 5];
+        let y = mat![1, 2, 3, 4, 5, 6, 7];
+        assert_eq!(mapped.mul_vector_blocked(&x, (3, 2).into()), &a * &x);
+        assert_eq!(mapped.mul_vector(&x), &a * &x);
+        assert_eq!(mapped.transpose_mul_vector(&y), &a.transpose() * &y);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn write_in_blocks() {
+        let elements = (0..3 * BLOCK + 3).map(|i| i as i32).collect();
+        let a = Matrix::from_row_major((3, BLOCK + 1).into(), elements);
+        let path = temp_path("blocks");
+
+        let mut file = Vec::new();
+        a.write_raw(&mut file).unwrap();
+        assert_eq!(file.len(), HEADER_LEN + a.dim().elements() * 4);
+        std::fs::write(&path, file).unwrap();
+
+        let mapped = MappedMatrix::<i32>::open(&path).unwrap();
+        assert_eq!(mapped.to_matrix(), a);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn write_through_mapping() {
+        let path = temp_path("write");
+
+        let mut a = MappedMatrix::<f32, _>::create(&path, (2, 3).into()).unwrap();
+        a.row_mut(1)[2] = 4.5;
+        a.flush().unwrap();
+        drop(a);
+
+        let mut a = MappedMatrix::<f32, _>::open_mut(&path).unwrap();
+        a.row_mut(0)[0] = -1.0;
+        drop(a);
+
+        let a = MappedMatrix::<f32>::open(&path).unwrap();
+        assert_eq!(a.to_matrix(), mat![[-1.0, 0.0, 0.0], [0.0, 0.0, 4.5]]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn invalid_files() {
+        let path = temp_path("invalid");
+        let error = |bytes: &[u8]| {
+            std::fs::write(&path, bytes).unwrap();
+            MappedMatrix::<f64>::open(&path).err().unwrap().to_string()
+        };
+
+        let mut file = Vec::new();
+        mat![[1.0f32, 2.0]].write_raw(&mut file).unwrap();
+
+        assert_eq!(
+            error(&file),
+            "raw matrix file has dtype '<f4', expected '<f8'"
+        );
+        assert_eq!(error(b"P5\n1 1\n255\n\x00"), "not a raw matrix file");
+
+        let mut file = Vec::new();
+        mat![[1.0f64, 2.0]].write_raw(&mut file).unwrap();
+        assert_eq!(
+            error(&file[..70]),
+            "raw matrix file of size 1x2 has 70 bytes"
+        );
+
+        let huge = Dimensions {
+            rows: usize::MAX / 2,
+            cols: 3,
+        };
+        assert_eq!(
+            MappedMatrix::<f64, MmapMut>::create(&path, huge)
+                .err()
+                .unwrap()
+                .to_string(),
+            format!("raw matrix size {} is too large", huge)
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,331 @@
+//! The native `land` binary format, for fast and verified checkpoints.
+//!
+//! A file starts with a 48 byte header:
+//!
+//! | Bytes  | Contents                                                   |
+//! |--------|------------------------------------------------------------|
+//! | 0..8   | The magic string `LANDMAT\0`                               |
+//! | 8..12  | The format version, currently 1                            |
+//! | 12..16 | The element type as a zero padded NumPy dtype, like `<f8`  |
+//! | 16..32 | The number of rows and columns                             |
+//! | 32..40 | The number of elements in each chunk                       |
+//! | 40..44 | The CRC32 of the preceding header bytes                    |
+//! | 44..48 | Zero padding                                               |
+//!
+//! The elements follow in row major order, starting at an 8 byte aligned offset so that the
+//! data can be mapped in place. They are checksummed in chunks, the last of which may be
+//! shorter, and a trailer after the data holds the CRC32 of each chunk in order. All
+//! integers are little endian.
+//! ```
+//! # use land::{mat, Matrix};
+//! # fn main() {
+//! let a = mat![[1.0, 2.0], [3.0, 4.0]];
+//!
+//! let mut file = Vec::new();
+//! a.write_native(&mut file, None).unwrap();
+//! assert_eq!(Matrix::<f64>::read_native(file.as_slice()).unwrap(), a);
+//!
+//! file[50] ^= 1;
+//! assert_eq!(
+//!     Matrix::<f64>::read_native(file.as_slice()).unwrap_err().to_string(),
+//!     "checksum mismatch in chunk 0, the file is corrupted"
+//! );
+//! # }
+//! ```
+
+use super::{npy::Element, Error, Result};
+use crate::matrix::{Dimensions, Matrix};
+use crc32fast::Hasher;
+use std::{
+    convert::TryInto,
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+const MAGIC: &[u8] = b"LANDMAT\0";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 48;
+
+/// The number of elements encoded or decoded at a time, which bounds the memory used in
+/// addition to the matrix itself.
+pub(super) const BLOCK: usize = 1 << 16;
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut hasher = Hasher::new();
+    hasher.update(bytes);
+    hasher.finalize()
+}
+
+fn write_header<S: Element, W: Write>(
+    writer: &mut W,
+    dimensions: Dimensions,
+    chunk_len: usize,
+) -> Result<()> {
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&VERSION.to_le_bytes());
+
+    let mut descr = [0; 4];
+    descr[..S::DESCR.len()].copy_from_slice(S::DESCR.as_bytes());
+    header.extend_from_slice(&descr);
+
+    for size in [dimensions.rows, dimensions.cols, chunk_len] {
+        header.extend_from_slice(&(size as u64).to_le_bytes());
+    }
+    header.extend_from_slice(&crc32(&header).to_le_bytes());
+    header.resize(HEADER_LEN, 0);
+
+    writer.write_all(&header)?;
+    Ok(())
+}
+
+/// Read the header, returning the dimensions and chunk length.
+fn read_header<S: Element, R: Read>(reader: &mut R) -> Result<(Dimensions, usize)> {
+    let mut header = [0; HEADER_LEN];
+    reader.read_exact(&mut header)?;
+
+    if &header[..8] != MAGIC {
+        return Err(Error::Invalid("not a land matrix file".into()));
+    }
+
+    let word = |start: usize| u32::from_le_bytes(header[start..start + 4].try_into().unwrap());
+    if crc32(&header[..40]) != word(40) {
+        return Err(Error::Invalid(
+            "checksum mismatch in header, the file is corrupted".into(),
+        ));
+    }
+
+    let version = word(8);
+    if version != VERSION {
+        return Err(Error::Invalid(format!(
+            "unsupported land matrix format version {}",
+            version
+        )));
+    }
+
+    let descr = String::from_utf8_lossy(&header[12..16]);
+    let descr = descr.trim_end_matches('\0');
+    if descr != S::DESCR {
+        return Err(Error::Invalid(format!(
+            "land matrix file has element type '{}', expected '{}'",
+            descr,
+            S::DESCR
+        )));
+    }
+
+    let size = |start: usize| {
+        let size = u64::from_le_bytes(header[start..start + 8].try_into().unwrap());
+        size.try_into()
+            .map_err(|_| Error::Invalid(format!("land matrix size {} is too large", size)))
+    };
+    let dimensions = Dimensions {
+        rows: size(16)?,
+        cols: size(24)?,
+    };
+    let chunk_len = size(32)?;
+
+    if dimensions.rows.checked_mul(dimensions.cols).is_none() {
+        return Err(Error::Invalid(format!(
+            "land matrix size {} is too large",
+            dimensions
+        )));
+    }
+    if chunk_len == 0 && dimensions.elements() > 0 {
+        return Err(Error::Invalid("land matrix file has empty chunks".into()));
+    }
+
+    Ok((dimensions, chunk_len))
+}
+
+impl<S> Matrix<S>
+where
+    S: Element,
+{
+    /// Save the matrix to a file in the native format, as a single chunk.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_native(&mut writer, None)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load a matrix saved in the native format, verifying every checksum.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Matrix<S>> {
+        Matrix::read_native(BufReader::new(File::open(path)?))
+    }
+
+    /// Write the matrix in the native format.
+    ///
+    /// With `chunk_len`, the elements are split into chunks of that many elements, each with
+    /// its own checksum, so that corruption can be located. Otherwise they form one chunk.
+    pub fn write_native<W: Write>(&self, mut writer: W, chunk_len: Option<usize>) -> Result<()> {
+        let len = self.dim().elements();
+        let chunk_len = chunk_len.unwrap_or(len);
+        assert!(chunk_len > 0 || len == 0, "Chunks must not be empty");
+
+        write_header::<S, _>(&mut writer, self.dim(), chunk_len)?;
+
+        let mut elements = self.iter_rows();
+        let mut bytes = Vec::new();
+        let mut checksums = Vec::new();
+        let mut remaining = len;
+
+        while remaining > 0 {
+            let mut chunk_remaining = chunk_len.min(remaining);
+            remaining -= chunk_remaining;
+            let mut hasher = Hasher::new();
+
+            while chunk_remaining > 0 {
+                let block = BLOCK.min(chunk_remaining);
+                chunk_remaining -= block;
+
+                bytes.clear();
+                for element in elements.by_ref().take(block) {
+                    element.extend_le_bytes(&mut bytes);
+                }
+                hasher.update(&bytes);
+                writer.write_all(&bytes)?;
+            }
+
+            checksums.extend_from_slice(&hasher.finalize().to_le_bytes());
+        }
+
+        writer.write_all(&checksums)?;
+        Ok(())
+    }
+
+    /// Read a matrix in the native format, verifying every checksum.
+    pub fn read_native<R: Read>(mut reader: R) -> Result<Matrix<S>> {
+        let (dimensions, chunk_len) = read_header::<S, _>(&mut reader)?;
+        let len = dimensions.elements();
+
+        // Don't trust the header with a huge allocation before the data has been read
+        let mut elements = Vec::with_capacity(len.min(BLOCK * 256));
+        let mut bytes = Vec::new();
+        let mut checksums = Vec::new();
+        let mut chunk_start = 0;
+
+        while chunk_start < len {
+            let chunk_end = chunk_start + chunk_len.min(len - chunk_start);
+            let mut hasher = Hasher::new();
+
+            let mut block_start = chunk_start;
+            while block_start < chunk_end {
+                let block_end = (block_start + BLOCK).min(chunk_end);
+                bytes.resize((block_end - block_start) * S::SIZE, 0);
+                reader.read_exact(&mut bytes)?;
+
+                hasher.update(&bytes);
+                elements.extend(bytes.chunks_exact(S::SIZE).map(S::from_le_bytes));
+                block_start = block_end;
+            }
+
+            checksums.push(hasher.finalize());
+            chunk_start = chunk_end;
+        }
+
+        for (chunk, &checksum) in checksums.iter().enumerate() {
+            let mut expected = [0; 4];
+            reader.read_exact(&mut expected)?;
+            if checksum != u32::from_le_bytes(expected) {
+                return Err(Error::Invalid(format!(
+                    "checksum mismatch in chunk {}, the file is corrupted",
+                    chunk
+                )));
+            }
+        }
+
+        Ok(Matrix::from_row_major(dimensions, elements))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(file: &[u8]) -> String {
+        Matrix::<i32>::read_native(file).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn chunked_round_trip() {
+        let elements = (0..7 * 11).map(|i| i * i - 300).collect();
+        let a = Matrix::<i32>::from_row_major((7, 11).into(), elements);
+
+        let mut file = Vec::new();
+        a.write_native(&mut file, Some(10)).unwrap();
+
+        // 8 chunks of up to 10 elements, with their checksums in a trailer
+        assert_eq!(file.len(), HEADER_LEN + 77 * 4 + 8 * 4);
+        assert_eq!(Matrix::<i32>::read_native(file.as_slice()).unwrap(), a);
+
+        // The data starts aligned and is stored contiguously, so it can be mapped in place
+        assert_eq!(HEADER_LEN % 8, 0);
+        let data: Vec<i32> = file[HEADER_LEN..HEADER_LEN + 77 * 4]
+            .chunks_exact(4)
+            .map(<i32 as Element>::from_le_bytes)
+            .collect();
+        assert_eq!(data, a.iter_rows().cloned().collect::<Vec<_>>());
+
+        let mut corrupted = file.clone();
+        corrupted[HEADER_LEN + 3 * 40 + 1] ^= 0x10;
+        assert_eq!(
+            error(&corrupted),
+            "checksum mismatch in chunk 3, the file is corrupted"
+        );
+
+        file[HEADER_LEN + 77 * 4 + 7 * 4] ^= 0x01;
+        assert_eq!(
+            error(&file),
+            "checksum mismatch in chunk 7, the file is corrupted"
+        );
+
+        let empty = Matrix::<i32>::zeros((0, 3).into());
+        let mut file = Vec::new();
+        empty.write_native(&mut file, None).unwrap();
+        assert_eq!(file.len(), HEADER_LEN);
+        assert_eq!(Matrix::<i32>::read_native(file.as_slice()).unwrap(), empty);
+    }
+
+    #[test]
+    fn invalid_files() {
+        let mut file = Vec::new();
+        mat![[1.0f64, 2.0]].write_native(&mut file, None).unwrap();
+
+        assert_eq!(
+            error(&file),
+            "land matrix file has element type '<f8', expected '<i4'"
+        );
+        let mut truncated = Vec::new();
+        mat![[1, 2, 3, 4]]
+            .write_native(&mut truncated, None)
+            .unwrap();
+        assert_eq!(
+            error(&truncated[..HEADER_LEN + 12]),
+            "failed to fill whole buffer"
+        );
+        assert_eq!(error(b"\x93NUMPY\x01\x00"), "failed to fill whole buffer");
+
+        file[20] = 1;
+        assert_eq!(
+            error(&file),
+            "checksum mismatch in header, the file is corrupted"
+        );
+
+        file[..8].copy_from_slice(b"LANDRAW\x01");
+        assert_eq!(error(&file), "not a land matrix file");
+    }
+
+    #[test]
+    fn save_and_load() {
+        let a = mat![[1.5f32, -2.0, 3.25], [0.0, 1e-30, f32::MAX]];
+        let path = std::env::temp_dir().join(format!("land-save-{}.land", std::process::id()));
+
+        a.save(&path).unwrap();
+        let b = Matrix::<f32>::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(b, a);
+    }
+}
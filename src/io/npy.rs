@@ -0,0 +1,419 @@
+//! The NumPy `.npy` array format.
+//!
+//! Arrays of the dtypes `<f4`, `<f8`, `<i4` and `<i8` can be read in both C and Fortran
+//! order. Arrays are always written in C order, which matches the row major layout of
+//! `Matrix`. See <https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html>.
+//! ```
+//! # use land::{mat, Matrix};
+//! # fn main() {
+//! let a = mat![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+//!
+//! let mut file = Vec::new();
+//! a.write_npy(&mut file).unwrap();
+//!
+//! assert_eq!(Matrix::<f64>::read_npy(file.as_slice()).unwrap(), a);
+//! # }
+//! ```
+
+use super::{Error, Result};
+use crate::{
+    matrix::{Dimensions, Matrix},
+    traits::Scalar,
+    vector::Vector,
+};
+use std::{
+    convert::TryInto,
+    io::{Read, Write},
+};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// The header, including the magic string and lengths, is padded to a multiple of this.
+const ALIGNMENT: usize = 64;
+
+/// The number of elements read at a time.
+const BLOCK: usize = 1 << 16;
+
+/// A type that can be stored in a `.npy` file.
+pub trait Element: Scalar + Copy {
+    /// The NumPy type description, such as `<f8`.
+    const DESCR: &'static str;
+
+    /// The number of bytes used by each element.
+    const SIZE: usize;
+
+    /// Decode an element from `SIZE` little endian bytes.
+    fn from_le_bytes(bytes: &[u8]) -> Self;
+
+    /// Append the little endian bytes of the element.
+    fn extend_le_bytes(self, out: &mut Vec<u8>);
+}
+
+macro_rules! impl_element {
+    ($($scalar:ty => $descr:expr),+) => {
+        $(
+            impl Element for $scalar {
+                const DESCR: &'static str = $descr;
+                const SIZE: usize = std::mem::size_of::<$scalar>();
+
+                fn from_le_bytes(bytes: &[u8]) -> $scalar {
+                    <$scalar>::from_le_bytes(bytes.try_into().unwrap())
+                }
+
+                fn extend_le_bytes(self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+        )+
+    };
+}
+
+impl_element!(f32 => "<f4", f64 => "<f8", i32 => "<i4", i64 => "<i8");
+
+/// The parsed header of a `.npy` file.
+struct Header {
+    descr: String,
+    fortran_order: bool,
+    shape: Vec<usize>,
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::Invalid(message.into())
+}
+
+/// Find the value following `'key':` in the header dictionary.
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str> {
+    let quoted = [format!("'{}'", key), format!("\"{}\"", key)];
+
+    let start = quoted
+        .iter()
+        .filter_map(|quoted| header.find(quoted.as_str()).map(|i| i + quoted.len()))
+        .next()
+        .ok_or_else(|| invalid(format!("npy header is missing '{}'", key)))?;
+
+    let rest = header[start..].trim_start();
+    if !rest.starts_with(':') {
+        return Err(invalid(format!("npy header has no value for '{}'", key)));
+    }
+
+    Ok(rest[1..].trim_start())
+}
+
+fn parse_header(header: &str) -> Result<Header> {
+    let descr = header_value(header, "descr")?;
+    let quote = descr.chars().next().filter(|&c| c == '\'' || c == '"');
+    let descr = match quote.and_then(|q| descr[1..].find(q)) {
+        Some(end) => descr[1..=end].to_owned(),
+        None => return Err(invalid("npy header has an invalid 'descr'")),
+    };
+
+    let fortran_order = header_value(header, "fortran_order")?;
+    let fortran_order = if fortran_order.starts_with("True") {
+        true
+    } else if fortran_order.starts_with("False") {
+        false
+    } else {
+        return Err(invalid("npy header has an invalid 'fortran_order'"));
+    };
+
+    let shape = header_value(header, "shape")?;
+    let end = match (shape.starts_with('('), shape.find(')')) {
+        (true, Some(end)) => end,
+        _ => return Err(invalid("npy header has an invalid 'shape'")),
+    };
+    let shape = shape[1..end]
+        .split(',')
+        .map(str::trim)
+        .filter(|size| !size.is_empty())
+        .map(|size| {
+            size.parse()
+                .map_err(|_| invalid(format!("npy header has an invalid size '{}'", size)))
+        })
+        .collect::<Result<Vec<usize>>>()?;
+
+    Ok(Header {
+        descr,
+        fortran_order,
+        shape,
+    })
+}
+
+/// Read an array, returning its shape, whether it is in Fortran order and the elements.
+fn read_array<S, R>(mut reader: R) -> Result<(Vec<usize>, bool, Vec<S>)>
+where
+    S: Element,
+    R: Read,
+{
+    let mut preamble = [0; 8];
+    reader.read_exact(&mut preamble)?;
+    if &preamble[..6] != MAGIC {
+        return Err(invalid("not a npy file"));
+    }
+
+    let header_len = match preamble[6] {
+        1 => {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        major => return Err(invalid(format!("unsupported npy version {}", major))),
+    };
+
+    let mut header = vec![0; header_len];
+    reader.read_exact(&mut header)?;
+    let header = String::from_utf8(header).map_err(|_| invalid("npy header is not UTF-8"))?;
+    let header = parse_header(&header)?;
+
+    if header.descr != S::DESCR {
+        return Err(invalid(format!(
+            "npy dtype '{}' does not match the element type '{}'",
+            header.descr,
+            S::DESCR
+        )));
+    }
+
+    let count = header
+        .shape
+        .iter()
+        .try_fold(1usize, |count, &size| count.checked_mul(size))
+        .filter(|count| count.checked_mul(S::SIZE).is_some())
+        .ok_or_else(|| invalid(format!("npy shape {:?} is too large", header.shape)))?;
+
+    // Don't trust the header with a huge allocation before the data has been read
+    let mut elements = Vec::with_capacity(count.min(BLOCK * 256));
+    let mut bytes = Vec::new();
+    let mut remaining = count;
+    while remaining > 0 {
+        let block = BLOCK.min(remaining);
+        bytes.resize(block * S::SIZE, 0);
+        reader.read_exact(&mut bytes)?;
+
+        elements.extend(bytes.chunks_exact(S::SIZE).map(S::from_le_bytes));
+        remaining -= block;
+    }
+
+    Ok((header.shape, header.fortran_order, elements))
+}
+
+fn write_array<'a, S, W, I>(mut writer: W, shape: &[usize], elements: I) -> Result<()>
+where
+    S: Element + 'a,
+    W: Write,
+    I: Iterator<Item = &'a S>,
+{
+    let shape = match shape {
+        [len] => format!("({},)", len),
+        _ => format!(
+            "({})",
+            shape
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+        S::DESCR,
+        shape
+    );
+
+    // Version 1.0 stores the header length in two bytes, version 2.0 in four
+    let (version, len_size) = if header.len() + MAGIC.len() + 4 < u16::MAX as usize {
+        (1, 2)
+    } else {
+        (2, 4)
+    };
+    let unpadded = MAGIC.len() + 2 + len_size + header.len() + 1;
+    let padding = (ALIGNMENT - unpadded % ALIGNMENT) % ALIGNMENT;
+    header.extend(std::iter::repeat_n(' ', padding));
+    header.push('\n');
+
+    let mut out = Vec::with_capacity(unpadded + padding);
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&[version, 0]);
+    if version == 1 {
+        out.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    } else {
+        out.extend_from_slice(&(header.len() as u32).to_le_bytes());
+    }
+    out.extend_from_slice(header.as_bytes());
+
+    for &e in elements {
+        e.extend_le_bytes(&mut out);
+    }
+
+    writer.write_all(&out)?;
+    Ok(())
+}
+
+impl<S> Matrix<S>
+where
+    S: Element,
+{
+    /// Read a two dimensional array from a `.npy` file. Arrays in Fortran order are
+    /// converted to row major order.
+    pub fn read_npy<R: Read>(reader: R) -> Result<Matrix<S>> {
+        let (shape, fortran_order, elements) = read_array(reader)?;
+
+        let dimensions = match shape[..] {
+            [rows, cols] => Dimensions { rows, cols },
+            _ => {
+                return Err(invalid(format!(
+                    "expected a two dimensional array, found shape {:?}",
+                    shape
+                )))
+            }
+        };
+
+        if fortran_order {
+            Ok(Matrix::from_row_major(dimensions.transpose(), elements).transpose())
+        } else {
+            Ok(Matrix::from_row_major(dimensions, elements))
+        }
+    }
+
+    /// Write the matrix as a two dimensional array in C order to a `.npy` file.
+    pub fn write_npy<W: Write>(&self, writer: W) -> Result<()> {
+        let dimensions = self.dim();
+        write_array(
+            writer,
+            &[dimensions.rows, dimensions.cols],
+            self.iter_rows(),
+        )
+    }
+}
+
+impl<S> Vector<S>
+where
+    S: Element,
+{
+    /// Read a one dimensional array from a `.npy` file.
+    pub fn read_npy<R: Read>(reader: R) -> Result<Vector<S>> {
+        let (shape, _, elements) = read_array(reader)?;
+
+        if shape.len() != 1 {
+            return Err(invalid(format!(
+                "expected a one dimensional array, found shape {:?}",
+                shape
+            )));
+        }
+
+        Ok(Vector::new(elements))
+    }
+
+    /// Write the vector as a one dimensional array to a `.npy` file.
+    pub fn write_npy<W: Write>(&self, writer: W) -> Result<()> {
+        write_array(writer, &[self.len()], self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A file as written by `numpy.save`.
+    fn npy(descr: &str, fortran_order: bool, shape: &str, data: &[u8]) -> Vec<u8> {
+        let mut header = format!(
+            "{{'descr': '{}', 'fortran_order': {}, 'shape': {}, }}",
+            descr,
+            if fortran_order { "True" } else { "False" },
+            shape
+        );
+        while (header.len() + 11) % 64 != 0 {
+            header.push(' ');
+        }
+        header.push('\n');
+
+        let mut file = b"\x93NUMPY\x01\x00".to_vec();
+        file.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        file.extend_from_slice(header.as_bytes());
+        file.extend_from_slice(data);
+        file
+    }
+
+    fn bytes<S: Element>(elements: &[S]) -> Vec<u8> {
+        let mut out = Vec::new();
+        elements.iter().for_each(|&e| e.extend_le_bytes(&mut out));
+        out
+    }
+
+    #[test]
+    fn c_and_fortran_order() {
+        let c = npy("<i4", false, "(2, 3)", &bytes(&[1i32, 2, 3, 4, 5, 6]));
+        let fortran = npy("<i4", true, "(2, 3)", &bytes(&[1i32, 4, 2, 5, 3, 6]));
+
+        let expected = mat![[1, 2, 3], [4, 5, 6]];
+        assert_eq!(Matrix::<i32>::read_npy(c.as_slice()).unwrap(), expected);
+        assert_eq!(
+            Matrix::<i32>::read_npy(fortran.as_slice()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn write_matches_numpy() {
+        let a = mat![[1.5f32, -2.0], [0.25, 8.0]];
+
+        let mut file = Vec::new();
+        a.write_npy(&mut file).unwrap();
+
+        assert_eq!(
+            file,
+            npy("<f4", false, "(2, 2)", &bytes(&[1.5f32, -2.0, 0.25, 8.0]))
+        );
+        assert_eq!(file.len() % 64, 16);
+    }
+
+    #[test]
+    fn vectors() {
+        let v = mat![3i64, -1, 4];
+
+        let mut file = Vec::new();
+        v.write_npy(&mut file).unwrap();
+
+        assert_eq!(file, npy("<i8", false, "(3,)", &bytes(&[3i64, -1, 4])));
+        assert_eq!(Vector::<i64>::read_npy(file.as_slice()).unwrap(), v);
+    }
+
+    #[test]
+    fn errors() {
+        let message = |result: Result<Matrix<f64>>| result.unwrap_err().to_string();
+
+        let file = npy("<f4", false, "(1, 1)", &bytes(&[1.0f32]));
+        assert_eq!(
+            message(Matrix::read_npy(file.as_slice())),
+            "npy dtype '<f4' does not match the element type '<f8'"
+        );
+
+        let file = npy("<f8", false, "(2, 1, 1)", &bytes(&[1.0f64, 2.0]));
+        assert_eq!(
+            message(Matrix::read_npy(file.as_slice())),
+            "expected a two dimensional array, found shape [2, 1, 1]"
+        );
+
+        let file = npy("<f8", false, "(2, 2)", &bytes(&[1.0f64, 2.0]));
+        assert!(matches!(
+            Matrix::<f64>::read_npy(file.as_slice()),
+            Err(Error::Io(_))
+        ));
+
+        let file = npy("<f8", false, "(4294967296, 4294967296)", &[]);
+        assert_eq!(
+            message(Matrix::read_npy(file.as_slice())),
+            "npy shape [4294967296, 4294967296] is too large"
+        );
+
+        // A huge shape is not allocated before the data is found to be missing
+        let file = npy("<f8", false, "(1000000000, 1000000)", &bytes(&[1.0f64]));
+        assert!(matches!(
+            Matrix::<f64>::read_npy(file.as_slice()),
+            Err(Error::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+//! NumPy `.npz` archives: zip files containing a `.npy` file for every array, as written
+//! by `numpy.savez` and `numpy.savez_compressed`.
+//! ```
+//! # use land::{io::npz::{NpzReader, NpzWriter}, mat, Matrix};
+//! # use std::io::Cursor;
+//! # fn main() {
+//! let weights = mat![[0.5, -1.0], [2.0, 0.0]];
+//! let bias = mat![1.0, 2.0];
+//!
+//! let mut writer = NpzWriter::new(Cursor::new(Vec::new()));
+//! writer.add_matrix("weights", &weights).unwrap();
+//! writer.add_vector("bias", &bias).unwrap();
+//! let file = writer.finish().unwrap();
+//!
+//! let mut reader = NpzReader::new(file).unwrap();
+//! assert_eq!(reader.names(), vec!["bias", "weights"]);
+//! assert_eq!(reader.matrix::<f64>("weights").unwrap(), weights);
+//! assert_eq!(reader.vector::<f64>("bias").unwrap(), bias);
+//! # }
+//! ```
+
+use super::{npy::Element, Error, Result};
+use crate::{matrix::Matrix, vector::Vector};
+use std::io::{Read, Seek, Write};
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+fn zip_error(error: zip::result::ZipError) -> Error {
+    Error::Io(error.into())
+}
+
+/// Reads arrays from an `.npz` archive.
+pub struct NpzReader<R> {
+    archive: ZipArchive<R>,
+}
+
+impl<R> NpzReader<R>
+where
+    R: Read + Seek,
+{
+    pub fn new(reader: R) -> Result<NpzReader<R>> {
+        let archive = ZipArchive::new(reader).map_err(zip_error)?;
+        Ok(NpzReader { archive })
+    }
+
+    /// The names of every array in the archive, in alphabetical order.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .archive
+            .file_names()
+            .map(|name| name.strip_suffix(".npy").unwrap_or(name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Read the two dimensional array with the given name.
+    pub fn matrix<S: Element>(&mut self, name: &str) -> Result<Matrix<S>> {
+        Matrix::read_npy(self.file(name)?)
+    }
+
+    /// Read the one dimensional array with the given name.
+    pub fn vector<S: Element>(&mut self, name: &str) -> Result<Vector<S>> {
+        Vector::read_npy(self.file(name)?)
+    }
+
+    fn file(&mut self, name: &str) -> Result<zip::read::ZipFile<'_>> {
+        let path = format!("{}.npy", name);
+
+        // NumPy stores every array as `<name>.npy`, but accept the full file name as well
+        let path = if self.archive.file_names().any(|file| file == path) {
+            path
+        } else {
+            name.to_owned()
+        };
+
+        self.archive.by_name(&path).map_err(|error| match error {
+            zip::result::ZipError::FileNotFound => {
+                Error::Invalid(format!("npz archive has no array named '{}'", name))
+            }
+            error => zip_error(error),
+        })
+    }
+}
+
+/// Writes arrays to an `.npz` archive.
+///
+/// Arrays are stored uncompressed, like `numpy.savez`.
+pub struct NpzWriter<W: Write + Seek> {
+    zip: ZipWriter<W>,
+}
+
+impl<W> NpzWriter<W>
+where
+    W: Write + Seek,
+{
+    pub fn new(writer: W) -> NpzWriter<W> {
+        NpzWriter {
+            zip: ZipWriter::new(writer),
+        }
+    }
+
+    /// Add a matrix as a two dimensional array.
+    pub fn add_matrix<S: Element>(&mut self, name: &str, matrix: &Matrix<S>) -> Result<()> {
+        self.start_file(name)?;
+        matrix.write_npy(&mut self.zip)
+    }
+
+    /// Add a vector as a one dimensional array.
+    pub fn add_vector<S: Element>(&mut self, name: &str, vector: &Vector<S>) -> Result<()> {
+        self.start_file(name)?;
+        vector.write_npy(&mut self.zip)
+    }
+
+    /// Write the end of the archive and return the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        self.zip.finish().map_err(zip_error)
+    }
+
+    fn start_file(&mut self, name: &str) -> Result<()> {
+        let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+        self.zip
+            .start_file(format!("{}.npy", name), options)
+            .map_err(zip_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn compressed_archive() {
+        // numpy.savez_compressed uses deflate
+        let a = mat![[1i32, 2, 3], [4, 5, 6]];
+        let mut npy = Vec::new();
+        a.write_npy(&mut npy).unwrap();
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        zip.start_file("a.npy", options).unwrap();
+        zip.write_all(&npy).unwrap();
+        let file = zip.finish().unwrap();
+
+        let mut reader = NpzReader::new(file).unwrap();
+        assert_eq!(reader.matrix::<i32>("a").unwrap(), a);
+    }
+
+    #[test]
+    fn missing_array() {
+        let writer = NpzWriter::new(Cursor::new(Vec::new()));
+        let mut reader = NpzReader::new(writer.finish().unwrap()).unwrap();
+
+        assert_eq!(
+            reader.matrix::<f64>("w").unwrap_err().to_string(),
+            "npz archive has no array named 'w'"
+        );
+    }
+}
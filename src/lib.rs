@@ -1,15 +1,43 @@
 #[macro_use]
 mod macros;
+mod autodiff;
+mod batch;
+mod fixed;
+mod interval;
+pub mod io;
 mod matrix;
+mod modular;
+mod parse;
+mod precision;
+mod semiring;
+mod tensor;
 mod traits;
 mod vector;
 
+pub use autodiff::*;
+pub use batch::*;
+pub use fixed::*;
+pub use interval::*;
 pub use matrix::*;
+pub use modular::*;
+pub use parse::*;
+pub use precision::*;
+pub use semiring::*;
+pub use tensor::*;
 pub use traits::*;
 pub use vector::*;
 
 pub mod prelude {
+    pub use crate::autodiff::*;
+    pub use crate::batch::*;
+    pub use crate::fixed::*;
+    pub use crate::interval::*;
     pub use crate::matrix::*;
+    pub use crate::modular::*;
+    pub use crate::parse::*;
+    pub use crate::precision::*;
+    pub use crate::semiring::*;
+    pub use crate::tensor::*;
     pub use crate::traits::*;
     pub use crate::vector::*;
 }
@@ -12,3 +12,26 @@ macro_rules! mat {
         $crate::Vector::new(vec![ $( $elem ),+ ])
     }
 }
+
+/// Implement every compound assignment operator, such as `+=`, in terms of the binary
+/// operator for a `Copy` type, for example `impl_assign_operators!([const P: u64] Zp<P>);`.
+macro_rules! impl_assign_operators {
+    ([$($generics:tt)*] $type:ty $(where $($bounds:tt)+)?) => {
+        impl_assign_operators!(@impl [$($generics)*] $type [$($($bounds)+)?], AddAssign, add_assign, add);
+        impl_assign_operators!(@impl [$($generics)*] $type [$($($bounds)+)?], SubAssign, sub_assign, sub);
+        impl_assign_operators!(@impl [$($generics)*] $type [$($($bounds)+)?], MulAssign, mul_assign, mul);
+        impl_assign_operators!(@impl [$($generics)*] $type [$($($bounds)+)?], DivAssign, div_assign, div);
+        impl_assign_operators!(@impl [$($generics)*] $type [$($($bounds)+)?], RemAssign, rem_assign, rem);
+    };
+
+    (@impl [$($generics:tt)*] $type:ty [$($bounds:tt)*], $trait:ident, $fn:ident, $op:ident) => {
+        impl<$($generics)*> $trait for $type
+        where
+            $($bounds)*
+        {
+            fn $fn(&mut self, rhs: $type) {
+                *self = self.$op(rhs);
+            }
+        }
+    };
+}
@@ -1,13 +1,38 @@
 mod constructors;
 mod dimensions;
+mod elimination;
+pub(crate) mod format;
 mod index;
 mod operations;
+#[cfg(feature = "serde")]
+mod serialize;
 
 pub use self::dimensions::*;
+pub use self::elimination::Rref;
 use crate::traits::Scalar;
-use std::{fmt, ops::*};
+use std::ops::*;
 
 /// A row major matrix
+///
+/// # Scalar operators
+///
+/// A scalar can be added to, subtracted from, multiplied with or divided into every
+/// element. With a primitive scalar on the left hand side the scalar is the left operand
+/// of every elementwise operation, as for vectors.
+///
+/// **Breaking change:** `10 - m` and `10 / m` used to compute `m - 10` and `m / 10`. They
+/// now compute `10 - e` and `10 / e` for every element `e`; swap the operands to keep the
+/// old result.
+/// ```
+/// # use land::{mat, Matrix};
+/// # fn main() {
+/// let m = mat![[1, 2], [4, 8]];
+///
+/// assert_eq!(10 - &m, mat![[9, 8], [6, 2]]);
+/// assert_eq!(&m - 10, mat![[-9, -8], [-6, -2]]);
+/// assert_eq!(8 / &m, mat![[8, 4], [2, 1]]);
+/// # }
+/// ```
 #[derive(Debug, Clone)]
 pub struct Matrix<S> {
     elements: Vec<S>,
@@ -50,6 +75,17 @@ impl<S> Matrix<S> {
 
         &mut self.elements[row_start..row_start + row_len]
     }
+
+    /// Apply a function to every element of the matrix, possibly changing its type.
+    pub fn map<T, F>(self, f: F) -> Matrix<T>
+    where
+        F: FnMut(S) -> T,
+    {
+        Matrix {
+            elements: self.elements.into_iter().map(f).collect(),
+            dimensions: self.dimensions,
+        }
+    }
 }
 
 impl<S> PartialEq<Self> for Matrix<S>
@@ -61,31 +97,6 @@ where
     }
 }
 
-impl<S> fmt::Display for Matrix<S>
-where
-    S: fmt::Display,
-{
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "[")?;
-        for row in 0..self.dimensions.rows {
-            write!(f, "    [")?;
-            for col in 0..self.dimensions.cols {
-                self.row(row)[col].fmt(f)?;
-
-                if col != self.dimensions.cols - 1 {
-                    write!(f, ", ")?;
-                }
-            }
-            if row == self.dimensions.rows - 1 {
-                writeln!(f, "]")?;
-            } else {
-                writeln!(f, "],")?;
-            }
-        }
-        write!(f, "]")
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -17,10 +17,10 @@ impl<S> Matrix<S> {
 
         let dimensions = Dimensions {
             rows: rows.len(),
-            cols: rows.get(0).map(Vec::len).unwrap_or(0),
+            cols: rows.first().map(Vec::len).unwrap_or(0),
         };
 
-        let elements = rows.into_iter().map(Vec::into_iter).flatten().collect();
+        let elements = rows.into_iter().flatten().collect();
 
         Matrix {
             elements,
@@ -68,7 +68,7 @@ where
     pub fn filled(value: S, dimensions: Dimensions) -> Matrix<S> {
         Matrix {
             elements: vec![value; dimensions.elements()],
-            dimensions: dimensions,
+            dimensions,
         }
     }
 }
@@ -2,6 +2,7 @@ use std::fmt;
 
 /// The dimensions of a matrix
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Dimensions {
     pub rows: usize,
     pub cols: usize,
@@ -26,12 +27,32 @@ impl Dimensions {
         }
     }
 
+    /// The dimensions of the result of an elementwise operation, where an axis of size 1
+    /// stretches to match the other side, or `None` if the dimensions are incompatible.
+    pub fn broadcast(&self, other: &Dimensions) -> Option<Dimensions> {
+        Some(Dimensions {
+            rows: broadcast_axis(self.rows, other.rows)?,
+            cols: broadcast_axis(self.cols, other.cols)?,
+        })
+    }
+
     /// Convert a coordinate to an index into a row-major matrix.
     pub(crate) fn row_major(&self, row: usize, col: usize) -> usize {
         row * self.cols + col
     }
 }
 
+/// The size of an axis after broadcasting two sizes together.
+pub(crate) fn broadcast_axis(a: usize, b: usize) -> Option<usize> {
+    if a == b || b == 1 {
+        Some(a)
+    } else if a == 1 {
+        Some(b)
+    } else {
+        None
+    }
+}
+
 impl From<(usize, usize)> for Dimensions {
     fn from((rows, cols): (usize, usize)) -> Dimensions {
         Dimensions { rows, cols }
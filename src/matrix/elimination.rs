@@ -0,0 +1,448 @@
+use super::*;
+use crate::{traits::Field, vector::Vector};
+
+/// The reduced row echelon form of a matrix, as returned by `Matrix::rref` and
+/// `Gf2Matrix::rref`.
+#[derive(Debug, Clone)]
+pub struct Rref<M> {
+    /// The matrix in reduced row echelon form
+    pub matrix: M,
+    /// The column of the leading one in each non-zero row, in order
+    pub pivots: Vec<usize>,
+}
+
+impl<M> Rref<M> {
+    /// The rank of the original matrix: the number of pivots.
+    pub fn rank(&self) -> usize {
+        self.pivots.len()
+    }
+}
+
+impl<S> Matrix<S>
+where
+    S: Clone,
+{
+    /// Place the columns of another matrix to the right of this one: `[self | rhs]`.
+    pub fn augment(&self, rhs: &Matrix<S>) -> Matrix<S> {
+        assert!(
+            self.dimensions.rows == rhs.dimensions.rows,
+            "Matrices must have the same number of rows. Left hand side is {} and right hand side is {}",
+            self.dimensions,
+            rhs.dimensions,
+        );
+
+        let dimensions = Dimensions {
+            rows: self.dimensions.rows,
+            cols: self.dimensions.cols + rhs.dimensions.cols,
+        };
+        let mut elements = Vec::with_capacity(dimensions.elements());
+
+        for row in 0..self.dimensions.rows {
+            elements.extend_from_slice(self.row(row));
+            elements.extend_from_slice(rhs.row(row));
+        }
+
+        Matrix::from_row_major(dimensions, elements)
+    }
+
+    /// Get the columns in the range `start..end` as a new matrix.
+    pub(crate) fn columns(&self, start: usize, end: usize) -> Matrix<S> {
+        let dimensions = Dimensions {
+            rows: self.dimensions.rows,
+            cols: end - start,
+        };
+        let mut elements = Vec::with_capacity(dimensions.elements());
+
+        for row in 0..self.dimensions.rows {
+            elements.extend_from_slice(&self.row(row)[start..end]);
+        }
+
+        Matrix::from_row_major(dimensions, elements)
+    }
+
+    /// Swap two rows of the matrix.
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+
+        let cols = self.dimensions.cols;
+        let (first, second) = (a.min(b), a.max(b));
+        let (head, tail) = self.elements.split_at_mut(second * cols);
+
+        head[first * cols..(first + 1) * cols].swap_with_slice(&mut tail[..cols]);
+    }
+}
+
+impl<S> Matrix<S>
+where
+    S: Scalar,
+{
+    /// Multiply every element in a row by a factor.
+    pub fn scale_row(&mut self, row: usize, factor: S) {
+        for e in self.row_mut(row) {
+            *e *= factor.clone();
+        }
+    }
+
+    /// Add a multiple of the row `source` to the row `target`: `target += factor * source`.
+    pub fn add_row_multiple(&mut self, target: usize, source: usize, factor: S) {
+        for col in 0..self.dimensions.cols {
+            let term = self[source][col].clone() * factor.clone();
+            self[target][col] += term;
+        }
+    }
+
+    /// The determinant of a square matrix, computed with fraction-free Bareiss elimination.
+    ///
+    /// Every division is exact, so the determinant of an integer matrix is computed without
+    /// truncation.
+    /// ```
+    /// # use land::{mat, Matrix};
+    /// # fn main() {
+    /// assert_eq!(mat![[2, 1], [1, 1]].determinant(), 1);
+    /// # }
+    /// ```
+    pub fn determinant(&self) -> S {
+        assert!(
+            self.dimensions.rows == self.dimensions.cols,
+            "Matrix must be square. Matrix is of size {}",
+            self.dimensions
+        );
+
+        let size = self.dimensions.rows;
+        let mut matrix = self.clone();
+        let mut negate = false;
+        let mut previous = S::one();
+
+        for col in 0..size {
+            let pivot = match (col..size).find(|&r| !matrix[r][col].is_zero()) {
+                Some(pivot) => pivot,
+                None => return S::zero(),
+            };
+
+            if pivot != col {
+                matrix.swap_rows(col, pivot);
+                negate = !negate;
+            }
+
+            let leading = matrix[col][col].clone();
+            for row in col + 1..size {
+                let factor = matrix[row][col].clone();
+                for j in col + 1..size {
+                    let term = leading.clone() * matrix[row][j].clone()
+                        - factor.clone() * matrix[col][j].clone();
+                    matrix[row][j] = term / previous.clone();
+                }
+            }
+            previous = leading;
+        }
+
+        if negate {
+            -previous
+        } else {
+            previous
+        }
+    }
+}
+
+impl<S> Matrix<S>
+where
+    S: Field,
+{
+    /// Compute the reduced row echelon form using Gauss–Jordan elimination.
+    ///
+    /// The first non-zero element in each column is used as pivot, so no precision is lost
+    /// when the scalar is exact, such as `num::rational::Ratio`. Floating point scalars
+    /// pivot on the element of largest magnitude, but the result is still subject to
+    /// rounding errors.
+    /// ```
+    /// # use land::{mat, Matrix};
+    /// # use num::rational::Ratio;
+    /// # fn main() {
+    /// let r = |n| Ratio::from_integer(n);
+    /// let a = mat![[r(1), r(2), r(3)], [r(2), r(4), r(7)]];
+    ///
+    /// let rref = a.rref();
+    ///
+    /// assert_eq!(rref.matrix, mat![[r(1), r(2), r(0)], [r(0), r(0), r(1)]]);
+    /// assert_eq!(rref.pivots, vec![0, 2]);
+    /// assert_eq!(rref.rank(), 2);
+    /// # }
+    /// ```
+    pub fn rref(&self) -> Rref<Matrix<S>> {
+        let mut matrix = self.clone();
+        let mut pivots = Vec::new();
+
+        for col in 0..self.dimensions.cols {
+            let row = pivots.len();
+            if row == self.dimensions.rows {
+                break;
+            }
+
+            let mut candidates = (row..self.dimensions.rows).filter(|&r| !matrix[r][col].is_zero());
+            let pivot = match candidates.next() {
+                Some(first) => candidates.fold(first, |pivot, r| {
+                    if matrix[r][col].is_better_pivot(&matrix[pivot][col]) {
+                        r
+                    } else {
+                        pivot
+                    }
+                }),
+                None => continue,
+            };
+
+            matrix.swap_rows(row, pivot);
+            let leading = matrix[row][col].clone();
+            matrix.scale_row(row, S::one() / leading);
+
+            for other in 0..self.dimensions.rows {
+                let factor = matrix[other][col].clone();
+                if other != row && !factor.is_zero() {
+                    matrix.add_row_multiple(other, row, -factor);
+                }
+            }
+
+            pivots.push(col);
+        }
+
+        Rref { matrix, pivots }
+    }
+
+    /// The rank of the matrix: the number of linearly independent rows.
+    pub fn rank(&self) -> usize {
+        self.rref().rank()
+    }
+
+    /// A basis for the null space: the columns of the returned matrix span every `x` such
+    /// that `self * x` is zero.
+    pub fn null_space(&self) -> Matrix<S> {
+        let rref = self.rref();
+        let cols = self.dimensions.cols;
+        let free: Vec<usize> = (0..cols).filter(|col| !rref.pivots.contains(col)).collect();
+
+        let mut basis = Matrix::zeros([cols, free.len()].into());
+        for (i, &free_col) in free.iter().enumerate() {
+            basis[free_col][i] = S::one();
+
+            for (row, &pivot) in rref.pivots.iter().enumerate() {
+                basis[pivot][i] = -rref.matrix[row][free_col].clone();
+            }
+        }
+
+        basis
+    }
+
+    /// Find an `x` such that `self * x == b`.
+    ///
+    /// Returns `None` if the system has no solution. If there are infinitely many
+    /// solutions, the one where every free variable is zero is returned.
+    pub fn solve(&self, b: &Vector<S>) -> Option<Vector<S>> {
+        assert!(
+            self.dimensions.rows == b.len(),
+            "Matrix dimensions must agree. Left hand side is {} and right hand side has length {}",
+            self.dimensions,
+            b.len(),
+        );
+
+        let cols = self.dimensions.cols;
+        let b = Matrix::from_row_major([b.len(), 1].into(), b.clone().into());
+        let rref = self.augment(&b).rref();
+
+        if rref.pivots.last() == Some(&cols) {
+            return None;
+        }
+
+        let mut x = Vector::zeros(cols);
+        for (row, &col) in rref.pivots.iter().enumerate() {
+            x[col] = rref.matrix[row][cols].clone();
+        }
+
+        Some(x)
+    }
+
+    /// Returns the inverse of a square matrix, or `None` if the matrix is singular.
+    pub fn inverse(&self) -> Option<Matrix<S>> {
+        assert!(
+            self.dimensions.rows == self.dimensions.cols,
+            "Matrix must be square. Matrix is of size {}",
+            self.dimensions
+        );
+
+        let size = self.dimensions.rows;
+        let rref = self.augment(&Matrix::identity(size)).rref();
+
+        if rref.pivots.iter().any(|&col| col >= size) {
+            return None;
+        }
+
+        Some(rref.matrix.columns(size, 2 * size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::{
+        rational::{BigRational, Ratio},
+        BigInt, Signed,
+    };
+
+    fn r(numer: i64, denom: i64) -> Ratio<i64> {
+        Ratio::new(numer, denom)
+    }
+
+    #[test]
+    fn rref_rank_deficient() {
+        let a = mat![[1, 2, 1], [2, 4, 0], [3, 6, 1]].map(Ratio::from_integer);
+
+        let rref = a.rref();
+
+        assert_eq!(
+            rref.matrix,
+            mat![[1, 2, 0], [0, 0, 1], [0, 0, 0]].map(Ratio::from_integer)
+        );
+        assert_eq!(rref.pivots, vec![0, 2]);
+        assert_eq!(a.rank(), 2);
+    }
+
+    #[test]
+    fn inverse_exact() {
+        let a = mat![[r(2, 1), r(1, 1)], [r(7, 1), r(4, 1)]];
+
+        let inverse = a.inverse().unwrap();
+
+        assert_eq!(inverse, mat![[r(4, 1), r(-1, 1)], [r(-7, 1), r(2, 1)]]);
+        assert_eq!(&a * &inverse, Matrix::identity(2));
+    }
+
+    #[test]
+    fn inverse_fractions() {
+        let a = mat![[r(3, 1), r(1, 1)], [r(1, 1), r(1, 1)]];
+
+        let inverse = a.inverse().unwrap();
+
+        assert_eq!(inverse, mat![[r(1, 2), r(-1, 2)], [r(-1, 2), r(3, 2)]]);
+    }
+
+    #[test]
+    fn inverse_singular() {
+        let a = mat![[r(1, 1), r(2, 1)], [r(1, 2), r(1, 1)]];
+
+        assert!(a.inverse().is_none());
+    }
+
+    #[test]
+    fn solve_exact() {
+        let a = mat![
+            [r(1, 1), r(1, 1), r(1, 1)],
+            [r(0, 1), r(2, 1), r(5, 1)],
+            [r(2, 1), r(5, 1), r(-1, 1)]
+        ];
+        let b = mat![r(6, 1), r(-4, 1), r(27, 1)];
+
+        let x = a.solve(&b).unwrap();
+
+        assert_eq!(x, mat![r(5, 1), r(3, 1), r(-2, 1)]);
+        assert_eq!(&a * &x, b);
+    }
+
+    #[test]
+    fn solve_inconsistent() {
+        let a = mat![[r(1, 1), r(1, 1)], [r(2, 1), r(2, 1)]];
+        let b = mat![r(1, 1), r(3, 1)];
+
+        assert!(a.solve(&b).is_none());
+    }
+
+    #[test]
+    fn determinant_exact() {
+        let a = mat![
+            [r(0, 1), r(2, 1), r(1, 1)],
+            [r(1, 2), r(1, 1), r(0, 1)],
+            [r(1, 1), r(1, 1), r(1, 1)]
+        ];
+
+        assert_eq!(a.determinant(), r(-3, 2));
+    }
+
+    #[test]
+    fn float_partial_pivoting() {
+        // Pivoting on the tiny first element would round the second row away entirely
+        let a = mat![[1e-20, 1.0], [1.0, 1.0]];
+        let b = Vector::from(vec![1.0, 2.0]);
+
+        let x = a.solve(&b).unwrap();
+
+        assert!((x[0] - 1.0f64).abs() < 1e-12);
+        assert!((x[1] - 1.0f64).abs() < 1e-12);
+    }
+
+    #[test]
+    fn determinant_integer() {
+        assert_eq!(mat![[2, 1], [1, 1]].determinant(), 1);
+        assert_eq!(mat![[0, 2, 1], [3, 1, 0], [2, 2, 2]].determinant(), -8);
+        assert_eq!(mat![[1, 2], [2, 4]].determinant(), 0);
+        assert_eq!(Matrix::<i64>::identity(4).determinant(), 1);
+    }
+
+    #[test]
+    fn null_space_exact() {
+        let a = mat![[r(1, 1), r(2, 1), r(1, 1)], [r(2, 1), r(4, 1), r(0, 1)]];
+
+        let null = a.null_space();
+
+        assert_eq!(null, mat![[r(-2, 1)], [r(1, 1)], [r(0, 1)]]);
+    }
+
+    #[test]
+    fn hilbert_inverse_big_rational() {
+        // Entries of the inverse grow beyond i64 for a 15x15 Hilbert matrix
+        let n = 15;
+        let mut h = Matrix::zeros([n, n].into());
+        for row in 0..n {
+            for col in 0..n {
+                h[row][col] = BigRational::new(1.into(), BigInt::from(row + col + 1));
+            }
+        }
+
+        let inverse = h.inverse().unwrap();
+
+        assert_eq!(&h * &inverse, Matrix::identity(n));
+        let limit = BigRational::from_integer(BigInt::from(i64::MAX));
+        assert!(inverse.iter_rows().any(|e| e.abs() > limit));
+    }
+
+    #[test]
+    fn polynomial_fit_big_rational() {
+        // Interpolate p(x) = 3x^9 - x^4 + 7 through ten points using a Vandermonde matrix
+        let big = |n: i64| BigRational::from_integer(n.into());
+        let p = |x: i64| big(3) * num::pow(big(x), 9) - num::pow(big(x), 4) + big(7);
+
+        let xs: Vec<i64> = (0..10).map(|i| 1000 + i).collect();
+        let mut vandermonde = Matrix::zeros([10, 10].into());
+        for (row, &x) in xs.iter().enumerate() {
+            for col in 0..10 {
+                vandermonde[row][col] = num::pow(big(x), col);
+            }
+        }
+        let values: Vector<_> = xs.iter().map(|&x| p(x)).collect::<Vec<_>>().into();
+
+        let coefficients = vandermonde.solve(&values).unwrap();
+
+        let mut expected = Vector::zeros(10);
+        expected[0] = big(7);
+        expected[4] = big(-1);
+        expected[9] = big(3);
+        assert_eq!(coefficients, expected);
+    }
+
+    #[test]
+    fn swap_rows() {
+        let mut a = mat![[1, 2], [3, 4], [5, 6]];
+
+        a.swap_rows(2, 0);
+
+        assert_eq!(a, mat![[5, 6], [3, 4], [1, 2]]);
+    }
+}
@@ -0,0 +1,239 @@
+use super::Matrix;
+use std::fmt;
+
+/// Matrices and vectors with more elements than this are truncated by `Display`.
+pub(crate) const TRUNCATE_THRESHOLD: usize = 1000;
+/// The number of rows and columns shown on each side of a truncated matrix.
+pub(crate) const EDGE_ITEMS: usize = 3;
+
+/// The indices shown along an axis, where `None` stands for the elided middle.
+pub(crate) fn shown_indices(len: usize, truncate: bool) -> Vec<Option<usize>> {
+    if truncate && len > 2 * EDGE_ITEMS {
+        (0..EDGE_ITEMS)
+            .map(Some)
+            .chain(std::iter::once(None))
+            .chain((len - EDGE_ITEMS..len).map(Some))
+            .collect()
+    } else {
+        (0..len).map(Some).collect()
+    }
+}
+
+/// Format an element with the sign and precision flags of a formatter.
+pub(crate) fn format_element<S: fmt::Display>(element: &S, f: &fmt::Formatter) -> String {
+    match (f.precision(), f.sign_plus()) {
+        (Some(precision), true) => format!("{:+.*}", precision, element),
+        (Some(precision), false) => format!("{:.*}", precision, element),
+        (None, true) => format!("{:+}", element),
+        (None, false) => element.to_string(),
+    }
+}
+
+impl<S> Matrix<S> {
+    /// The formatted elements of every shown row, where `None` is an elided row and
+    /// `"..."` an elided column.
+    fn cells<F>(&self, truncate: bool, format: F) -> Vec<Option<Vec<String>>>
+    where
+        F: Fn(&S) -> String,
+    {
+        let cols = shown_indices(self.dimensions.cols, truncate);
+
+        shown_indices(self.dimensions.rows, truncate)
+            .into_iter()
+            .map(|row| {
+                row.map(|row| {
+                    cols.iter()
+                        .map(|col| match col {
+                            Some(col) => format(&self.row(row)[*col]),
+                            None => "...".to_owned(),
+                        })
+                        .collect()
+                })
+            })
+            .collect()
+    }
+
+    /// All formatted rows, right-aligned in columns at least `width` wide.
+    fn aligned<F>(&self, width: usize, format: F) -> Vec<Vec<String>>
+    where
+        F: Fn(&S) -> String,
+    {
+        let rows: Vec<Vec<String>> = self.cells(false, format).into_iter().flatten().collect();
+        align(rows, width)
+    }
+}
+
+fn column_widths(rows: &[Vec<String>], width: usize) -> Vec<usize> {
+    let cols = rows.first().map_or(0, Vec::len);
+
+    (0..cols)
+        .map(|col| {
+            rows.iter()
+                .map(|row| row[col].chars().count())
+                .fold(width, usize::max)
+        })
+        .collect()
+}
+
+fn align(rows: Vec<Vec<String>>, width: usize) -> Vec<Vec<String>> {
+    let widths = column_widths(&rows, width);
+
+    rows.into_iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:>1$}", cell, width))
+                .collect()
+        })
+        .collect()
+}
+
+impl<S> fmt::Display for Matrix<S>
+where
+    S: fmt::Display,
+{
+    /// Print the matrix as nested lists with right-aligned columns.
+    ///
+    /// The width, precision and `+` flags apply to every element, like `{:8.3}`. Matrices
+    /// with more than 1000 elements only show their first and last three rows and columns,
+    /// unless the alternate flag `{:#}` is used.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let truncate = !f.alternate() && self.dimensions.elements() > TRUNCATE_THRESHOLD;
+        let cells = self.cells(truncate, |element| format_element(element, f));
+
+        let shown: Vec<Vec<String>> = cells.iter().flatten().cloned().collect();
+        let widths = column_widths(&shown, f.width().unwrap_or(0));
+
+        writeln!(f, "[")?;
+        for (index, row) in cells.iter().enumerate() {
+            let separator = if index == cells.len() - 1 { "" } else { "," };
+
+            match row {
+                Some(row) => {
+                    let row: Vec<String> = row
+                        .iter()
+                        .zip(&widths)
+                        .map(|(cell, width)| format!("{:>1$}", cell, width))
+                        .collect();
+                    writeln!(f, "    [{}]{}", row.join(", "), separator)?;
+                }
+                None => writeln!(f, "    ...{}", separator)?,
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+impl<S> Matrix<S>
+where
+    S: fmt::Display,
+{
+    /// Render the matrix as a LaTeX `bmatrix`.
+    pub fn to_latex(&self) -> String {
+        let rows: Vec<String> = self
+            .aligned(0, ToString::to_string)
+            .iter()
+            .map(|row| format!("    {}", row.join(" & ")))
+            .collect();
+
+        format!(
+            "\\begin{{bmatrix}}\n{}\n\\end{{bmatrix}}",
+            rows.join(" \\\\\n")
+        )
+    }
+
+    /// Render the matrix as a Markdown table, with the column numbers as header.
+    pub fn to_markdown(&self) -> String {
+        let header: Vec<String> = (0..self.dimensions.cols)
+            .map(|col| col.to_string())
+            .collect();
+        let mut rows = vec![header];
+        rows.extend(self.cells(false, ToString::to_string).into_iter().flatten());
+        let rows = align(rows, 3);
+
+        let rule: Vec<String> = rows[0]
+            .iter()
+            .map(|cell| format!("{}:", "-".repeat(cell.chars().count() + 1)))
+            .collect();
+
+        let mut lines: Vec<String> = rows
+            .iter()
+            .map(|row| format!("| {} |", row.join(" | ")))
+            .collect();
+        lines.insert(1, format!("|{}|", rule.join("|")));
+        lines.join("\n")
+    }
+
+    /// Render the matrix as a MATLAB or Octave literal, like `[1 2; 3 4]`.
+    pub fn to_matlab(&self) -> String {
+        let rows: Vec<String> = self
+            .cells(false, ToString::to_string)
+            .into_iter()
+            .flatten()
+            .map(|row| row.join(" "))
+            .collect();
+
+        format!("[{}]", rows.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligned_columns() {
+        let a = mat![[1.0, -20.5], [300.25, 4.0]];
+
+        assert_eq!(
+            format!("{}", a),
+            "[\n    [     1, -20.5],\n    [300.25,     4]\n]"
+        );
+        assert_eq!(
+            format!("{:+7.1}", a),
+            "[\n    [   +1.0,   -20.5],\n    [ +300.2,    +4.0]\n]"
+        );
+        assert_eq!(a.to_string().parse::<Matrix<f64>>().unwrap(), a);
+    }
+
+    #[test]
+    fn truncated() {
+        let elements = (0..40).flat_map(|i| (0..30).map(move |j| i * j)).collect();
+        let a = Matrix::<i32>::from_row_major((40, 30).into(), elements);
+
+        let out = a.to_string();
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[1], "    [0,  0,  0, ...,    0,    0,    0],");
+        assert_eq!(lines[4], "    ...,");
+        assert_eq!(lines[7], "    [0, 39, 78, ..., 1053, 1092, 1131]");
+        assert_eq!(format!("{:#}", a).lines().count(), 42);
+    }
+
+    #[test]
+    fn renderers() {
+        let a = mat![[1, -2], [30, 4]];
+
+        assert_eq!(
+            a.to_latex(),
+            "\\begin{bmatrix}\n     1 & -2 \\\\\n    30 &  4\n\\end{bmatrix}"
+        );
+        assert_eq!(
+            a.to_markdown(),
+            "|   0 |   1 |\n|----:|----:|\n|   1 |  -2 |\n|  30 |   4 |"
+        );
+        assert_eq!(a.to_matlab(), "[1 -2; 30 4]");
+        assert_eq!(a.to_matlab().parse::<Matrix<i32>>().unwrap(), a);
+    }
+
+    #[test]
+    fn markdown_counts_characters() {
+        let a = Matrix::from_row_major((1, 2).into(), vec!["αβγδ", "x"]);
+
+        assert_eq!(
+            a.to_markdown(),
+            "|    0 |   1 |\n|-----:|----:|\n| αβγδ |   x |"
+        );
+    }
+}
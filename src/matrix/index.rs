@@ -1,9 +1,6 @@
 use super::*;
 
-impl<S> Index<usize> for Matrix<S>
-where
-    S: Scalar,
-{
+impl<S> Index<usize> for Matrix<S> {
     type Output = [S];
 
     /// Get a row of the matrix.
@@ -12,20 +9,14 @@ where
     }
 }
 
-impl<S> IndexMut<usize> for Matrix<S>
-where
-    S: Scalar,
-{
+impl<S> IndexMut<usize> for Matrix<S> {
     /// Get a row of the matrix mutably
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         self.row_mut(index)
     }
 }
 
-impl<S> Index<[usize; 2]> for Matrix<S>
-where
-    S: Scalar,
-{
+impl<S> Index<[usize; 2]> for Matrix<S> {
     type Output = S;
 
     /// Get a row of the matrix.
@@ -35,10 +26,7 @@ where
     }
 }
 
-impl<S> IndexMut<[usize; 2]> for Matrix<S>
-where
-    S: Scalar,
-{
+impl<S> IndexMut<[usize; 2]> for Matrix<S> {
     /// Get a row of the matrix.
     fn index_mut(&mut self, [row, col]: [usize; 2]) -> &mut Self::Output {
         let index = self.dimensions.row_major(row, col);
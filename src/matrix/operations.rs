@@ -1,75 +1,186 @@
 use super::*;
-use crate::vector::{operations::dot, Vector};
+use crate::{
+    traits::Semiring,
+    vector::{
+        operations::{dot, semiring_dot},
+        Vector,
+    },
+};
+
+/// The dimensions of the result of an elementwise operation.
+fn broadcast(lhs: Dimensions, rhs: Dimensions) -> Dimensions {
+    match lhs.broadcast(&rhs) {
+        Some(dimensions) => dimensions,
+        None => panic!(
+            "Matrix dimensions must agree or be 1. Left hand side is of size {} and right hand side is of size {}",
+            lhs, rhs
+        ),
+    }
+}
 
-macro_rules! assert_equal_dimensions {
-    ($lhs:expr, $rhs:expr) => {
-        let lhs = $lhs.dimensions;
-        let rhs = $rhs.dimensions;
-        assert!(
-            lhs == rhs,
-            "Matrix dimensions must agree. Left hand side is of size {} and right hand side is of size {}",
-            lhs,
-            rhs
-        )
+/// Combine the elements of two matrices, stretching any row or column of size 1 to match
+/// the other side.
+fn zip_broadcast<S, F>(lhs: &Matrix<S>, rhs: &Matrix<S>, mut f: F) -> Matrix<S>
+where
+    S: Clone,
+    F: FnMut(S, S) -> S,
+{
+    let dimensions = broadcast(lhs.dimensions, rhs.dimensions);
+    let index = |m: &Matrix<S>, row: usize, col: usize| {
+        let row = if m.dimensions.rows == 1 { 0 } else { row };
+        let col = if m.dimensions.cols == 1 { 0 } else { col };
+        m.dimensions.row_major(row, col)
+    };
+
+    let mut elements = Vec::with_capacity(dimensions.elements());
+    for row in 0..dimensions.rows {
+        for col in 0..dimensions.cols {
+            let a = lhs.elements[index(lhs, row, col)].clone();
+            let b = rhs.elements[index(rhs, row, col)].clone();
+            elements.push(f(a, b));
+        }
     }
+
+    Matrix::from_row_major(dimensions, elements)
 }
 
 impl<S> Matrix<S>
 where
-    S: Scalar,
+    S: Clone,
 {
     /// Returns the transpose of a matrix
     pub fn transpose(&self) -> Matrix<S> {
-        let mut out = Matrix::zeros(self.dimensions.transpose());
+        let mut elements = Vec::with_capacity(self.dimensions.elements());
 
         for col in 0..self.dimensions.cols {
             for row in 0..self.dimensions.rows {
-                out[[col, row]] = self[[row, col]];
+                elements.push(self[[row, col]].clone());
             }
         }
 
-        out
+        Matrix::from_row_major(self.dimensions.transpose(), elements)
+    }
+
+    /// Multiply two matrices where addition and multiplication are those of the semiring `R`.
+    ///
+    /// The elements are converted into `R` before multiplying, so for a distance matrix
+    /// `d: Matrix<f64>`, `d.mul_in::<MinPlus<f64>>(&d)` gives the shortest paths of at
+    /// most two edges.
+    /// ```
+    /// # use land::{mat, Matrix, MinPlus};
+    /// # fn main() {
+    /// let inf = f64::INFINITY;
+    /// let d = mat![[0.0, 1.0, inf], [inf, 0.0, 2.0], [inf, inf, 0.0]];
+    ///
+    /// let paths = d.mul_in::<MinPlus<f64>>(&d).map(|MinPlus(x)| x);
+    ///
+    /// assert_eq!(paths, mat![[0.0, 1.0, 3.0], [inf, 0.0, 2.0], [inf, inf, 0.0]]);
+    /// # }
+    /// ```
+    pub fn mul_in<R>(&self, rhs: &Matrix<S>) -> Matrix<R>
+    where
+        R: Semiring + From<S>,
+    {
+        let lhs = self.clone().map(R::from);
+        let rhs = rhs.clone().map(R::from);
+        semiring_mul(&lhs, &rhs)
     }
 }
 
-// Matrix-Matrix Multiplication
-impl<S> Mul<Self> for &Matrix<S>
+impl<R> Matrix<R>
 where
-    S: Scalar,
+    R: Semiring,
 {
-    type Output = Matrix<S>;
-
-    // Standard matrix multiplication
-    fn mul(self, rhs: Self) -> Matrix<S> {
+    /// Returns the closure (Kleene star) of a square matrix: `I + A + A^2 + A^3 + ...`,
+    /// computed with the Floyd–Warshall algorithm.
+    ///
+    /// Over `MinPlus` this gives all pairs shortest paths and over `Boolean` the
+    /// transitive reflexive closure. The semiring is assumed to have no cycles that keep
+    /// improving a path, such as negative cycles in `MinPlus`.
+    pub fn closure(&self) -> Matrix<R> {
         assert!(
-            self.dimensions.cols == rhs.dimensions.rows,
-            "Matrix dimensions must agree. Left hand side is {} and right hand side is {}",
-            self.dimensions,
-            rhs.dimensions,
+            self.dimensions.rows == self.dimensions.cols,
+            "Matrix must be square. Matrix is of size {}",
+            self.dimensions
         );
 
-        let rhs_transpose = rhs.transpose();
-
-        let out_dimensions = Dimensions {
-            rows: self.dimensions.rows,
-            cols: rhs.dimensions.cols,
-        };
-        let mut out = Matrix::zeros(out_dimensions);
-
-        for row in 0..out_dimensions.rows {
-            let lhs_row = &self[row];
+        let size = self.dimensions.rows;
+        let mut out = self.clone();
 
-            for col in 0..out_dimensions.cols {
-                let rhs_col = &rhs_transpose[col];
+        for k in 0..size {
+            for i in 0..size {
+                let via = out[i][k].clone();
 
-                out[row][col] = dot(lhs_row, rhs_col);
+                for j in 0..size {
+                    let path = via.times(&out[k][j]);
+                    out[i][j] = out[i][j].plus(&path);
+                }
             }
         }
 
+        for i in 0..size {
+            out[i][i] = out[i][i].plus(&R::multiplicative_identity());
+        }
+
         out
     }
 }
 
+/// Matrix multiplication where addition and multiplication are those of a semiring.
+fn semiring_mul<R>(lhs: &Matrix<R>, rhs: &Matrix<R>) -> Matrix<R>
+where
+    R: Semiring,
+{
+    product(lhs, rhs, semiring_dot)
+}
+
+/// Matrix multiplication with each element computed by `dot` of a row and a column.
+fn product<S, F>(lhs: &Matrix<S>, rhs: &Matrix<S>, dot: F) -> Matrix<S>
+where
+    S: Clone,
+    F: Fn(&[S], &[S]) -> S,
+{
+    assert!(
+        lhs.dimensions.cols == rhs.dimensions.rows,
+        "Matrix dimensions must agree. Left hand side is {} and right hand side is {}",
+        lhs.dimensions,
+        rhs.dimensions,
+    );
+
+    let rhs_transpose = rhs.transpose();
+
+    let out_dimensions = Dimensions {
+        rows: lhs.dimensions.rows,
+        cols: rhs.dimensions.cols,
+    };
+    let mut elements = Vec::with_capacity(out_dimensions.elements());
+
+    for row in 0..out_dimensions.rows {
+        let lhs_row = &lhs[row];
+
+        for col in 0..out_dimensions.cols {
+            let rhs_col = &rhs_transpose[col];
+
+            elements.push(dot(lhs_row, rhs_col));
+        }
+    }
+
+    Matrix::from_row_major(out_dimensions, elements)
+}
+
+// Matrix-Matrix Multiplication
+impl<S> Mul<Self> for &Matrix<S>
+where
+    S: Scalar,
+{
+    type Output = Matrix<S>;
+
+    // Standard matrix multiplication
+    fn mul(self, rhs: Self) -> Matrix<S> {
+        product(self, rhs, dot)
+    }
+}
+
 // Matrix-Vector Multiplication
 impl<S> Mul<&Vector<S>> for &Matrix<S>
 where
@@ -91,7 +202,7 @@ where
         for row in 0..self.dimensions.rows {
             let lhs_row = &self[row];
 
-            out[row] = dot(lhs_row, &rhs);
+            out[row] = dot(lhs_row, rhs);
         }
 
         out.into()
@@ -119,6 +230,8 @@ impl<S: Scalar> Mul<Vector<S>> for &Matrix<S> {
     }
 }
 
+// Elementwise operators broadcast, so a row or column of size 1 is repeated to match the
+// other side.
 macro_rules! impl_elementwise_operator {
     ($trait:ident, $fn:ident, $fn_assign:ident) => {
         impl<S> $trait<&Matrix<S>> for Matrix<S>
@@ -128,8 +241,12 @@ macro_rules! impl_elementwise_operator {
             type Output = Matrix<S>;
 
             fn $fn(mut self, rhs: &Matrix<S>) -> Self::Output {
-                self.$fn_assign(rhs);
-                self
+                if broadcast(self.dimensions, rhs.dimensions) == self.dimensions {
+                    self.$fn_assign(rhs);
+                    self
+                } else {
+                    zip_broadcast(&self, rhs, |a, b| a.$fn(b))
+                }
             }
         }
 
@@ -140,9 +257,7 @@ macro_rules! impl_elementwise_operator {
             type Output = Matrix<S>;
 
             fn $fn(self, rhs: &Matrix<S>) -> Self::Output {
-                let mut tmp = self.clone();
-                tmp.$fn_assign(rhs);
-                tmp
+                zip_broadcast(self, rhs, |a, b| a.$fn(b))
             }
         }
 
@@ -152,9 +267,8 @@ macro_rules! impl_elementwise_operator {
         {
             type Output = Matrix<S>;
 
-            fn $fn(mut self, rhs: Matrix<S>) -> Self::Output {
-                self.$fn_assign(&rhs);
-                self
+            fn $fn(self, rhs: Matrix<S>) -> Self::Output {
+                self.$fn(&rhs)
             }
         }
 
@@ -165,9 +279,7 @@ macro_rules! impl_elementwise_operator {
             type Output = Matrix<S>;
 
             fn $fn(self, rhs: Matrix<S>) -> Self::Output {
-                let mut tmp = self.clone();
-                tmp.$fn_assign(&rhs);
-                tmp
+                self.$fn(&rhs)
             }
         }
     };
@@ -191,11 +303,29 @@ macro_rules! impl_elementwise_assign {
         where
             S: Scalar,
         {
+            /// Update every element, where the right hand side may have a single row or
+            /// column that is repeated.
             fn $fn(&mut self, rhs: &Matrix<S>) {
-                assert_equal_dimensions!(self, rhs);
+                let dimensions = self.dimensions;
+                assert!(
+                    broadcast(dimensions, rhs.dimensions) == dimensions,
+                    "Matrix dimensions must agree or be 1 on the right hand side. Left hand side is of size {} and right hand side is of size {}",
+                    dimensions,
+                    rhs.dimensions
+                );
+
+                if rhs.dimensions == dimensions {
+                    for (a, b) in self.elements.iter_mut().zip(rhs.elements.iter()) {
+                        a.$fn(b.clone())
+                    }
+                    return;
+                }
 
-                for (a, b) in self.elements.iter_mut().zip(rhs.elements.iter()) {
-                    a.$fn(*b)
+                for row in 0..dimensions.rows {
+                    let rhs_row = rhs.row(if rhs.dimensions.rows == 1 { 0 } else { row });
+                    for (col, a) in self.row_mut(row).iter_mut().enumerate() {
+                        a.$fn(rhs_row[if rhs_row.len() == 1 { 0 } else { col }].clone())
+                    }
                 }
             }
         }
@@ -205,38 +335,153 @@ macro_rules! impl_elementwise_assign {
 impl_elementwise_assign!(AddAssign, add_assign);
 impl_elementwise_assign!(SubAssign, sub_assign);
 
-macro_rules! impl_scalar_operators {
-    ($trait:ident, $fn:ident, ($($scalar:ty),+)) => (
-        $(
-            impl_scalar_operators!($trait, $fn, $scalar);
-        )+
-    );
+impl<S> Matrix<S>
+where
+    S: Scalar,
+{
+    /// Multiply every element by a scalar.
+    pub fn scale(&self, factor: S) -> Matrix<S> {
+        self * factor
+    }
 
-    ($trait:ident, $fn:ident, $scalar:ty) => (
-        impl $trait<$scalar> for Matrix<$scalar> {
-            type Output = Matrix<$scalar>;
+    /// Add a scalar to every element.
+    pub fn add_scalar(&self, value: S) -> Matrix<S> {
+        self + value
+    }
+
+    /// The elementwise (Hadamard) product of two matrices of the same size, or where a row
+    /// or column of size 1 is repeated to match the other side.
+    pub fn hadamard(&self, rhs: &Matrix<S>) -> Matrix<S> {
+        zip_broadcast(self, rhs, |a, b| a * b)
+    }
+
+    /// The Khatri–Rao product: the Kronecker product of each pair of columns.
+    ///
+    /// For an `I x R` matrix `a` and a `J x R` matrix `b` the result is `IJ x R`, with
+    /// `a.khatri_rao(&b)[i * J + j][r] == a[i][r] * b[j][r]`.
+    pub fn khatri_rao(&self, rhs: &Matrix<S>) -> Matrix<S> {
+        assert!(
+            self.dimensions.cols == rhs.dimensions.cols,
+            "Matrices must have the same number of columns. Left hand side is {} and right hand side is {}",
+            self.dimensions,
+            rhs.dimensions,
+        );
+
+        let dimensions = Dimensions {
+            rows: self.dimensions.rows * rhs.dimensions.rows,
+            cols: self.dimensions.cols,
+        };
+        let mut elements = Vec::with_capacity(dimensions.elements());
+        for i in 0..self.dimensions.rows {
+            for j in 0..rhs.dimensions.rows {
+                for (a, b) in self.row(i).iter().zip(rhs.row(j)) {
+                    elements.push(a.clone() * b.clone());
+                }
+            }
+        }
+
+        Matrix::from_row_major(dimensions, elements)
+    }
+
+    /// The sum of each column, as a single row.
+    pub fn sum_cols(&self) -> Matrix<S> {
+        let mut sums = vec![S::zero(); self.dimensions.cols];
+        for row in 0..self.dimensions.rows {
+            for (sum, e) in sums.iter_mut().zip(self.row(row)) {
+                *sum += e.clone();
+            }
+        }
+        Matrix::from_row_major((1, self.dimensions.cols).into(), sums)
+    }
+
+    /// The sum of each row, as a single column.
+    pub fn sum_rows(&self) -> Matrix<S> {
+        let sums = (0..self.dimensions.rows)
+            .map(|row| {
+                self.row(row)
+                    .iter()
+                    .fold(S::zero(), |sum, e| sum + e.clone())
+            })
+            .collect();
+        Matrix::from_row_major((self.dimensions.rows, 1).into(), sums)
+    }
+
+    /// The mean of each column, as a single row, so that `&m - &m.mean_cols()` centers
+    /// each column.
+    pub fn mean_cols(&self) -> Matrix<S> {
+        self.sum_cols() / count(self.dimensions.rows)
+    }
+
+    /// The mean of each row, as a single column.
+    pub fn mean_rows(&self) -> Matrix<S> {
+        self.sum_rows() / count(self.dimensions.cols)
+    }
+}
+
+/// A count as a scalar.
+fn count<S: Scalar>(n: usize) -> S {
+    (0..n).fold(S::zero(), |count, _| count + S::one())
+}
 
-            fn $fn(mut self, rhs: $scalar) -> Self::Output {
-                self.elements.iter_mut().for_each(|e| *e = e.$fn(rhs));
+macro_rules! impl_scalar_operators {
+    ($trait:ident, $fn:ident, $assign_trait:ident, $fn_assign:ident) => {
+        impl<S> $trait<S> for Matrix<S>
+        where
+            S: Scalar,
+        {
+            type Output = Matrix<S>;
+
+            fn $fn(mut self, rhs: S) -> Self::Output {
+                self.$fn_assign(rhs);
                 self
             }
         }
 
-        impl $trait<$scalar> for &Matrix<$scalar> {
-            type Output = Matrix<$scalar>;
+        impl<S> $trait<S> for &Matrix<S>
+        where
+            S: Scalar,
+        {
+            type Output = Matrix<S>;
 
-            fn $fn(self, rhs: $scalar) -> Self::Output {
-                let mut tmp = self.clone();
-                tmp.elements.iter_mut().for_each(|e| *e = e.$fn(rhs));
-                tmp
+            fn $fn(self, rhs: S) -> Self::Output {
+                self.clone().$fn(rhs)
             }
         }
 
+        impl<S> $assign_trait<S> for Matrix<S>
+        where
+            S: Scalar,
+        {
+            fn $fn_assign(&mut self, rhs: S) {
+                for e in self.elements.iter_mut() {
+                    e.$fn_assign(rhs.clone())
+                }
+            }
+        }
+    };
+}
+
+impl_scalar_operators!(Add, add, AddAssign, add_assign);
+impl_scalar_operators!(Sub, sub, SubAssign, sub_assign);
+impl_scalar_operators!(Mul, mul, MulAssign, mul_assign);
+impl_scalar_operators!(Div, div, DivAssign, div_assign);
+
+// The orphan rules forbid `impl<S> Mul<Matrix<S>> for S`, so scalars on the left hand
+// side are only supported for the primitive types. The scalar is the left operand, so
+// `10 - m` subtracts every element from 10; this used to compute `m - 10`, see `Matrix`.
+macro_rules! impl_scalar_lhs_operators {
+    ($trait:ident, $fn:ident, ($($scalar:ty),+)) => (
+        $(
+            impl_scalar_lhs_operators!($trait, $fn, $scalar);
+        )+
+    );
+
+    ($trait:ident, $fn:ident, $scalar:ty) => (
         impl $trait<Matrix<$scalar>> for $scalar {
             type Output = Matrix<$scalar>;
 
             fn $fn(self, mut rhs: Matrix<$scalar>) -> Self::Output {
-                rhs.elements.iter_mut().for_each(|e| *e = e.$fn(self));
+                rhs.elements.iter_mut().for_each(|e| *e = self.$fn(*e));
                 rhs
             }
         }
@@ -245,37 +490,38 @@ macro_rules! impl_scalar_operators {
             type Output = Matrix<$scalar>;
 
             fn $fn(self, rhs: &Matrix<$scalar>) -> Self::Output {
-                let mut rhs = rhs.clone();
-                rhs.elements.iter_mut().for_each(|e| *e = e.$fn(self));
-                rhs
+                self.$fn(rhs.clone())
             }
         }
     );
 }
 
-impl_scalar_operators!(
+impl_scalar_lhs_operators!(
     Add,
     add,
     (i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
 );
-impl_scalar_operators!(
+impl_scalar_lhs_operators!(
     Sub,
     sub,
     (i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
 );
-impl_scalar_operators!(
+impl_scalar_lhs_operators!(
     Mul,
     mul,
     (i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
 );
-impl_scalar_operators!(
+impl_scalar_lhs_operators!(
     Div,
     div,
     (i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
 );
 
 #[cfg(test)]
+#[allow(clippy::identity_op)]
 mod tests {
+    use crate::{Boolean, Matrix, MaxMin, MaxTimes, MinPlus};
+
     #[test]
     fn transpose_square() {
         let a = mat![[1, 2, 3], [4, 5, 6], [7, 8, 9]];
@@ -329,4 +575,183 @@ mod tests {
 
         assert_eq!(result, mat![1 * 1 + 2 * 2 + 3 * 3, 4 * 1 + 5 * 2 + 6 * 3]);
     }
+
+    #[test]
+    fn hadamard_product() {
+        let a = mat![[1, 2], [3, 4]];
+        let b = mat![[5, 6], [7, 8]];
+
+        assert_eq!(a.hadamard(&b), mat![[5, 12], [21, 32]]);
+    }
+
+    #[test]
+    fn broadcast_rows_and_columns() {
+        let m = mat![[1.0, 2.0, 3.0], [3.0, 6.0, 9.0]];
+
+        assert_eq!(m.mean_cols(), mat![[2.0, 4.0, 6.0]]);
+        assert_eq!(m.sum_rows(), mat![[6.0], [18.0]]);
+        assert_eq!(
+            &m - &m.mean_cols(),
+            mat![[-1.0, -2.0, -3.0], [1.0, 2.0, 3.0]]
+        );
+        assert_eq!(
+            m.clone() - m.mean_rows(),
+            mat![[-1.0, 0.0, 1.0], [-3.0, 0.0, 3.0]]
+        );
+
+        let bias = mat![10.0, 20.0, 30.0];
+        assert_eq!(
+            m + bias.as_row(),
+            mat![[11.0, 22.0, 33.0], [13.0, 26.0, 39.0]]
+        );
+
+        // A column and a row stretch to a full matrix
+        let column = mat![1, 2].as_column();
+        assert_eq!(
+            &column + mat![10, 20, 30].as_row(),
+            mat![[11, 21, 31], [12, 22, 32]]
+        );
+        assert_eq!(column.hadamard(&mat![[1, -1]]), mat![[1, -1], [2, -2]]);
+
+        let mut a = mat![[1, 2], [3, 4]];
+        a -= mat![[1, 2]];
+        a += &column;
+        assert_eq!(a, mat![[1, 1], [4, 4]]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Matrix dimensions must agree or be 1. Left hand side is of size 2x3 and right hand side is of size 3x1"
+    )]
+    fn broadcast_incompatible() {
+        let _ = Matrix::<i32>::zeros((2, 3).into()) - Matrix::zeros((3, 1).into());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Matrix dimensions must agree or be 1 on the right hand side. Left hand side is of size 1x3 and right hand side is of size 2x3"
+    )]
+    fn broadcast_assign_cannot_grow() {
+        let mut a = Matrix::<i32>::zeros((1, 3).into());
+        a += Matrix::zeros((2, 3).into());
+    }
+
+    #[test]
+    fn khatri_rao_product() {
+        let a = mat![[1, 2], [3, 4]];
+        let b = mat![[1, -1], [0, 2], [5, 1]];
+
+        assert_eq!(
+            a.khatri_rao(&b),
+            mat![[1, -2], [0, 4], [5, 2], [3, -4], [0, 8], [15, 4]]
+        );
+    }
+
+    #[test]
+    fn scalar_operators() {
+        let a = mat![[1, 2], [3, 4]];
+
+        assert_eq!(&a * 2, mat![[2, 4], [6, 8]]);
+        assert_eq!(10 - &a, mat![[9, 8], [7, 6]]);
+        assert_eq!(12 / a.clone(), mat![[12, 6], [4, 3]]);
+        assert_eq!(a.add_scalar(1), mat![[2, 3], [4, 5]]);
+    }
+
+    #[test]
+    fn scalar_lhs_is_left_operand() {
+        // A scalar on the left is the left operand, as for vectors: `10 - m` is not `m - 10`
+        let a = mat![[1, 2], [4, 8]];
+
+        assert_eq!(10 - a.clone(), mat![[9, 8], [6, 2]]);
+        assert_eq!(&a - 10, mat![[-9, -8], [-6, -2]]);
+        assert_eq!(8 / a.clone(), mat![[8, 4], [2, 1]]);
+        assert_eq!(8.0 / &mat![[2.0, 4.0]], mat![[4.0, 2.0]]);
+    }
+
+    #[test]
+    fn scalar_assign_operators() {
+        let mut a = mat![[1.0, 2.0], [3.0, 4.0]];
+
+        a *= 2.0;
+        a -= 1.0;
+        a /= 2.0;
+        a += 0.5;
+
+        assert_eq!(a, mat![[1.0, 2.0], [3.0, 4.0]]);
+    }
+
+    #[test]
+    fn generic_scalar_operators() {
+        use num::{rational::Ratio, Complex};
+
+        let r = Ratio::new;
+        let a = mat![[r(1, 2), r(1, 3)], [r(2, 1), r(0, 1)]];
+        assert_eq!(a * r(3, 2), mat![[r(3, 4), r(1, 2)], [r(3, 1), r(0, 1)]]);
+
+        let i = Complex::new(0.0, 1.0);
+        let mut b = mat![[Complex::new(1.0, 0.0), i]];
+        b *= i;
+        assert_eq!(b.scale(i), mat![[-Complex::new(1.0, 0.0), -i]]);
+    }
+
+    #[test]
+    fn min_plus_closure_shortest_paths() {
+        let inf = f64::INFINITY;
+        let d = mat![
+            [0.0, 4.0, 1.0, inf],
+            [inf, 0.0, inf, 1.0],
+            [inf, 2.0, 0.0, 6.0],
+            [inf, inf, inf, 0.0]
+        ];
+
+        let result = d.map(MinPlus).closure().map(|MinPlus(x)| x);
+
+        assert_eq!(
+            result,
+            mat![
+                [0.0, 3.0, 1.0, 4.0],
+                [inf, 0.0, inf, 1.0],
+                [inf, 2.0, 0.0, 3.0],
+                [inf, inf, inf, 0.0]
+            ]
+        );
+    }
+
+    #[test]
+    fn max_min_widest_path() {
+        // Missing edges are negative infinity, and staying in place is unbounded
+        let inf = f64::INFINITY;
+        let w = mat![[inf, 5.0, 2.0], [-inf, inf, 3.0], [-inf, -inf, inf]];
+
+        let result = w.mul_in::<MaxMin<f64>>(&w).map(|MaxMin(x)| x);
+
+        assert_eq!(
+            result,
+            mat![[inf, 5.0, 3.0], [-inf, inf, 3.0], [-inf, -inf, inf]]
+        );
+    }
+
+    #[test]
+    fn max_times_most_likely_step() {
+        let p = mat![[0.5, 0.5], [0.25, 0.75]];
+
+        let result = p.mul_in::<MaxTimes<f64>>(&p).map(|MaxTimes(x)| x);
+
+        assert_eq!(result, mat![[0.25, 0.375], [0.1875, 0.5625]]);
+    }
+
+    #[test]
+    fn boolean_reachability() {
+        let adjacency = mat![
+            [false, true, false],
+            [false, false, true],
+            [false, false, false]
+        ]
+        .map(Boolean);
+
+        let result = adjacency.closure().map(|Boolean(b)| b);
+
+        assert_eq!(result.row(0), &[true, true, true],);
+        assert_eq!(result.row(2), &[false, false, true]);
+    }
 }
@@ -0,0 +1,124 @@
+use super::*;
+use serde::{de::Error, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Matrices are encoded as `{ rows, cols, data }` where `data` holds the elements in row
+/// major order.
+impl<S> Serialize for Matrix<S>
+where
+    S: Serialize,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Matrix", 3)?;
+        state.serialize_field("rows", &self.dimensions.rows)?;
+        state.serialize_field("cols", &self.dimensions.cols)?;
+        state.serialize_field("data", &self.elements)?;
+        state.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Matrix")]
+struct RawMatrix<S> {
+    rows: usize,
+    cols: usize,
+    data: Vec<S>,
+}
+
+/// Fails if the number of elements does not match the dimensions.
+impl<'de, S> Deserialize<'de> for Matrix<S>
+where
+    S: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Matrix<S>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let RawMatrix { rows, cols, data } = RawMatrix::deserialize(deserializer)?;
+        let dimensions = Dimensions { rows, cols };
+
+        match rows.checked_mul(cols) {
+            Some(len) if len == data.len() => {}
+            Some(len) => {
+                return Err(D::Error::custom(format!(
+                    "Matrix of size {} must have {} elements, found {}",
+                    dimensions,
+                    len,
+                    data.len()
+                )))
+            }
+            None => {
+                return Err(D::Error::custom(format!(
+                    "Matrix of size {} is too large",
+                    dimensions
+                )))
+            }
+        }
+
+        Ok(Matrix {
+            elements: data,
+            dimensions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector::Vector;
+
+    #[test]
+    fn json_layout() {
+        let a = mat![[1.0, 2.5], [3.0, -4.0], [5.0, 6.0]];
+
+        let json = serde_json::to_string(&a).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"rows":3,"cols":2,"data":[1.0,2.5,3.0,-4.0,5.0,6.0]}"#
+        );
+        assert_eq!(serde_json::from_str::<Matrix<f64>>(&json).unwrap(), a);
+    }
+
+    #[test]
+    fn rejects_wrong_element_count() {
+        let json = r#"{"rows":2,"cols":2,"data":[1,2,3]}"#;
+
+        let error = serde_json::from_str::<Matrix<i32>>(json).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("Matrix of size 2x2 must have 4 elements, found 3"));
+
+        let json = r#"{"rows":4294967296,"cols":4294967296,"data":[]}"#;
+        let error = serde_json::from_str::<Matrix<i32>>(json).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Matrix of size 4294967296x4294967296 is too large"));
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let a = mat![[1i64, -2], [3, 4]];
+        let v = mat![0.5f32, 1.5];
+
+        let a_bytes = bincode::serialize(&a).unwrap();
+        let v_bytes = bincode::serialize(&v).unwrap();
+
+        assert_eq!(bincode::deserialize::<Matrix<i64>>(&a_bytes).unwrap(), a);
+        assert_eq!(bincode::deserialize::<Vector<f32>>(&v_bytes).unwrap(), v);
+    }
+
+    #[test]
+    fn vector_and_dimensions() {
+        let v = mat![1, 2, 3];
+
+        assert_eq!(serde_json::to_string(&v).unwrap(), "[1,2,3]");
+        assert_eq!(
+            serde_json::from_str::<Dimensions>(r#"{"rows":4,"cols":5}"#).unwrap(),
+            Dimensions { rows: 4, cols: 5 }
+        );
+    }
+}
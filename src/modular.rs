@@ -0,0 +1,284 @@
+mod gf2;
+
+pub use self::gf2::*;
+
+use crate::traits::Field;
+use num::{traits::Pow, Num, One, Zero};
+use std::{fmt, ops::*};
+
+/// An integer modulo `P`, where `P` is a prime, forming the finite field GF(P).
+///
+/// Arithmetic wraps around modulo `P` and is exact. Division multiplies by the
+/// inverse, which only exists for every non-zero element when `P` is prime.
+///
+/// A modulus below 2 fails to compile.
+/// ```compile_fail
+/// # use land::Zp;
+/// let _ = Zp::<1>::new(3);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Zp<const P: u64>(u64);
+
+/// The field with two elements, where addition is XOR and multiplication is AND.
+pub type Gf2 = Zp<2>;
+
+impl<const P: u64> Zp<P> {
+    /// `P`, checked at compile time to be at least 2. Every use of `P` in arithmetic
+    /// goes through this.
+    const MODULUS: u64 = {
+        assert!(P >= 2, "P must be a prime, so at least 2");
+        P
+    };
+
+    /// Create a new element, reducing the value modulo `P`.
+    pub fn new(value: u64) -> Zp<P> {
+        Zp(value % Self::MODULUS)
+    }
+
+    /// The representative of this element in `0..P`.
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    /// Raise the element to an integer power.
+    pub fn pow(self, mut exp: u64) -> Zp<P> {
+        let mut base = self;
+        let mut out = Zp::one();
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                out *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+
+        out
+    }
+
+    /// The multiplicative inverse, or `None` for zero.
+    pub fn inverse(self) -> Option<Zp<P>> {
+        if self.is_zero() {
+            None
+        } else {
+            // Fermat's little theorem: a^(p-1) = 1
+            Some(self.pow(Self::MODULUS - 2))
+        }
+    }
+}
+
+impl<const P: u64> Field for Zp<P> {}
+
+impl<const P: u64> From<u64> for Zp<P> {
+    fn from(value: u64) -> Zp<P> {
+        Zp::new(value)
+    }
+}
+
+impl<const P: u64> From<i64> for Zp<P> {
+    fn from(value: i64) -> Zp<P> {
+        Zp::from(value as i128)
+    }
+}
+
+// Reduced in i128, since `P` may be larger than `i64::MAX`
+impl<const P: u64> From<i128> for Zp<P> {
+    fn from(value: i128) -> Zp<P> {
+        Zp(value.rem_euclid(Self::MODULUS as i128) as u64)
+    }
+}
+
+impl<const P: u64> Zero for Zp<P> {
+    fn zero() -> Zp<P> {
+        Zp(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl<const P: u64> One for Zp<P> {
+    fn one() -> Zp<P> {
+        Zp(1 % Self::MODULUS)
+    }
+}
+
+impl<const P: u64> Num for Zp<P> {
+    type FromStrRadixErr = std::num::ParseIntError;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Zp<P>, Self::FromStrRadixErr> {
+        i128::from_str_radix(s, radix).map(Zp::from)
+    }
+}
+
+impl<const P: u64> Add for Zp<P> {
+    type Output = Zp<P>;
+
+    fn add(self, rhs: Zp<P>) -> Zp<P> {
+        Zp(((self.0 as u128 + rhs.0 as u128) % Self::MODULUS as u128) as u64)
+    }
+}
+
+impl<const P: u64> Sub for Zp<P> {
+    type Output = Zp<P>;
+
+    fn sub(self, rhs: Zp<P>) -> Zp<P> {
+        self + -rhs
+    }
+}
+
+impl<const P: u64> Mul for Zp<P> {
+    type Output = Zp<P>;
+
+    fn mul(self, rhs: Zp<P>) -> Zp<P> {
+        Zp(((self.0 as u128 * rhs.0 as u128) % Self::MODULUS as u128) as u64)
+    }
+}
+
+impl<const P: u64> Div for Zp<P> {
+    type Output = Zp<P>;
+
+    fn div(self, rhs: Zp<P>) -> Zp<P> {
+        match rhs.inverse() {
+            Some(inverse) => self.mul(inverse),
+            None => panic!("attempt to divide by zero in GF({})", P),
+        }
+    }
+}
+
+/// In a field every non-zero element divides every other, so the remainder is always zero.
+impl<const P: u64> Rem for Zp<P> {
+    type Output = Zp<P>;
+
+    fn rem(self, rhs: Zp<P>) -> Zp<P> {
+        if rhs.is_zero() {
+            panic!(
+                "attempt to calculate the remainder with a divisor of zero in GF({})",
+                P
+            )
+        }
+        Zp::zero()
+    }
+}
+
+impl<const P: u64> Neg for Zp<P> {
+    type Output = Zp<P>;
+
+    fn neg(self) -> Zp<P> {
+        if self.0 == 0 {
+            self
+        } else {
+            Zp(Self::MODULUS - self.0)
+        }
+    }
+}
+
+impl<const P: u64> Pow<u64> for Zp<P> {
+    type Output = Zp<P>;
+
+    fn pow(self, exp: u64) -> Zp<P> {
+        Zp::pow(self, exp)
+    }
+}
+
+impl_assign_operators!([const P: u64] Zp<P>);
+
+impl<const P: u64> fmt::Display for Zp<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Matrix;
+
+    type Z7 = Zp<7>;
+
+    fn z7(values: Vec<Vec<i64>>) -> Matrix<Z7> {
+        Matrix::new(values).map(Z7::from)
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = Z7::new(5);
+        let b = Z7::new(4);
+
+        assert_eq!(a + b, Z7::new(2));
+        assert_eq!(a - b, Z7::new(1));
+        assert_eq!(b - a, Z7::new(6));
+        assert_eq!(a * b, Z7::new(6));
+        assert_eq!(a / b * b, a);
+        assert_eq!(-a, Z7::new(2));
+        assert_eq!(Z7::from(-1i64), Z7::new(6));
+    }
+
+    #[test]
+    fn inverse_of_every_element() {
+        for value in 1..7 {
+            let a = Z7::new(value);
+            assert_eq!(a * a.inverse().unwrap(), Z7::one());
+        }
+        assert!(Z7::zero().inverse().is_none());
+    }
+
+    #[test]
+    fn large_modulus_does_not_overflow() {
+        type Big = Zp<18446744073709551557>;
+        let a = Big::new(18446744073709551556);
+
+        assert_eq!(a * a, Big::one());
+        assert_eq!(a + a, Big::new(18446744073709551555));
+    }
+
+    #[test]
+    fn signed_values_with_large_modulus() {
+        type Big = Zp<18446744073709551557>;
+
+        assert_eq!(Big::from(-1i64), Big::new(18446744073709551556));
+        assert_eq!(
+            Big::from(i64::MIN),
+            Big::new(18446744073709551557 - (1 << 63))
+        );
+        assert_eq!(
+            Big::from_str_radix("-2", 10),
+            Ok(Big::new(18446744073709551555))
+        );
+        assert_eq!(
+            Big::from_str_radix("18446744073709551558", 10),
+            Ok(Big::one())
+        );
+    }
+
+    #[test]
+    fn matrix_inverse_mod_p() {
+        let a = z7(vec![vec![1, 2], vec![3, 4]]);
+
+        let inverse = a.inverse().unwrap();
+
+        assert_eq!(&a * &inverse, Matrix::identity(2));
+        assert_eq!(a.determinant(), Z7::from(-2i64));
+    }
+
+    #[test]
+    fn singular_only_mod_p() {
+        // Determinant is 7, so the matrix is invertible over the rationals but not GF(7)
+        let a = z7(vec![vec![3, 1], vec![1, 5]]);
+
+        assert_eq!(a.determinant(), Z7::zero());
+        assert_eq!(a.rank(), 1);
+        assert!(a.inverse().is_none());
+    }
+
+    #[test]
+    fn null_space_mod_p() {
+        let a = z7(vec![vec![1, 2, 3], vec![2, 4, 6]]);
+
+        let null = a.null_space();
+
+        assert_eq!(null.dim(), (3, 2).into());
+        assert_eq!(&a * &null, Matrix::zeros((2, 2).into()));
+    }
+}
@@ -0,0 +1,389 @@
+use super::*;
+use crate::{
+    matrix::{Dimensions, Rref},
+    vector::Vector,
+    Matrix,
+};
+
+const WORD_BITS: usize = 64;
+
+/// A matrix over GF(2) where every row is packed into 64-bit words.
+///
+/// Adding one row to another is a XOR of whole words, which makes elimination roughly 64
+/// times faster than using `Matrix<Gf2>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Gf2Matrix {
+    words: Vec<u64>,
+    words_per_row: usize,
+    dimensions: Dimensions,
+}
+
+impl Gf2Matrix {
+    /// A matrix filled with zeros
+    pub fn zeros(dimensions: Dimensions) -> Gf2Matrix {
+        let words_per_row = dimensions.cols.div_ceil(WORD_BITS);
+
+        Gf2Matrix {
+            words: vec![0; words_per_row * dimensions.rows],
+            words_per_row,
+            dimensions,
+        }
+    }
+
+    /// A square matrix with ones along the diagonal and zeros everywhere else.
+    pub fn identity(size: usize) -> Gf2Matrix {
+        let mut mat = Self::zeros(Dimensions::square(size));
+
+        for i in 0..size {
+            mat.set(i, i, true);
+        }
+
+        mat
+    }
+
+    /// Get the dimensions of a matrix
+    pub fn dim(&self) -> Dimensions {
+        self.dimensions
+    }
+
+    /// Get the element at a row and column.
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        let (word, bit) = self.position(row, col);
+        self.words[word] >> bit & 1 == 1
+    }
+
+    /// Set the element at a row and column.
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        let (word, bit) = self.position(row, col);
+
+        if value {
+            self.words[word] |= 1 << bit;
+        } else {
+            self.words[word] &= !(1 << bit);
+        }
+    }
+
+    /// Get the packed words of a row. Column `c` is bit `c % 64` of word `c / 64`.
+    pub fn row_words(&self, row: usize) -> &[u64] {
+        let start = row * self.words_per_row;
+        &self.words[start..start + self.words_per_row]
+    }
+
+    /// Swap two rows of the matrix.
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        for i in 0..self.words_per_row {
+            self.words
+                .swap(a * self.words_per_row + i, b * self.words_per_row + i);
+        }
+    }
+
+    /// Add the row `source` to the row `target`.
+    pub fn add_row(&mut self, target: usize, source: usize) {
+        for i in 0..self.words_per_row {
+            let word = self.words[source * self.words_per_row + i];
+            self.words[target * self.words_per_row + i] ^= word;
+        }
+    }
+
+    /// Returns the transpose of a matrix
+    pub fn transpose(&self) -> Gf2Matrix {
+        let mut out = Gf2Matrix::zeros(self.dimensions.transpose());
+
+        for row in 0..self.dimensions.rows {
+            for col in self.ones_in_row(row) {
+                out.set(col, row, true);
+            }
+        }
+
+        out
+    }
+
+    /// Compute the reduced row echelon form using Gauss–Jordan elimination.
+    pub fn rref(&self) -> Rref<Gf2Matrix> {
+        let mut matrix = self.clone();
+        let mut pivots = Vec::new();
+
+        for col in 0..self.dimensions.cols {
+            let row = pivots.len();
+            if row == self.dimensions.rows {
+                break;
+            }
+
+            let pivot = match (row..self.dimensions.rows).find(|&r| matrix.get(r, col)) {
+                Some(pivot) => pivot,
+                None => continue,
+            };
+
+            matrix.swap_rows(row, pivot);
+
+            for other in 0..self.dimensions.rows {
+                if other != row && matrix.get(other, col) {
+                    matrix.add_row(other, row);
+                }
+            }
+
+            pivots.push(col);
+        }
+
+        Rref { matrix, pivots }
+    }
+
+    /// The rank of the matrix: the number of linearly independent rows.
+    pub fn rank(&self) -> usize {
+        self.rref().rank()
+    }
+
+    /// The determinant of a square matrix: `true` if the matrix is invertible.
+    pub fn determinant(&self) -> bool {
+        assert!(
+            self.dimensions.rows == self.dimensions.cols,
+            "Matrix must be square. Matrix is of size {}",
+            self.dimensions
+        );
+
+        self.rank() == self.dimensions.rows
+    }
+
+    /// Returns the inverse of a square matrix, or `None` if the matrix is singular.
+    pub fn inverse(&self) -> Option<Gf2Matrix> {
+        assert!(
+            self.dimensions.rows == self.dimensions.cols,
+            "Matrix must be square. Matrix is of size {}",
+            self.dimensions
+        );
+
+        let size = self.dimensions.rows;
+        let mut augmented = Gf2Matrix::zeros([size, 2 * size].into());
+        for row in 0..size {
+            for col in self.ones_in_row(row) {
+                augmented.set(row, col, true);
+            }
+            augmented.set(row, size + row, true);
+        }
+
+        let rref = augmented.rref();
+        if rref.pivots.iter().any(|&col| col >= size) {
+            return None;
+        }
+
+        let mut inverse = Gf2Matrix::zeros(self.dimensions);
+        for row in 0..size {
+            for col in 0..size {
+                inverse.set(row, col, rref.matrix.get(row, size + col));
+            }
+        }
+
+        Some(inverse)
+    }
+
+    /// A basis for the null space: the columns of the returned matrix span every `x` such
+    /// that `self * x` is zero.
+    ///
+    /// For a parity-check matrix this is a generator matrix of the code, with one
+    /// codeword per column.
+    pub fn null_space(&self) -> Gf2Matrix {
+        let rref = self.rref();
+        let cols = self.dimensions.cols;
+        let free: Vec<usize> = (0..cols).filter(|col| !rref.pivots.contains(col)).collect();
+
+        let mut basis = Gf2Matrix::zeros([cols, free.len()].into());
+        for (i, &free_col) in free.iter().enumerate() {
+            basis.set(free_col, i, true);
+
+            for (row, &pivot) in rref.pivots.iter().enumerate() {
+                basis.set(pivot, i, rref.matrix.get(row, free_col));
+            }
+        }
+
+        basis
+    }
+
+    fn position(&self, row: usize, col: usize) -> (usize, usize) {
+        assert!(
+            row < self.dimensions.rows && col < self.dimensions.cols,
+            "Index [{}, {}] is out of bounds for matrix of size {}",
+            row,
+            col,
+            self.dimensions
+        );
+
+        (row * self.words_per_row + col / WORD_BITS, col % WORD_BITS)
+    }
+
+    /// Iterate over the columns that are set in a row.
+    fn ones_in_row(&self, row: usize) -> impl Iterator<Item = usize> + '_ {
+        self.row_words(row)
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &word)| {
+                (0..WORD_BITS)
+                    .filter(move |bit| word >> bit & 1 == 1)
+                    .map(move |bit| i * WORD_BITS + bit)
+            })
+    }
+}
+
+// Matrix-Matrix Multiplication
+impl Mul<Self> for &Gf2Matrix {
+    type Output = Gf2Matrix;
+
+    fn mul(self, rhs: Self) -> Gf2Matrix {
+        assert!(
+            self.dimensions.cols == rhs.dimensions.rows,
+            "Matrix dimensions must agree. Left hand side is {} and right hand side is {}",
+            self.dimensions,
+            rhs.dimensions,
+        );
+
+        let mut out = Gf2Matrix::zeros([self.dimensions.rows, rhs.dimensions.cols].into());
+
+        for row in 0..self.dimensions.rows {
+            for k in self.ones_in_row(row) {
+                for i in 0..out.words_per_row {
+                    out.words[row * out.words_per_row + i] ^= rhs.words[k * rhs.words_per_row + i];
+                }
+            }
+        }
+
+        out
+    }
+}
+
+// Matrix-Vector Multiplication, such as computing the syndrome of a received word
+impl Mul<&Vector<Gf2>> for &Gf2Matrix {
+    type Output = Vector<Gf2>;
+
+    fn mul(self, rhs: &Vector<Gf2>) -> Vector<Gf2> {
+        assert!(
+            self.dimensions.cols == rhs.len(),
+            "Matrix dimensions must agree. Left hand side is {} and right hand side has length {}",
+            self.dimensions,
+            rhs.len(),
+        );
+
+        let mut packed = vec![0u64; self.words_per_row];
+        for (col, bit) in rhs.iter().enumerate() {
+            packed[col / WORD_BITS] |= bit.value() << (col % WORD_BITS);
+        }
+
+        (0..self.dimensions.rows)
+            .map(|row| {
+                let ones: u32 = self
+                    .row_words(row)
+                    .iter()
+                    .zip(&packed)
+                    .map(|(a, b)| (a & b).count_ones())
+                    .sum();
+                Gf2::new(u64::from(ones))
+            })
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+impl From<&Matrix<Gf2>> for Gf2Matrix {
+    fn from(matrix: &Matrix<Gf2>) -> Gf2Matrix {
+        let dimensions = matrix.dim();
+        let mut out = Gf2Matrix::zeros(dimensions);
+
+        for row in 0..dimensions.rows {
+            for col in 0..dimensions.cols {
+                out.set(row, col, !matrix[row][col].is_zero());
+            }
+        }
+
+        out
+    }
+}
+
+impl From<&Gf2Matrix> for Matrix<Gf2> {
+    fn from(matrix: &Gf2Matrix) -> Matrix<Gf2> {
+        let dimensions = matrix.dim();
+        let mut elements = Vec::with_capacity(dimensions.elements());
+
+        for row in 0..dimensions.rows {
+            for col in 0..dimensions.cols {
+                elements.push(Gf2::new(matrix.get(row, col) as u64));
+            }
+        }
+
+        Matrix::from_row_major(dimensions, elements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Parity-check matrix of the Hamming(7, 4) code
+    fn hamming() -> Gf2Matrix {
+        let h = Matrix::new(vec![
+            vec![1u64, 0, 1, 0, 1, 0, 1],
+            vec![0, 1, 1, 0, 0, 1, 1],
+            vec![0, 0, 0, 1, 1, 1, 1],
+        ])
+        .map(Gf2::new);
+        Gf2Matrix::from(&h)
+    }
+
+    #[test]
+    fn round_trip_matrix() {
+        let h = hamming();
+        assert_eq!(Gf2Matrix::from(&Matrix::from(&h)), h);
+    }
+
+    #[test]
+    fn hamming_code() {
+        let h = hamming();
+
+        let generator = h.null_space();
+
+        assert_eq!(h.rank(), 3);
+        assert_eq!(generator.dim(), (7, 4).into());
+        assert_eq!(&h * &generator, Gf2Matrix::zeros((3, 4).into()));
+    }
+
+    #[test]
+    fn syndrome_locates_error() {
+        let h = hamming();
+        let mut received = Vector::zeros(7);
+        received[4] = Gf2::one();
+
+        let syndrome = &h * &received;
+
+        // The syndrome spells out the position of the flipped bit in binary: 5 = 0b101
+        assert_eq!(syndrome, vec![Gf2::new(1), Gf2::new(0), Gf2::new(1)].into());
+    }
+
+    #[test]
+    fn inverse_wide_rows() {
+        let size = 70;
+        let mut a = Gf2Matrix::identity(size);
+        for row in 1..size {
+            a.set(row, row - 1, true);
+        }
+
+        let inverse = a.inverse().unwrap();
+
+        assert!(a.determinant());
+        assert_eq!(&a * &inverse, Gf2Matrix::identity(size));
+        assert_eq!(&inverse * &a, Gf2Matrix::identity(size));
+    }
+
+    #[test]
+    fn agrees_with_generic_elimination() {
+        let a = Matrix::new(vec![
+            vec![1u64, 1, 0, 1],
+            vec![0, 1, 1, 1],
+            vec![1, 0, 1, 0],
+        ])
+        .map(Gf2::new);
+
+        let packed = Gf2Matrix::from(&a).rref();
+        let rref = a.rref();
+
+        assert_eq!(Matrix::from(&packed.matrix), rref.matrix);
+        assert_eq!(packed.pivots, rref.pivots);
+        assert_eq!(packed.rank(), rref.rank());
+    }
+}
@@ -0,0 +1,384 @@
+use crate::{
+    matrix::{Dimensions, Matrix},
+    vector::Vector,
+};
+use std::{fmt, str::FromStr};
+
+/// An error from parsing a matrix or vector from a string.
+///
+/// Lines and columns are numbered from 1, and columns count characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+type Result<T> = std::result::Result<T, ParseError>;
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Parser<'a> {
+        Parser { input, position: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.peek() {
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn error_at(&self, position: usize, message: impl Into<String>) -> ParseError {
+        let before = &self.input[..position];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        let found = match self.peek() {
+            Some(c) => format!("'{}'", c.escape_default()),
+            None => "end of input".to_owned(),
+        };
+        self.error_at(
+            self.position,
+            format!("expected {}, found {}", expected, found),
+        )
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    /// Skip whitespace up to the next newline, which separates rows in MATLAB syntax.
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace() && c != '\n') {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.peek() == Some(c) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{}'", c)))
+        }
+    }
+
+    fn end(&mut self) -> Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.unexpected("end of input")),
+        }
+    }
+
+    fn element<S>(&mut self) -> Result<S>
+    where
+        S: FromStr,
+        S::Err: fmt::Display,
+    {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| !c.is_whitespace() && !"[],;".contains(c))
+        {
+            self.bump();
+        }
+
+        let token = &self.input[start..self.position];
+        if token.is_empty() {
+            return Err(self.unexpected("a number"));
+        }
+
+        token
+            .parse()
+            .map_err(|e| self.error_at(start, format!("invalid number '{}': {}", token, e)))
+    }
+
+    /// A comma separated list of elements in brackets, like `[1, 2, 3]`.
+    fn list<S>(&mut self) -> Result<Vec<S>>
+    where
+        S: FromStr,
+        S::Err: fmt::Display,
+    {
+        self.expect('[')?;
+        self.skip_whitespace();
+
+        let mut elements = Vec::new();
+        if self.peek() == Some(']') {
+            self.bump();
+            return Ok(elements);
+        }
+
+        loop {
+            self.skip_whitespace();
+            elements.push(self.element()?);
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => self.bump(),
+                Some(']') => {
+                    self.bump();
+                    return Ok(elements);
+                }
+                _ => return Err(self.unexpected("',' or ']'")),
+            }
+        }
+    }
+
+    /// The rows of a nested list, like `[[1, 2], [3, 4]]`, after the opening bracket.
+    fn nested_rows<S>(&mut self) -> Result<Vec<(usize, Vec<S>)>>
+    where
+        S: FromStr,
+        S::Err: fmt::Display,
+    {
+        let mut rows = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            rows.push((self.position, self.list()?));
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => self.bump(),
+                Some(']') => {
+                    self.bump();
+                    return Ok(rows);
+                }
+                _ => return Err(self.unexpected("',' or ']'")),
+            }
+        }
+    }
+
+    /// The rows of a MATLAB matrix, like `[1 2; 3 4]`, after the opening bracket.
+    ///
+    /// Elements are separated by commas or whitespace, and rows by semicolons or newlines.
+    /// Empty rows are ignored.
+    fn matlab_rows<S>(&mut self) -> Result<Vec<(usize, Vec<S>)>>
+    where
+        S: FromStr,
+        S::Err: fmt::Display,
+    {
+        let mut rows = Vec::new();
+
+        loop {
+            self.skip_spaces();
+            let start = self.position;
+            let mut row = Vec::new();
+
+            loop {
+                self.skip_spaces();
+                match self.peek() {
+                    Some(']') | Some(';') | Some('\n') | None if row.is_empty() => break,
+                    _ => row.push(self.element()?),
+                }
+
+                self.skip_spaces();
+                match self.peek() {
+                    Some(',') => self.bump(),
+                    Some(']') | Some(';') | Some('\n') => break,
+                    Some(_) => {}
+                    None => return Err(self.unexpected("']'")),
+                }
+            }
+
+            if !row.is_empty() {
+                rows.push((start, row));
+            }
+
+            match self.peek() {
+                Some(']') => {
+                    self.bump();
+                    return Ok(rows);
+                }
+                Some(';') | Some('\n') => self.bump(),
+                _ => return Err(self.unexpected("']'")),
+            }
+        }
+    }
+
+    /// The rows of a matrix in either syntax, each with the position where it starts.
+    fn rows<S>(&mut self) -> Result<Vec<(usize, Vec<S>)>>
+    where
+        S: FromStr,
+        S::Err: fmt::Display,
+    {
+        self.skip_whitespace();
+        self.expect('[')?;
+        self.skip_whitespace();
+
+        let rows = match self.peek() {
+            Some(']') => {
+                self.bump();
+                Vec::new()
+            }
+            Some('[') => self.nested_rows()?,
+            _ => self.matlab_rows()?,
+        };
+
+        self.end()?;
+        Ok(rows)
+    }
+}
+
+impl<S> FromStr for Matrix<S>
+where
+    S: FromStr,
+    S::Err: fmt::Display,
+{
+    type Err = ParseError;
+
+    /// Parse a matrix from its `Display` output, a nested list like `[[1, 2], [3, 4]]`,
+    /// or MATLAB syntax like `[1 2; 3 4]`.
+    fn from_str(s: &str) -> Result<Matrix<S>> {
+        let mut parser = Parser::new(s);
+        let rows = parser.rows()?;
+
+        let cols = rows.first().map_or(0, |(_, row)| row.len());
+        let mut elements = Vec::with_capacity(rows.len() * cols);
+        for (index, (start, row)) in rows.iter().enumerate() {
+            if row.len() != cols {
+                return Err(parser.error_at(
+                    *start,
+                    format!(
+                        "row {} has {} elements, but the first row has {}",
+                        index + 1,
+                        row.len(),
+                        cols
+                    ),
+                ));
+            }
+        }
+
+        let dimensions = Dimensions {
+            rows: rows.len(),
+            cols,
+        };
+        elements.extend(rows.into_iter().flat_map(|(_, row)| row));
+        Ok(Matrix::from_row_major(dimensions, elements))
+    }
+}
+
+impl<S> FromStr for Vector<S>
+where
+    S: FromStr,
+    S::Err: fmt::Display,
+{
+    type Err = ParseError;
+
+    /// Parse a vector from its `Display` output like `[1, 2, 3]`, or MATLAB syntax for a
+    /// row or column vector like `[1 2 3]` or `[1; 2; 3]`.
+    fn from_str(s: &str) -> Result<Vector<S>> {
+        let mut parser = Parser::new(s);
+        parser.skip_whitespace();
+        let start = parser.position;
+        let rows = parser.rows::<S>()?;
+
+        let is_column = rows.iter().all(|(_, row)| row.len() == 1);
+        if rows.len() > 1 && !is_column {
+            return Err(parser.error_at(start, "expected a single row or column"));
+        }
+
+        Ok(Vector::new(
+            rows.into_iter().flat_map(|(_, row)| row).collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error<T>(s: &str) -> String
+    where
+        T: FromStr<Err = ParseError> + fmt::Debug,
+    {
+        s.parse::<T>().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn round_trip_display() {
+        let a = mat![[1.5, -2.0, 3.0], [4.0, 5.0, 1e-20]];
+        let v = mat![1, -2, 3];
+
+        assert_eq!(a.to_string().parse::<Matrix<f64>>().unwrap(), a);
+        assert_eq!(v.to_string().parse::<Vector<i32>>().unwrap(), v);
+        assert_eq!("[]".parse::<Matrix<i32>>().unwrap().dim(), (0, 0).into());
+        assert!(" [ ] ".parse::<Vector<i32>>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn matlab_syntax() {
+        let a = mat![[1, -2, 3], [4, 5, 6]];
+
+        assert_eq!("[1 -2 3; 4 5 6]".parse::<Matrix<i32>>().unwrap(), a);
+        assert_eq!("[1, -2, 3;4,5,6;]".parse::<Matrix<i32>>().unwrap(), a);
+        assert_eq!("[1 -2 3\n 4 5 6\n]".parse::<Matrix<i32>>().unwrap(), a);
+        assert_eq!("[1 2 3]".parse::<Vector<i32>>().unwrap(), mat![1, 2, 3]);
+        assert_eq!("[1; 2; 3]".parse::<Vector<i32>>().unwrap(), mat![1, 2, 3]);
+    }
+
+    #[test]
+    fn error_positions() {
+        assert_eq!(
+            error::<Matrix<i32>>("[\n    [1, 2],\n    [3, x]\n]"),
+            "line 3, column 9: invalid number 'x': invalid digit found in string"
+        );
+        assert_eq!(
+            error::<Matrix<i32>>("[[1, 2], [3]]"),
+            "line 1, column 10: row 2 has 1 elements, but the first row has 2"
+        );
+        assert_eq!(
+            error::<Matrix<i32>>("[1 2; 3]"),
+            "line 1, column 7: row 2 has 1 elements, but the first row has 2"
+        );
+        assert_eq!(
+            error::<Matrix<i32>>("[[1 2]]"),
+            "line 1, column 5: expected ',' or ']', found '2'"
+        );
+        assert_eq!(
+            error::<Vector<i32>>("[1, 2,]"),
+            "line 1, column 7: expected a number, found ']'"
+        );
+        assert_eq!(
+            error::<Vector<i32>>("[1 2; 3 4]"),
+            "line 1, column 1: expected a single row or column"
+        );
+        assert_eq!(
+            error::<Vector<i32>>("[1, 2] 3"),
+            "line 1, column 8: expected end of input, found '3'"
+        );
+        assert_eq!(
+            error::<Vector<i32>>("[1, 2"),
+            "line 1, column 6: expected ']', found end of input"
+        );
+    }
+}
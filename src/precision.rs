@@ -0,0 +1,196 @@
+//! Mixed precision products and conversions between element types.
+
+use crate::{
+    matrix::{Dimensions, Matrix},
+    traits::MixedPrecision,
+    vector::Vector,
+};
+use num::{NumCast, ToPrimitive, Zero};
+
+/// Dot product where the products are summed in the accumulator type.
+pub fn dot_mixed<S>(lhs: &[S], rhs: &[S]) -> S::Accumulator
+where
+    S: MixedPrecision,
+{
+    assert!(
+        lhs.len() == rhs.len(),
+        "Vectors must be of same length. Left hand side has length {} and right hand side has length {}",
+        lhs.len(),
+        rhs.len()
+    );
+
+    lhs.iter()
+        .zip(rhs)
+        .fold(S::Accumulator::zero(), |acc, (&a, &b)| {
+            acc + a.widen() * b.widen()
+        })
+}
+
+impl<S> Vector<S>
+where
+    S: MixedPrecision,
+{
+    /// The dot product, accumulated and returned in the accumulator type.
+    ///
+    /// For 16-bit floats the result is an `f32`, so sums of many elements do not stall
+    /// once they exceed the precision of the elements.
+    pub fn dot_mixed(&self, other: &Self) -> S::Accumulator {
+        dot_mixed(self, other)
+    }
+}
+
+impl<S> Matrix<S>
+where
+    S: MixedPrecision,
+{
+    /// Matrix multiplication where every element is accumulated in the accumulator type
+    /// and rounded to the element type once at the end.
+    pub fn mul_mixed(&self, rhs: &Matrix<S>) -> Matrix<S> {
+        assert!(
+            self.dim().cols == rhs.dim().rows,
+            "Matrix dimensions must agree. Left hand side is {} and right hand side is {}",
+            self.dim(),
+            rhs.dim(),
+        );
+
+        let rhs_transpose = rhs.transpose();
+
+        let out_dimensions = Dimensions {
+            rows: self.dim().rows,
+            cols: rhs.dim().cols,
+        };
+        let mut elements = Vec::with_capacity(out_dimensions.elements());
+
+        for row in 0..out_dimensions.rows {
+            for col in 0..out_dimensions.cols {
+                elements.push(S::narrow(dot_mixed(&self[row], &rhs_transpose[col])));
+            }
+        }
+
+        Matrix::from_row_major(out_dimensions, elements)
+    }
+
+    /// Matrix-vector multiplication where every element is accumulated in the accumulator
+    /// type and rounded to the element type once at the end.
+    pub fn mul_vector_mixed(&self, rhs: &Vector<S>) -> Vector<S> {
+        assert!(
+            self.dim().cols == rhs.len(),
+            "Matrix dimensions must agree. Left hand side is {} and right hand side has length {}",
+            self.dim(),
+            rhs.len(),
+        );
+
+        (0..self.dim().rows)
+            .map(|row| S::narrow(dot_mixed(&self[row], rhs)))
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+impl<S> Matrix<S>
+where
+    S: ToPrimitive + Clone,
+{
+    /// Convert every element to another numeric type, such as between `f32` and `f16`.
+    ///
+    /// Returns `None` if any element cannot be represented in the new type, including a
+    /// finite element too large for a floating point type, which would become infinite.
+    /// ```
+    /// # use land::{mat, Matrix};
+    /// # fn main() {
+    /// let a = mat![[1.5f64, 2.0], [3.0, 4.25]];
+    ///
+    /// assert_eq!(a.cast::<f32>(), Some(mat![[1.5f32, 2.0], [3.0, 4.25]]));
+    /// assert!(mat![[300.0f64]].cast::<i8>().is_none());
+    /// # }
+    /// ```
+    pub fn cast<T>(&self) -> Option<Matrix<T>>
+    where
+        T: NumCast,
+    {
+        let elements = self
+            .iter_rows()
+            .map(|e| {
+                let value = T::from(e.clone())?;
+                let finite = |x: Option<f64>| x.is_none_or(f64::is_finite);
+                if finite(e.to_f64()) && !finite(value.to_f64()) {
+                    None
+                } else {
+                    Some(value)
+                }
+            })
+            .collect::<Option<Vec<T>>>()?;
+
+        Some(Matrix::from_row_major(self.dim(), elements))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mixed_matches_regular_for_wide_types() {
+        let a = mat![[1.0f32, 2.0], [3.0, 4.0]];
+        let b = mat![[0.5f32, -1.0], [2.0, 0.25]];
+
+        assert_eq!(a.mul_mixed(&b), &a * &b);
+        assert_eq!(a.mul_vector_mixed(&mat![1.0, 2.0]), &a * &mat![1.0, 2.0]);
+
+        let v = Vector::from(vec![1.0f64, -2.0, 3.0]);
+        assert_eq!(v.dot_mixed(&v), v.dot(&v));
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn f16_accumulates_in_f32() {
+        use half::f16;
+
+        let ones = Vector::filled(f16::from_f32(1.0), 4096);
+
+        // Accumulating in f16 would stall at 2048, where the spacing between values becomes 2
+        assert_eq!(ones.dot_mixed(&ones), 4096.0f32);
+
+        let a = Matrix::filled(f16::from_f32(1.0), (1, 4096).into());
+        assert_eq!(a.mul_mixed(&a.transpose())[0][0], f16::from_f32(4096.0));
+        assert_eq!(a.mul_vector_mixed(&ones)[0], f16::from_f32(4096.0));
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn bf16_matrix_product() {
+        use half::bf16;
+
+        let a = Matrix::filled(bf16::from_f32(1.0), (2, 512).into());
+        let b = Matrix::filled(bf16::from_f32(1.0), (512, 2).into());
+
+        let product = a.mul_mixed(&b);
+
+        assert_eq!(
+            product,
+            Matrix::filled(bf16::from_f32(512.0), (2, 2).into())
+        );
+        assert_eq!(
+            product.cast::<f32>().unwrap(),
+            Matrix::filled(512.0, (2, 2).into())
+        );
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn cast_between_precisions() {
+        use half::f16;
+
+        let a = mat![[0.1f32, 65504.0], [f32::INFINITY, -2.5]];
+
+        let narrow = a.cast::<f16>().unwrap();
+
+        assert_eq!(narrow[0][0], f16::from_f32(0.1));
+        assert_eq!(narrow[0][1], f16::MAX);
+        assert!(narrow[1][0].is_infinite());
+        assert_eq!(narrow.cast::<f32>().unwrap()[1][1], -2.5);
+
+        // A finite element too large for f16 is not representable
+        assert!(mat![[1e6f32]].cast::<f16>().is_none());
+    }
+}
@@ -0,0 +1,182 @@
+//! Wrapper scalars that give matrix multiplication a different meaning.
+//!
+//! Multiplying two matrices over one of these semirings computes
+//! `c[i][j] = plus(times(a[i][0], b[0][j]), times(a[i][1], b[1][j]), ...)`,
+//! which for adjacency matrices answers questions about paths in a graph.
+
+use crate::traits::Semiring;
+use num::Float;
+use std::fmt;
+
+macro_rules! impl_wrapper {
+    ($name:ident) => {
+        impl<T> From<T> for $name<T> {
+            fn from(value: T) -> Self {
+                $name(value)
+            }
+        }
+
+        impl<T> fmt::Display for $name<T>
+        where
+            T: fmt::Display,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    };
+}
+
+/// The tropical (min, +) semiring, used for shortest paths.
+///
+/// "Addition" takes the minimum and "multiplication" adds. Missing edges are
+/// represented by infinity.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct MinPlus<T>(pub T);
+
+impl<T> Semiring for MinPlus<T>
+where
+    T: Float,
+{
+    fn additive_identity() -> Self {
+        MinPlus(T::infinity())
+    }
+
+    fn multiplicative_identity() -> Self {
+        MinPlus(T::zero())
+    }
+
+    fn plus(&self, rhs: &Self) -> Self {
+        MinPlus(self.0.min(rhs.0))
+    }
+
+    fn times(&self, rhs: &Self) -> Self {
+        MinPlus(self.0 + rhs.0)
+    }
+}
+
+impl_wrapper!(MinPlus);
+
+/// The (max, +) semiring, used for longest paths and critical path scheduling.
+///
+/// "Addition" takes the maximum and "multiplication" adds. Missing edges are
+/// represented by negative infinity.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct MaxPlus<T>(pub T);
+
+impl<T> Semiring for MaxPlus<T>
+where
+    T: Float,
+{
+    fn additive_identity() -> Self {
+        MaxPlus(T::neg_infinity())
+    }
+
+    fn multiplicative_identity() -> Self {
+        MaxPlus(T::zero())
+    }
+
+    fn plus(&self, rhs: &Self) -> Self {
+        MaxPlus(self.0.max(rhs.0))
+    }
+
+    fn times(&self, rhs: &Self) -> Self {
+        MaxPlus(self.0 + rhs.0)
+    }
+}
+
+impl_wrapper!(MaxPlus);
+
+/// The (max, min) semiring, used for widest (bottleneck) paths.
+///
+/// "Addition" takes the maximum and "multiplication" the minimum. Missing edges
+/// are represented by negative infinity.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct MaxMin<T>(pub T);
+
+impl<T> Semiring for MaxMin<T>
+where
+    T: Float,
+{
+    fn additive_identity() -> Self {
+        MaxMin(T::neg_infinity())
+    }
+
+    fn multiplicative_identity() -> Self {
+        MaxMin(T::infinity())
+    }
+
+    fn plus(&self, rhs: &Self) -> Self {
+        MaxMin(self.0.max(rhs.0))
+    }
+
+    fn times(&self, rhs: &Self) -> Self {
+        MaxMin(self.0.min(rhs.0))
+    }
+}
+
+impl_wrapper!(MaxMin);
+
+/// The (max, ×) semiring, used for most likely paths (Viterbi).
+///
+/// "Addition" takes the maximum and "multiplication" multiplies. Missing edges
+/// are represented by zero.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct MaxTimes<T>(pub T);
+
+impl<T> Semiring for MaxTimes<T>
+where
+    T: Float,
+{
+    fn additive_identity() -> Self {
+        MaxTimes(T::zero())
+    }
+
+    fn multiplicative_identity() -> Self {
+        MaxTimes(T::one())
+    }
+
+    fn plus(&self, rhs: &Self) -> Self {
+        MaxTimes(self.0.max(rhs.0))
+    }
+
+    fn times(&self, rhs: &Self) -> Self {
+        MaxTimes(self.0 * rhs.0)
+    }
+}
+
+impl_wrapper!(MaxTimes);
+
+/// The boolean (or, and) semiring, used for reachability.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Boolean(pub bool);
+
+impl Semiring for Boolean {
+    fn additive_identity() -> Self {
+        Boolean(false)
+    }
+
+    fn multiplicative_identity() -> Self {
+        Boolean(true)
+    }
+
+    fn plus(&self, rhs: &Self) -> Self {
+        Boolean(self.0 || rhs.0)
+    }
+
+    fn times(&self, rhs: &Self) -> Self {
+        Boolean(self.0 && rhs.0)
+    }
+}
+
+impl From<bool> for Boolean {
+    fn from(value: bool) -> Self {
+        Boolean(value)
+    }
+}
+
+impl fmt::Display for Boolean {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
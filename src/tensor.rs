@@ -0,0 +1,490 @@
+mod decomposition;
+mod einsum;
+mod index;
+mod operations;
+mod shape;
+
+pub use self::decomposition::*;
+pub use self::einsum::*;
+pub use self::shape::*;
+use crate::{
+    matrix::{Dimensions, Matrix},
+    traits::Scalar,
+    vector::Vector,
+};
+use std::{convert::TryFrom, fmt, ops::Range};
+
+/// An N-dimensional array with elements laid out by strides.
+///
+/// Tensors are created in row-major order, where the last axis is contiguous. Permuting
+/// axes only permutes the strides, so the elements are not moved until they need to be.
+/// ```
+/// # use land::{Tensor, mat};
+/// # fn main() {
+/// let t = Tensor::from_row_major([2, 3, 4], (0..24).collect());
+///
+/// assert_eq!(t[[1, 2, 3]], 23);
+/// assert_eq!(t.shape().strides(), vec![12, 4, 1]);
+///
+/// let t = t.permute(&[2, 0, 1]);
+/// assert_eq!(t[[3, 1, 2]], 23);
+/// assert_eq!(t.slice(&[1..2, 0..1]).into_row_major(), vec![1, 5, 9]);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tensor<S> {
+    elements: Vec<S>,
+    shape: Shape,
+    strides: Vec<usize>,
+}
+
+/// The offsets into the elements of a tensor, visited in row-major order.
+struct Offsets<'a> {
+    dims: &'a [usize],
+    strides: &'a [usize],
+    index: Vec<usize>,
+    offset: usize,
+    remaining: usize,
+}
+
+impl<'a> Offsets<'a> {
+    fn new(dims: &'a [usize], strides: &'a [usize], offset: usize) -> Offsets<'a> {
+        Offsets {
+            dims,
+            strides,
+            index: vec![0; dims.len()],
+            offset,
+            remaining: dims.iter().product(),
+        }
+    }
+}
+
+impl<'a> Iterator for Offsets<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let offset = self.offset;
+
+        for axis in (0..self.dims.len()).rev() {
+            self.index[axis] += 1;
+            self.offset += self.strides[axis];
+            if self.index[axis] < self.dims[axis] {
+                break;
+            }
+            self.offset -= self.strides[axis] * self.dims[axis];
+            self.index[axis] = 0;
+        }
+
+        Some(offset)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<S> Tensor<S> {
+    /// Create a tensor from its elements in row-major order.
+    pub fn from_row_major<T: Into<Shape>>(shape: T, elements: Vec<S>) -> Tensor<S> {
+        let shape = shape.into();
+        assert!(
+            elements.len() == shape.elements(),
+            "Number of elements must match tensor shape. Number of elements was {} and shape {}",
+            elements.len(),
+            shape
+        );
+
+        Tensor {
+            elements,
+            strides: shape.strides(),
+            shape,
+        }
+    }
+
+    /// Get the shape of the tensor
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    /// The number of axes.
+    pub fn rank(&self) -> usize {
+        self.shape.rank()
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// The distance in elements between consecutive indices along each axis.
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+
+    fn offsets(&self) -> Offsets<'_> {
+        Offsets::new(self.shape.dims(), &self.strides, 0)
+    }
+
+    /// The position of an element in `elements`, or `None` if the index is out of bounds.
+    fn offset(&self, index: &[usize]) -> Option<usize> {
+        assert!(
+            index.len() == self.rank(),
+            "Index must have one coordinate per axis. Index was {:?} and shape {}",
+            index,
+            self.shape
+        );
+
+        let mut offset = 0;
+        for ((&i, &size), &stride) in index.iter().zip(self.shape.dims()).zip(&self.strides) {
+            if i >= size {
+                return None;
+            }
+            offset += i * stride;
+        }
+        Some(offset)
+    }
+
+    /// Get an element, or `None` if the index is out of bounds.
+    ///
+    /// Panics if the index does not have one coordinate per axis.
+    pub fn get(&self, index: &[usize]) -> Option<&S> {
+        self.offset(index).map(|offset| &self.elements[offset])
+    }
+
+    /// Get an element mutably, or `None` if the index is out of bounds.
+    pub fn get_mut(&mut self, index: &[usize]) -> Option<&mut S> {
+        self.offset(index)
+            .map(move |offset| &mut self.elements[offset])
+    }
+
+    /// Return an iterator through each element in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = &S> {
+        self.offsets().map(move |offset| &self.elements[offset])
+    }
+
+    /// The elements in row-major order.
+    pub fn into_row_major(self) -> Vec<S> {
+        if self.strides == self.shape.strides() {
+            return self.elements;
+        }
+
+        let offsets: Vec<usize> = self.offsets().collect();
+        let mut elements: Vec<Option<S>> = self.elements.into_iter().map(Some).collect();
+        offsets
+            .into_iter()
+            .map(|offset| elements[offset].take().unwrap())
+            .collect()
+    }
+
+    /// Apply a function to every element, possibly changing its type.
+    pub fn map<T, F>(self, f: F) -> Tensor<T>
+    where
+        F: FnMut(S) -> T,
+    {
+        Tensor {
+            elements: self.elements.into_iter().map(f).collect(),
+            shape: self.shape,
+            strides: self.strides,
+        }
+    }
+
+    /// Change the shape, keeping the elements in row-major order.
+    pub fn reshape<T: Into<Shape>>(self, shape: T) -> Tensor<S> {
+        let shape = shape.into();
+        assert!(
+            shape.elements() == self.len(),
+            "Tensor of shape {} cannot be reshaped to {}",
+            self.shape,
+            shape
+        );
+
+        Tensor::from_row_major(shape, self.into_row_major())
+    }
+
+    /// Reorder the axes, so that axis `i` of the result is axis `axes[i]` of `self`.
+    pub fn permute(self, axes: &[usize]) -> Tensor<S> {
+        let mut seen = vec![false; self.rank()];
+        for &axis in axes {
+            if axis < seen.len() {
+                seen[axis] = true;
+            }
+        }
+        assert!(
+            axes.len() == self.rank() && seen.iter().all(|&seen| seen),
+            "Axes must be a permutation of the axes of the tensor. Axes were {:?} and shape {}",
+            axes,
+            self.shape
+        );
+
+        Tensor {
+            shape: Shape::new(axes.iter().map(|&axis| self.shape[axis]).collect()),
+            strides: axes.iter().map(|&axis| self.strides[axis]).collect(),
+            elements: self.elements,
+        }
+    }
+
+    /// Reverse the order of the axes.
+    pub fn transpose(self) -> Tensor<S> {
+        let axes: Vec<usize> = (0..self.rank()).rev().collect();
+        self.permute(&axes)
+    }
+}
+
+impl<S> Tensor<S>
+where
+    S: Clone,
+{
+    /// A tensor filled with a value.
+    pub fn filled<T: Into<Shape>>(value: S, shape: T) -> Tensor<S> {
+        let shape = shape.into();
+        Tensor::from_row_major(shape.clone(), vec![value; shape.elements()])
+    }
+
+    /// Copy out the part of the tensor within the given range along each of the first
+    /// `ranges.len()` axes. The remaining axes are kept whole.
+    pub fn slice(&self, ranges: &[Range<usize>]) -> Tensor<S> {
+        assert!(
+            ranges.len() <= self.rank(),
+            "Cannot slice {} axes of a tensor of shape {}",
+            ranges.len(),
+            self.shape
+        );
+
+        let mut dims = self.shape.dims().to_vec();
+        let mut start = 0;
+        for (axis, range) in ranges.iter().enumerate() {
+            assert!(
+                range.start <= range.end && range.end <= dims[axis],
+                "Range {:?} is out of bounds for axis {} of a tensor of shape {}",
+                range,
+                axis,
+                self.shape
+            );
+            dims[axis] = range.end - range.start;
+            start += range.start * self.strides[axis];
+        }
+
+        let elements = Offsets::new(&dims, &self.strides, start)
+            .map(|offset| self.elements[offset].clone())
+            .collect();
+        Tensor::from_row_major(dims, elements)
+    }
+
+    /// Copy out the tensor of one lower rank at `index` along `axis`.
+    pub fn index_axis(&self, axis: usize, index: usize) -> Tensor<S> {
+        assert!(
+            axis < self.rank() && index < self.shape[axis],
+            "Index {} along axis {} is out of bounds for a tensor of shape {}",
+            index,
+            axis,
+            self.shape
+        );
+
+        let mut dims = self.shape.dims().to_vec();
+        let mut strides = self.strides.clone();
+        dims.remove(axis);
+        strides.remove(axis);
+
+        let elements = Offsets::new(&dims, &strides, index * self.strides[axis])
+            .map(|offset| self.elements[offset].clone())
+            .collect();
+        Tensor::from_row_major(dims, elements)
+    }
+}
+
+impl<S> Tensor<S>
+where
+    S: Scalar,
+{
+    /// A tensor filled with zeros.
+    pub fn zeros<T: Into<Shape>>(shape: T) -> Tensor<S> {
+        Self::filled(S::zero(), shape)
+    }
+
+    /// A tensor filled with ones.
+    pub fn ones<T: Into<Shape>>(shape: T) -> Tensor<S> {
+        Self::filled(S::one(), shape)
+    }
+}
+
+impl<S> From<Vector<S>> for Tensor<S> {
+    fn from(vector: Vector<S>) -> Tensor<S> {
+        let len = vector.len();
+        Tensor::from_row_major([len], vector.to_vec())
+    }
+}
+
+impl<S> From<Matrix<S>> for Tensor<S> {
+    fn from(matrix: Matrix<S>) -> Tensor<S> {
+        let dimensions = matrix.dim();
+        Tensor::from_row_major(dimensions, matrix.into_iter_rows().collect())
+    }
+}
+
+impl<S> TryFrom<Tensor<S>> for Vector<S> {
+    /// The tensor is returned if it does not have rank 1.
+    type Error = Tensor<S>;
+
+    fn try_from(tensor: Tensor<S>) -> Result<Vector<S>, Tensor<S>> {
+        if tensor.rank() == 1 {
+            Ok(Vector::new(tensor.into_row_major()))
+        } else {
+            Err(tensor)
+        }
+    }
+}
+
+impl<S> TryFrom<Tensor<S>> for Matrix<S> {
+    /// The tensor is returned if it does not have rank 2.
+    type Error = Tensor<S>;
+
+    fn try_from(tensor: Tensor<S>) -> Result<Matrix<S>, Tensor<S>> {
+        if tensor.rank() == 2 {
+            let dimensions = Dimensions {
+                rows: tensor.shape[0],
+                cols: tensor.shape[1],
+            };
+            Ok(Matrix::from_row_major(dimensions, tensor.into_row_major()))
+        } else {
+            Err(tensor)
+        }
+    }
+}
+
+impl<S> PartialEq<Self> for Tensor<S>
+where
+    S: Scalar + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.shape == other.shape && self.iter().eq(other.iter())
+    }
+}
+
+/// Write the elements of a row-major tensor as nested lists.
+fn fmt_nested<S>(
+    f: &mut fmt::Formatter,
+    elements: &[&S],
+    dims: &[usize],
+    depth: usize,
+) -> fmt::Result
+where
+    S: fmt::Display,
+{
+    match dims {
+        [] => elements[0].fmt(f),
+        [_] => {
+            write!(f, "[")?;
+            for (i, element) in elements.iter().enumerate() {
+                if i != 0 {
+                    write!(f, ", ")?;
+                }
+                element.fmt(f)?;
+            }
+            write!(f, "]")
+        }
+        [size, rest @ ..] => {
+            let indent = "    ".repeat(depth + 1);
+            let len: usize = rest.iter().product();
+
+            writeln!(f, "[")?;
+            for i in 0..*size {
+                write!(f, "{}", indent)?;
+                fmt_nested(f, &elements[i * len..(i + 1) * len], rest, depth + 1)?;
+                writeln!(f, "{}", if i + 1 == *size { "" } else { "," })?;
+            }
+            write!(f, "{}]", "    ".repeat(depth))
+        }
+    }
+}
+
+impl<S> fmt::Display for Tensor<S>
+where
+    S: fmt::Display,
+{
+    /// Print the tensor as nested lists. The formatting flags apply to every element.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let elements: Vec<&S> = self.iter().collect();
+        fmt_nested(f, &elements, self.shape.dims(), 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arange(shape: &[usize]) -> Tensor<i32> {
+        let shape = Shape::from(shape);
+        let elements = (0..shape.elements() as i32).collect();
+        Tensor::from_row_major(shape, elements)
+    }
+
+    #[test]
+    fn reshape_permuted() {
+        let t = arange(&[2, 3]).transpose();
+
+        assert_eq!(t.shape(), &Shape::from([3, 2]));
+        assert_eq!(t.strides(), [1, 3]);
+        assert_eq!(t.clone().into_row_major(), vec![0, 3, 1, 4, 2, 5]);
+        assert_eq!(t.reshape([6]).strides(), [1]);
+    }
+
+    #[test]
+    fn slices() {
+        let t = arange(&[2, 3, 4]);
+
+        assert_eq!(
+            t.slice(&[0..2, 1..3, 2..3]),
+            Tensor::from_row_major([2, 2, 1], vec![6, 10, 18, 22])
+        );
+        assert_eq!(t.slice(&[1..1, 0..3]).shape(), &Shape::from([0, 3, 4]));
+        assert_eq!(
+            t.index_axis(1, 2),
+            Tensor::from_row_major([2, 4], vec![8, 9, 10, 11, 20, 21, 22, 23])
+        );
+        assert_eq!(t.index_axis(0, 1).index_axis(0, 0).index_axis(0, 3)[[]], 15);
+    }
+
+    #[test]
+    fn matrix_conversions() {
+        let a = mat![[1, 2, 3], [4, 5, 6]];
+
+        let t = Tensor::from(a.clone()).permute(&[1, 0]);
+        assert_eq!(Matrix::try_from(t).unwrap(), a.transpose());
+
+        let v = Vector::try_from(Tensor::from(mat![1, 2])).unwrap();
+        assert_eq!(v, mat![1, 2]);
+
+        assert!(Matrix::try_from(arange(&[2, 2, 2])).is_err());
+    }
+
+    #[test]
+    fn display() {
+        let t = Tensor::from_row_major([2, 2, 2], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+
+        assert_eq!(
+            format!("{:.1}", t),
+            "[
+    [
+        [1.0, 2.0],
+        [3.0, 4.0]
+    ],
+    [
+        [5.0, 6.0],
+        [7.0, 8.0]
+    ]
+]"
+        );
+        assert_eq!(
+            Tensor::from_row_major(Shape::new(vec![]), vec![7]).to_string(),
+            "7"
+        );
+    }
+}
@@ -0,0 +1,527 @@
+use super::{Shape, Tensor};
+use crate::{
+    matrix::{Dimensions, Matrix},
+    traits::{FloatScalar, Scalar},
+    vector::Vector,
+};
+use num::NumCast;
+
+/// The order in which `unfold` places the axes: `mode` first and the others after it.
+fn unfold_axes(rank: usize, mode: usize) -> Vec<usize> {
+    std::iter::once(mode)
+        .chain((0..rank).filter(|&axis| axis != mode))
+        .collect()
+}
+
+impl<S> Tensor<S>
+where
+    S: Clone,
+{
+    /// The mode-`mode` unfolding (matricisation) of the tensor.
+    ///
+    /// Row `i` holds the elements whose index along `mode` is `i`, in row-major order of
+    /// the remaining axes, so the last remaining axis varies fastest.
+    pub fn unfold(&self, mode: usize) -> Matrix<S> {
+        assert!(
+            mode < self.rank(),
+            "Mode {} is out of bounds for a tensor of shape {}",
+            mode,
+            self.shape
+        );
+
+        let rows = self.shape[mode];
+        let axes = unfold_axes(self.rank(), mode);
+        let elements = self.clone().permute(&axes).into_row_major();
+        Matrix::from_row_major(
+            Dimensions {
+                rows,
+                cols: self.len().checked_div(rows).unwrap_or(0),
+            },
+            elements,
+        )
+    }
+
+    /// Fold a mode-`mode` unfolding back into a tensor of the given shape. This is the
+    /// inverse of `unfold`.
+    pub fn fold<T: Into<Shape>>(unfolded: &Matrix<S>, mode: usize, shape: T) -> Tensor<S> {
+        let shape = shape.into();
+        assert!(
+            mode < shape.rank()
+                && unfolded.dim().rows == shape[mode]
+                && unfolded.dim().elements() == shape.elements(),
+            "A matrix of size {} is not a mode {} unfolding of a tensor of shape {}",
+            unfolded.dim(),
+            mode,
+            shape
+        );
+
+        let axes = unfold_axes(shape.rank(), mode);
+        let dims: Vec<usize> = axes.iter().map(|&axis| shape[axis]).collect();
+        let mut inverse = vec![0; axes.len()];
+        for (position, &axis) in axes.iter().enumerate() {
+            inverse[axis] = position;
+        }
+
+        let elements = unfolded.iter_rows().cloned().collect();
+        let permuted = Tensor::from_row_major(dims, elements).permute(&inverse);
+        Tensor::from_row_major(shape, permuted.into_row_major())
+    }
+}
+
+impl<S> Tensor<S>
+where
+    S: Scalar,
+{
+    /// The mode-`mode` product with a `J x I` matrix, where `I` is the size along `mode`,
+    /// which multiplies every fiber along `mode` by the matrix and changes its size to `J`.
+    pub fn mode_product(&self, matrix: &Matrix<S>, mode: usize) -> Tensor<S> {
+        assert!(
+            mode < self.rank() && matrix.dim().cols == self.shape[mode],
+            "Matrix of size {} cannot multiply mode {} of a tensor of shape {}",
+            matrix.dim(),
+            mode,
+            self.shape
+        );
+
+        let mut dims = self.shape.dims().to_vec();
+        dims[mode] = matrix.dim().rows;
+        Tensor::fold(&(matrix * &self.unfold(mode)), mode, dims)
+    }
+}
+
+/// A CP (CANDECOMP/PARAFAC) decomposition, as returned by `Tensor::cp_als`: a weighted sum
+/// of `rank` outer products of vectors.
+#[derive(Debug, Clone)]
+pub struct Cp<S> {
+    /// The weight of each component
+    pub weights: Vector<S>,
+    /// One matrix per axis, with a column of unit length per component
+    pub factors: Vec<Matrix<S>>,
+    /// How well the decomposition fits: `1 - |X - Y| / |X|` for the tensor `X` and its
+    /// approximation `Y`, in the Frobenius norm
+    pub fit: S,
+    /// The number of alternating least squares sweeps done
+    pub iterations: usize,
+}
+
+impl<S> Cp<S>
+where
+    S: Scalar,
+{
+    /// The tensor approximated by the decomposition.
+    pub fn to_tensor(&self) -> Tensor<S> {
+        let shape: Vec<usize> = self.factors.iter().map(|f| f.dim().rows).collect();
+        let mut first = self.factors[0].clone();
+        for row in 0..first.dim().rows {
+            for (e, weight) in first.row_mut(row).iter_mut().zip(self.weights.iter()) {
+                *e *= weight.clone();
+            }
+        }
+
+        let rest = khatri_rao(&self.factors[1..], self.weights.len());
+        Tensor::fold(&(&first * &rest.transpose()), 0, shape)
+    }
+}
+
+/// A Tucker decomposition, as returned by `Tensor::tucker`: a core tensor multiplied along
+/// each axis by a factor matrix.
+#[derive(Debug, Clone)]
+pub struct Tucker<S> {
+    /// The core tensor, with one axis per factor
+    pub core: Tensor<S>,
+    /// One matrix per axis, with orthonormal columns
+    pub factors: Vec<Matrix<S>>,
+}
+
+impl<S> Tucker<S>
+where
+    S: Scalar,
+{
+    /// The tensor approximated by the decomposition.
+    pub fn to_tensor(&self) -> Tensor<S> {
+        self.factors
+            .iter()
+            .enumerate()
+            .fold(self.core.clone(), |tensor, (mode, factor)| {
+                tensor.mode_product(factor, mode)
+            })
+    }
+}
+
+/// The Khatri–Rao product of the factors in order, or a row of ones if there are none.
+fn khatri_rao<S: Scalar>(factors: &[Matrix<S>], rank: usize) -> Matrix<S> {
+    factors
+        .iter()
+        .fold(Matrix::ones((1, rank).into()), |product, factor| {
+            product.khatri_rao(factor)
+        })
+}
+
+/// The eigenvalues and eigenvectors of a symmetric matrix using the cyclic Jacobi method,
+/// with the eigenvalues in decreasing order and the eigenvectors as columns.
+fn symmetric_eigen<F: FloatScalar>(matrix: &Matrix<F>) -> (Vec<F>, Matrix<F>) {
+    let size = matrix.dim().rows;
+    let mut a = matrix.clone();
+    let mut vectors = Matrix::identity(size);
+    let two = F::one() + F::one();
+
+    for _ in 0..100 {
+        let mut total = F::zero();
+        let mut off_diagonal = F::zero();
+        for p in 0..size {
+            for q in 0..size {
+                let square = a[p][q] * a[p][q];
+                total += square;
+                if p != q {
+                    off_diagonal += square;
+                }
+            }
+        }
+        if off_diagonal <= F::epsilon() * F::epsilon() * total {
+            break;
+        }
+
+        for p in 0..size {
+            for q in p + 1..size {
+                if a[p][q].is_zero() {
+                    continue;
+                }
+
+                // The rotation in the (p, q) plane that zeroes a[p][q]
+                let theta = (a[q][q] - a[p][p]) / (two * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + F::one()).sqrt());
+                let c = (t * t + F::one()).sqrt().recip();
+                let s = t * c;
+
+                for k in 0..size {
+                    let (kp, kq) = (a[k][p], a[k][q]);
+                    a[k][p] = c * kp - s * kq;
+                    a[k][q] = s * kp + c * kq;
+                }
+                for k in 0..size {
+                    let (pk, qk) = (a[p][k], a[q][k]);
+                    a[p][k] = c * pk - s * qk;
+                    a[q][k] = s * pk + c * qk;
+                }
+                for k in 0..size {
+                    let (kp, kq) = (vectors[k][p], vectors[k][q]);
+                    vectors[k][p] = c * kp - s * kq;
+                    vectors[k][q] = s * kp + c * kq;
+                }
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..size).collect();
+    order.sort_by(|&i, &j| {
+        a[j][j]
+            .partial_cmp(&a[i][i])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let values = order.iter().map(|&i| a[i][i]).collect();
+    let mut sorted = Matrix::zeros(Dimensions::square(size));
+    for row in 0..size {
+        for (col, &i) in order.iter().enumerate() {
+            sorted[row][col] = vectors[row][i];
+        }
+    }
+    (values, sorted)
+}
+
+/// The pseudo-inverse of a symmetric matrix, ignoring eigenvalues that are negligible
+/// relative to the largest.
+fn symmetric_pseudo_inverse<F: FloatScalar>(matrix: &Matrix<F>) -> Matrix<F> {
+    let size = matrix.dim().rows;
+    let (values, vectors) = symmetric_eigen(matrix);
+    let largest = values
+        .iter()
+        .fold(F::zero(), |max, value| max.max(value.abs()));
+    let cutoff = largest * F::epsilon() * <F as NumCast>::from(size.max(1)).unwrap();
+
+    let mut scaled = vectors.clone();
+    for row in 0..size {
+        for (e, &value) in scaled.row_mut(row).iter_mut().zip(&values) {
+            *e = if value.abs() > cutoff {
+                *e / value
+            } else {
+                F::zero()
+            };
+        }
+    }
+    &scaled * &vectors.transpose()
+}
+
+/// The square root of the sum of squares of the elements.
+fn frobenius_norm<'a, F: FloatScalar + 'a>(elements: impl Iterator<Item = &'a F>) -> F {
+    elements.fold(F::zero(), |sum, &e| sum + e * e).sqrt()
+}
+
+impl<F> Tensor<F>
+where
+    F: FloatScalar,
+{
+    /// The `rank` leading left singular vectors of the mode-`mode` unfolding, as columns.
+    fn leading_singular_vectors(&self, mode: usize, rank: usize) -> Matrix<F> {
+        let unfolded = self.unfold(mode);
+        let gram = &unfolded * &unfolded.transpose();
+        let (_, vectors) = symmetric_eigen(&gram);
+
+        let size = self.shape[mode];
+        let mut leading = Matrix::zeros((size, rank).into());
+        for row in 0..size {
+            leading
+                .row_mut(row)
+                .copy_from_slice(&vectors.row(row)[..rank]);
+        }
+        leading
+    }
+
+    /// The truncated higher-order SVD: a Tucker decomposition with a core of shape `ranks`.
+    ///
+    /// The factor for each axis holds the leading left singular vectors of the unfolding
+    /// along that axis, and the core is the tensor projected onto them. With each rank equal
+    /// to the size of its axis, the decomposition is exact.
+    /// ```
+    /// # use land::Tensor;
+    /// # fn main() {
+    /// let t = Tensor::from_row_major([2, 3, 2], (0..12).map(f64::from).collect());
+    ///
+    /// let tucker = t.tucker(&[2, 2, 2]);
+    /// assert_eq!(tucker.core.shape().dims(), [2, 2, 2]);
+    ///
+    /// let error = &tucker.to_tensor() - &t;
+    /// assert!(error.iter().all(|e| e.abs() < 1e-9));
+    /// # }
+    /// ```
+    pub fn tucker(&self, ranks: &[usize]) -> Tucker<F> {
+        assert!(
+            ranks.len() == self.rank()
+                && ranks.iter().zip(self.shape.dims()).all(|(r, size)| r <= size),
+            "Tucker ranks must have one rank per axis, each at most its size. Ranks were {:?} and shape {}",
+            ranks,
+            self.shape
+        );
+
+        let factors: Vec<Matrix<F>> = ranks
+            .iter()
+            .enumerate()
+            .map(|(mode, &rank)| self.leading_singular_vectors(mode, rank))
+            .collect();
+        let core = factors
+            .iter()
+            .enumerate()
+            .fold(self.clone(), |core, (mode, factor)| {
+                core.mode_product(&factor.transpose(), mode)
+            });
+
+        Tucker { core, factors }
+    }
+
+    /// Compute a CP decomposition with `rank` components using alternating least squares.
+    ///
+    /// Each sweep solves for one factor at a time with the others fixed. The sweeps stop
+    /// after `max_iterations`, or once the fit improves by less than `tolerance`. The
+    /// factors start from the leading singular vectors of each unfolding, so the result is
+    /// deterministic.
+    pub fn cp_als(&self, rank: usize, max_iterations: usize, tolerance: F) -> Cp<F> {
+        assert!(
+            self.rank() > 0 && rank > 0,
+            "CP decomposition needs a tensor with at least one axis and at least one component"
+        );
+
+        let mut factors: Vec<Matrix<F>> = (0..self.rank())
+            .map(|mode| {
+                let size = self.shape[mode];
+                let leading = rank.min(size);
+                let vectors = self.leading_singular_vectors(mode, leading);
+
+                // Fill any further columns with a fixed pseudo-random pattern
+                let mut factor = Matrix::zeros((size, rank).into());
+                let mut seed = 0x2545_f491u32 ^ mode as u32;
+                for row in 0..size {
+                    factor.row_mut(row)[..leading].copy_from_slice(vectors.row(row));
+                    for e in &mut factor.row_mut(row)[leading..] {
+                        seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                        *e = <F as NumCast>::from(seed >> 8).unwrap()
+                            / <F as NumCast>::from(1u32 << 24).unwrap();
+                    }
+                }
+                factor
+            })
+            .collect();
+
+        let unfolded: Vec<Matrix<F>> = (0..self.rank()).map(|mode| self.unfold(mode)).collect();
+        let norm = frobenius_norm(self.iter());
+        let mut weights = Vector::ones(rank);
+        let mut fit = F::zero();
+        let mut iterations = 0;
+
+        while iterations < max_iterations {
+            iterations += 1;
+
+            for mode in 0..self.rank() {
+                let others: Vec<Matrix<F>> = (0..self.rank())
+                    .filter(|&other| other != mode)
+                    .map(|other| factors[other].clone())
+                    .collect();
+
+                let mut gram = Matrix::ones(Dimensions::square(rank));
+                for other in &others {
+                    gram = gram.hadamard(&(&other.transpose() * other));
+                }
+
+                let product = &unfolded[mode] * &khatri_rao(&others, rank);
+                let mut factor = &product * &symmetric_pseudo_inverse(&gram);
+
+                let lengths = factor.transpose().map(|e| e * e).sum_rows();
+                for (r, weight) in weights.iter_mut().enumerate() {
+                    *weight = lengths[r][0].sqrt();
+                }
+                for row in 0..factor.dim().rows {
+                    for (e, weight) in factor.row_mut(row).iter_mut().zip(weights.iter()) {
+                        if !weight.is_zero() {
+                            *e /= *weight;
+                        }
+                    }
+                }
+                factors[mode] = factor;
+            }
+
+            let approximation = Cp {
+                weights: weights.clone(),
+                factors: factors.clone(),
+                fit,
+                iterations,
+            }
+            .to_tensor();
+            let residual = frobenius_norm((&approximation - self).iter());
+            let previous = fit;
+            fit = if norm.is_zero() {
+                F::one()
+            } else {
+                F::one() - residual / norm
+            };
+
+            if (fit - previous).abs() < tolerance {
+                break;
+            }
+        }
+
+        Cp {
+            weights,
+            factors,
+            fit,
+            iterations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arange(shape: &[usize]) -> Tensor<f64> {
+        let len: usize = shape.iter().product();
+        Tensor::from_row_major(shape, (0..len).map(|i| i as f64).collect())
+    }
+
+    fn assert_close(a: &Tensor<f64>, b: &Tensor<f64>, tolerance: f64) {
+        assert_eq!(a.shape(), b.shape());
+        for (a, b) in a.iter().zip(b.iter()) {
+            assert!((a - b).abs() < tolerance, "{} is not close to {}", a, b);
+        }
+    }
+
+    #[test]
+    fn unfold_and_fold() {
+        let t = arange(&[2, 3, 2]);
+
+        assert_eq!(
+            t.unfold(1),
+            mat![
+                [0.0, 1.0, 6.0, 7.0],
+                [2.0, 3.0, 8.0, 9.0],
+                [4.0, 5.0, 10.0, 11.0]
+            ]
+        );
+        for mode in 0..3 {
+            assert_eq!(Tensor::fold(&t.unfold(mode), mode, [2, 3, 2]), t);
+        }
+
+        let m = mat![[1.0, 0.0, -1.0]];
+        let product = t.mode_product(&m, 1);
+        assert_eq!(product.shape().dims(), [2, 1, 2]);
+        assert_eq!(product.into_row_major(), vec![-4.0, -4.0, -4.0, -4.0]);
+    }
+
+    #[test]
+    fn jacobi_eigenvalues() {
+        let a = mat![[4.0, 1.0, 0.0], [1.0, 3.0, 1.0], [0.0, 1.0, 2.0]];
+
+        let (values, vectors) = symmetric_eigen(&a);
+        assert!(values.windows(2).all(|pair| pair[0] >= pair[1]));
+        for (i, value) in values.iter().enumerate() {
+            let column =
+                Matrix::from_row_major((3, 1).into(), (0..3).map(|r| vectors[r][i]).collect());
+            let residual = &(&a * &column) - &column.scale(*value);
+            assert!(residual.iter_rows().all(|e: &f64| e.abs() < 1e-12));
+        }
+    }
+
+    #[test]
+    fn tucker_decomposition() {
+        let t = arange(&[3, 4, 5]).map(|e: f64| (e * 0.7).sin());
+
+        let full = t.tucker(&[3, 4, 5]);
+        assert_close(&full.to_tensor(), &t, 1e-9);
+        for factor in &full.factors {
+            let gram = &factor.transpose() * factor;
+            let identity = Matrix::identity(gram.dim().rows);
+            assert!((&gram - &identity).iter_rows().all(|e| e.abs() < 1e-9));
+        }
+
+        // A tensor with multilinear rank (1, 2, 2) is recovered from a truncated core
+        let low = Tucker {
+            core: Tensor::from_row_major([1, 2, 2], vec![3.0, 1.0, -2.0, 0.5]),
+            factors: vec![
+                mat![[1.0], [2.0], [-1.0]],
+                mat![[1.0, 0.0], [1.0, 1.0], [0.0, 2.0], [3.0, -1.0]],
+                mat![[1.0, 1.0], [0.0, 1.0], [2.0, 0.0], [1.0, -1.0], [0.5, 0.5]],
+            ],
+        }
+        .to_tensor();
+        let truncated = low.tucker(&[1, 2, 2]);
+        assert_eq!(truncated.core.shape().dims(), [1, 2, 2]);
+        assert_close(&truncated.to_tensor(), &low, 1e-9);
+    }
+
+    #[test]
+    fn cp_decomposition() {
+        let factors = vec![
+            mat![[1.0, 0.5], [2.0, -1.0], [-1.0, 1.0]],
+            mat![[1.0, 2.0], [0.0, 1.0], [1.0, 1.0], [-2.0, 0.0]],
+            mat![[3.0, 1.0], [1.0, -1.0], [0.0, 2.0], [1.0, 1.0], [2.0, 0.0]],
+        ];
+        let t = Cp {
+            weights: mat![1.0, 1.0],
+            factors,
+            fit: 1.0,
+            iterations: 0,
+        }
+        .to_tensor();
+
+        let cp = t.cp_als(2, 500, 1e-12);
+        assert!(cp.fit > 0.9999, "fit was {}", cp.fit);
+        assert_close(&cp.to_tensor(), &t, 1e-3);
+        for factor in &cp.factors {
+            let lengths = factor.transpose().map(|e| e * e).sum_rows();
+            assert!(lengths.iter_rows().all(|e| (e - 1.0).abs() < 1e-9));
+        }
+
+        let rank_one = Tensor::from_row_major([2, 2], vec![1.0, 2.0, 2.0, 4.0]);
+        let cp = rank_one.cp_als(1, 10, 1e-12);
+        assert!((cp.weights[0] - 5.0f64).abs() < 1e-9);
+        assert!(cp.iterations < 10);
+    }
+}
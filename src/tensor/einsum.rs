@@ -0,0 +1,539 @@
+use super::{Offsets, Tensor};
+use crate::{matrix::Matrix, traits::Scalar, vector::Vector};
+use std::borrow::Cow;
+
+/// The optimal contraction order is searched exhaustively up to this many operands, and
+/// chosen greedily beyond it.
+const OPTIMAL_OPERANDS: usize = 8;
+
+/// A value that can be an operand of [`einsum`].
+pub trait EinsumOperand<S: Clone> {
+    /// View the operand as a tensor, copying it if it is not one already.
+    fn as_tensor(&self) -> Cow<'_, Tensor<S>>;
+}
+
+impl<S: Clone> EinsumOperand<S> for Tensor<S> {
+    fn as_tensor(&self) -> Cow<'_, Tensor<S>> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<S: Clone> EinsumOperand<S> for Matrix<S> {
+    fn as_tensor(&self) -> Cow<'_, Tensor<S>> {
+        Cow::Owned(Tensor::from(self.clone()))
+    }
+}
+
+impl<S: Clone> EinsumOperand<S> for Vector<S> {
+    fn as_tensor(&self) -> Cow<'_, Tensor<S>> {
+        Cow::Owned(Tensor::from_row_major(
+            [self.len()],
+            self.iter().cloned().collect(),
+        ))
+    }
+}
+
+/// Contract tensors using Einstein summation notation.
+///
+/// The subscripts name one index per axis of each operand, separated by commas, followed by
+/// `->` and the indices of the result. Indices that are not in the result are summed over.
+/// Without `->`, the result has the indices that appear exactly once, in alphabetical order.
+///
+/// With three or more operands, they are contracted pairwise in the order that needs the
+/// fewest multiplications.
+/// ```
+/// # use land::{einsum, mat, Matrix};
+/// # use std::convert::TryFrom;
+/// # fn main() {
+/// let a = mat![[1, 2], [3, 4]];
+/// let b = mat![[5, 6], [7, 8]];
+/// let v = mat![1, -1];
+///
+/// let ab = einsum("ij,jk->ik", &[&a, &b]);
+/// assert_eq!(Matrix::try_from(ab).unwrap(), &a * &b);
+///
+/// assert_eq!(einsum("ii", &[&a])[[]], 5);
+/// assert_eq!(einsum("i,ij,j", &[&v, &a, &v])[[]], 0);
+/// assert_eq!(einsum("i,j->ij", &[&v, &v]).into_row_major(), vec![1, -1, -1, 1]);
+/// # }
+/// ```
+pub fn einsum<S: Scalar>(subscripts: &str, operands: &[&dyn EinsumOperand<S>]) -> Tensor<S> {
+    let spec = Subscripts::parse(subscripts, operands.len());
+    let tensors: Vec<Cow<Tensor<S>>> = operands.iter().map(|operand| operand.as_tensor()).collect();
+
+    let mut sizes: Vec<Option<(usize, usize)>> = vec![None; spec.names.len()];
+    for (i, (tensor, labels)) in tensors.iter().zip(&spec.inputs).enumerate() {
+        assert!(
+            tensor.rank() == labels.len(),
+            "Operand {} has shape {} but einsum subscripts \"{}\" give it {} indices",
+            i,
+            tensor.shape(),
+            subscripts,
+            labels.len()
+        );
+
+        for (&label, &size) in labels.iter().zip(tensor.shape().dims()) {
+            match sizes[label] {
+                Some((first, operand)) => assert!(
+                    first == size,
+                    "Index '{}' has size {} in operand {} but size {} in operand {}",
+                    spec.names[label],
+                    first,
+                    operand,
+                    size,
+                    i
+                ),
+                None => sizes[label] = Some((size, i)),
+            }
+        }
+    }
+
+    let contraction = Contraction {
+        masks: spec.inputs.iter().map(|labels| mask(labels)).collect(),
+        output: mask(&spec.output),
+        sizes: sizes.into_iter().map(|size| size.unwrap().0).collect(),
+    };
+    let path = contraction.path();
+
+    let operands: Vec<(&Tensor<S>, &[usize])> = tensors
+        .iter()
+        .map(|tensor| tensor.as_ref())
+        .zip(spec.inputs.iter().map(|labels| labels.as_slice()))
+        .collect();
+    contraction.evaluate(&path, &operands, &spec.output)
+}
+
+/// Parsed einsum subscripts, with each index numbered by its position in `names`.
+struct Subscripts {
+    names: Vec<char>,
+    inputs: Vec<Vec<usize>>,
+    output: Vec<usize>,
+}
+
+impl Subscripts {
+    fn parse(subscripts: &str, operands: usize) -> Subscripts {
+        let spec: String = subscripts.chars().filter(|c| !c.is_whitespace()).collect();
+        let (inputs, output) = match spec.find("->") {
+            Some(arrow) => (&spec[..arrow], Some(&spec[arrow + 2..])),
+            None => (spec.as_str(), None),
+        };
+
+        if let Some(c) = spec
+            .chars()
+            .find(|&c| !c.is_ascii_alphabetic() && !",->".contains(c))
+        {
+            panic!(
+                "Einsum subscripts \"{}\" contain invalid character '{}'",
+                subscripts, c
+            );
+        }
+        let inputs: Vec<&str> = inputs.split(',').collect();
+        assert!(
+            inputs.len() == operands,
+            "Einsum subscripts \"{}\" name {} operands but {} were given",
+            subscripts,
+            inputs.len(),
+            operands
+        );
+        assert!(
+            !inputs.iter().chain(&output).any(|s| s.contains(['-', '>'])),
+            "Einsum subscripts \"{}\" must contain at most one '->'",
+            subscripts
+        );
+
+        let mut names: Vec<char> = inputs.iter().flat_map(|s| s.chars()).collect();
+        names.sort_unstable();
+        names.dedup();
+        let label = |c: char| names.binary_search(&c).ok();
+
+        let output: Vec<char> = match output {
+            Some(output) => output.chars().collect(),
+            None => names
+                .iter()
+                .copied()
+                .filter(|&c| {
+                    inputs
+                        .iter()
+                        .flat_map(|s| s.chars())
+                        .filter(|&d| d == c)
+                        .count()
+                        == 1
+                })
+                .collect(),
+        };
+        for (i, &c) in output.iter().enumerate() {
+            assert!(
+                label(c).is_some(),
+                "Output index '{}' does not appear in the inputs of einsum subscripts \"{}\"",
+                c,
+                subscripts
+            );
+            assert!(
+                !output[..i].contains(&c),
+                "Output index '{}' is repeated in einsum subscripts \"{}\"",
+                c,
+                subscripts
+            );
+        }
+
+        Subscripts {
+            inputs: inputs
+                .iter()
+                .map(|s| s.chars().map(|c| label(c).unwrap()).collect())
+                .collect(),
+            output: output.into_iter().map(|c| label(c).unwrap()).collect(),
+            names,
+        }
+    }
+}
+
+/// The set of labels as a bit mask.
+fn mask(labels: &[usize]) -> u64 {
+    labels.iter().fold(0, |mask, &label| mask | 1 << label)
+}
+
+/// The order in which operands are contracted pairwise.
+#[derive(Debug, PartialEq)]
+enum Path {
+    Operand(usize),
+    Pair(Box<Path>, Box<Path>),
+}
+
+impl Path {
+    /// The operands contracted by this part of the path, as a bit mask.
+    fn operands(&self) -> u64 {
+        match self {
+            Path::Operand(i) => 1 << i,
+            Path::Pair(a, b) => a.operands() | b.operands(),
+        }
+    }
+}
+
+/// The labels of each operand and the result, and the size of each label.
+struct Contraction {
+    masks: Vec<u64>,
+    output: u64,
+    sizes: Vec<usize>,
+}
+
+impl Contraction {
+    /// The labels of a set of operands.
+    fn labels(&self, operands: u64) -> u64 {
+        (0..self.masks.len())
+            .filter(|i| operands >> i & 1 == 1)
+            .fold(0, |mask, i| mask | self.masks[i])
+    }
+
+    /// The labels that remain after contracting a set of operands, because they are in the
+    /// result or still to be contracted with another operand.
+    fn kept(&self, operands: u64) -> u64 {
+        let all = u64::MAX >> (64 - self.masks.len());
+        self.labels(operands) & (self.output | self.labels(all & !operands))
+    }
+
+    /// The number of combinations of a set of labels.
+    fn size(&self, labels: u64) -> usize {
+        (0..self.sizes.len())
+            .filter(|i| labels >> i & 1 == 1)
+            .fold(1, |size, i| size.saturating_mul(self.sizes[i]))
+    }
+
+    /// The number of multiplications needed to contract the results of two sets of operands.
+    fn cost(&self, a: u64, b: u64) -> usize {
+        self.size(self.kept(a) | self.kept(b))
+    }
+
+    fn path(&self) -> Path {
+        assert!(
+            self.masks.len() <= 64,
+            "Einsum supports at most 64 operands, {} were given",
+            self.masks.len()
+        );
+
+        if self.masks.len() <= OPTIMAL_OPERANDS {
+            self.optimal_path()
+        } else {
+            self.greedy_path()
+        }
+    }
+
+    /// Find the cheapest contraction order by trying every way to split every set of
+    /// operands into two.
+    fn optimal_path(&self) -> Path {
+        let all = (1usize << self.masks.len()) - 1;
+        let mut best = vec![(0, 0); all + 1];
+
+        for set in 1..=all {
+            if set.is_power_of_two() {
+                continue;
+            }
+
+            best[set] = (usize::MAX, 0);
+            let lowest = set & set.wrapping_neg();
+            let mut part = (set - 1) & set;
+            while part != 0 {
+                // Each split is seen twice, so only consider the half with the lowest operand
+                if part & lowest != 0 {
+                    let rest = set ^ part;
+                    let cost = self
+                        .cost(part as u64, rest as u64)
+                        .saturating_add(best[part].0)
+                        .saturating_add(best[rest].0);
+                    if cost < best[set].0 {
+                        best[set] = (cost, part);
+                    }
+                }
+                part = (part - 1) & set;
+            }
+        }
+
+        fn build(best: &[(usize, usize)], set: usize) -> Path {
+            if set.is_power_of_two() {
+                Path::Operand(set.trailing_zeros() as usize)
+            } else {
+                let part = best[set].1;
+                Path::Pair(
+                    Box::new(build(best, part)),
+                    Box::new(build(best, set ^ part)),
+                )
+            }
+        }
+        build(&best, all)
+    }
+
+    /// Repeatedly contract the pair that is cheapest to contract, preferring smaller results.
+    fn greedy_path(&self) -> Path {
+        let mut paths: Vec<(u64, Path)> = (0..self.masks.len())
+            .map(|i| (1 << i, Path::Operand(i)))
+            .collect();
+
+        while paths.len() > 1 {
+            let mut best = (usize::MAX, usize::MAX, 0, 1);
+            for i in 0..paths.len() {
+                for j in i + 1..paths.len() {
+                    let (a, b) = (paths[i].0, paths[j].0);
+                    let cost = (self.cost(a, b), self.size(self.kept(a | b)), i, j);
+                    if cost < best {
+                        best = cost;
+                    }
+                }
+            }
+
+            let (_, _, i, j) = best;
+            let (b, second) = paths.swap_remove(j);
+            let (a, first) = paths.swap_remove(i);
+            paths.push((a | b, Path::Pair(Box::new(first), Box::new(second))));
+        }
+
+        paths.pop().unwrap().1
+    }
+
+    /// Contract the operands along a path, with the result indexed by `output`.
+    fn evaluate<S: Scalar>(
+        &self,
+        path: &Path,
+        operands: &[(&Tensor<S>, &[usize])],
+        output: &[usize],
+    ) -> Tensor<S> {
+        match path {
+            Path::Operand(i) => contract(&operands[*i..*i + 1], output, &self.sizes),
+            Path::Pair(a, b) => {
+                let (a, a_labels) = self.intermediate(a, operands);
+                let (b, b_labels) = self.intermediate(b, operands);
+                contract(&[(&a, &a_labels), (&b, &b_labels)], output, &self.sizes)
+            }
+        }
+    }
+
+    /// Evaluate part of a path, keeping the labels still needed in increasing order.
+    fn intermediate<'a, S: Scalar>(
+        &self,
+        path: &Path,
+        operands: &[(&'a Tensor<S>, &[usize])],
+    ) -> (Cow<'a, Tensor<S>>, Vec<usize>) {
+        match path {
+            Path::Operand(i) => (Cow::Borrowed(operands[*i].0), operands[*i].1.to_vec()),
+            Path::Pair(..) => {
+                let kept = self.kept(path.operands());
+                let labels: Vec<usize> = (0..self.sizes.len())
+                    .filter(|label| kept >> label & 1 == 1)
+                    .collect();
+                (Cow::Owned(self.evaluate(path, operands, &labels)), labels)
+            }
+        }
+    }
+}
+
+/// Multiply the operands together, summing over every label that is not in `output`.
+///
+/// The labels of an operand may repeat, which takes its diagonal along those axes.
+fn contract<S: Scalar>(
+    operands: &[(&Tensor<S>, &[usize])],
+    output: &[usize],
+    sizes: &[usize],
+) -> Tensor<S> {
+    let mut labels = output.to_vec();
+    for (_, operand_labels) in operands {
+        for &label in operand_labels.iter() {
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+    }
+
+    // Visiting every combination of labels in row-major order, with the summed labels last,
+    // visits the terms of each output element consecutively
+    let dims: Vec<usize> = labels.iter().map(|&label| sizes[label]).collect();
+    let strides: Vec<Vec<usize>> = operands
+        .iter()
+        .map(|(tensor, operand_labels)| {
+            labels
+                .iter()
+                .map(|&label| {
+                    operand_labels
+                        .iter()
+                        .zip(tensor.strides())
+                        .filter(|&(&l, _)| l == label)
+                        .map(|(_, &stride)| stride)
+                        .sum()
+                })
+                .collect()
+        })
+        .collect();
+    let mut offsets: Vec<Offsets> = strides
+        .iter()
+        .map(|strides| Offsets::new(&dims, strides, 0))
+        .collect();
+
+    let len: usize = dims[..output.len()].iter().product();
+    let terms: usize = dims[output.len()..].iter().product();
+    let mut elements = Vec::with_capacity(len);
+    for _ in 0..len {
+        let mut sum = S::zero();
+        for _ in 0..terms {
+            let mut product = S::one();
+            for ((tensor, _), offsets) in operands.iter().zip(&mut offsets) {
+                product *= tensor.elements[offsets.next().unwrap()].clone();
+            }
+            sum += product;
+        }
+        elements.push(sum);
+    }
+
+    Tensor::from_row_major(&dims[..output.len()], elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    fn arange(shape: &[usize]) -> Tensor<i64> {
+        let len: usize = shape.iter().product();
+        Tensor::from_row_major(shape, (0..len as i64).map(|i| i * i % 7 - 3).collect())
+    }
+
+    fn matrix(tensor: Tensor<i64>) -> Matrix<i64> {
+        Matrix::try_from(tensor).unwrap()
+    }
+
+    #[test]
+    fn matrices_and_vectors() {
+        let a = matrix(arange(&[3, 4]));
+        let b = matrix(arange(&[4, 2]));
+        let u = mat![1, 2, 3];
+        let v = mat![4, -5];
+
+        assert_eq!(matrix(einsum("ij,jk->ik", &[&a, &b])), &a * &b);
+        assert_eq!(matrix(einsum("ij,jk", &[&a, &b])), &a * &b);
+        assert_eq!(
+            matrix(einsum("jk,ij->ki", &[&b, &a])),
+            (&a * &b).transpose()
+        );
+        assert_eq!(matrix(einsum("ij->ji", &[&a])), a.transpose());
+        assert_eq!(matrix(einsum("i,j->ij", &[&u, &v])), u.mul_transpose(&v));
+        assert_eq!(
+            einsum("ij,j->i", &[&b, &v]).into_row_major(),
+            (&b * &v).to_vec()
+        );
+
+        let square = &a * &a.transpose();
+        let trace: i64 = (0..3).map(|i| square[i][i]).sum();
+        assert_eq!(einsum("ii", &[&square])[[]], trace);
+        assert_eq!(einsum("ii->i", &[&square])[[2]], square[2][2]);
+        assert_eq!(einsum("ij->", &[&a])[[]], a.iter_rows().sum::<i64>());
+    }
+
+    #[test]
+    fn batched_and_higher_rank() {
+        let a = arange(&[5, 3, 4]);
+        let b = arange(&[5, 4, 2]);
+
+        let c = einsum("bij,bjk->bik", &[&a, &b]);
+        assert_eq!(c.shape().dims(), [5, 3, 2]);
+        for batch in 0..5 {
+            let product = &matrix(a.index_axis(0, batch)) * &matrix(b.index_axis(0, batch));
+            assert_eq!(matrix(c.index_axis(0, batch)), product);
+        }
+
+        // A permuted operand is read through its strides
+        let permuted = b.clone().permute(&[0, 2, 1]);
+        assert_eq!(
+            einsum("ijk,imk->jm", &[&a, &permuted]),
+            einsum("ijk,ikm->jm", &[&a, &b])
+        );
+    }
+
+    #[test]
+    fn contraction_order() {
+        let a = Matrix::<i64>::ones((20, 20).into());
+        let b = Matrix::<i64>::ones((20, 20).into());
+        let v = Vector::new(vec![1; 20]);
+
+        // Multiplying the vector first avoids the matrix product
+        let contraction = Contraction {
+            masks: vec![0b011, 0b110, 0b100],
+            output: 0b001,
+            sizes: vec![20, 20, 20],
+        };
+        assert_eq!(
+            contraction.optimal_path(),
+            Path::Pair(
+                Box::new(Path::Operand(0)),
+                Box::new(Path::Pair(
+                    Box::new(Path::Operand(1)),
+                    Box::new(Path::Operand(2))
+                ))
+            )
+        );
+        assert_eq!(contraction.greedy_path(), contraction.optimal_path());
+        assert_eq!(
+            einsum("ij,jk,k->i", &[&a, &b, &v]).into_row_major(),
+            vec![400; 20]
+        );
+
+        let operands: Vec<Vector<i64>> = (1..=10).map(|i| mat![i, -i]).collect();
+        let refs: Vec<&dyn EinsumOperand<i64>> = operands.iter().map(|v| v as _).collect();
+        let product: i64 = (1..=10i64).product();
+        assert_eq!(einsum("a,b,c,d,e,f,g,h,i,a->", &refs)[[]], 0);
+        assert_eq!(
+            einsum("i,i,i,i,i,i,i,i,i,i->i", &refs).into_row_major(),
+            vec![product, product]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Index 'j' has size 4 in operand 0 but size 3 in operand 1")]
+    fn mismatched_sizes() {
+        let a = Matrix::<f64>::zeros((3, 4).into());
+        einsum("ij,jk->ik", &[&a, &a]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Operand 0 has shape 3x4 but einsum subscripts \"ijk\" give it 3 indices"
+    )]
+    fn wrong_rank() {
+        einsum("ijk", &[&Matrix::<f64>::zeros((3, 4).into())]);
+    }
+}
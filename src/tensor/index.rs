@@ -0,0 +1,70 @@
+use super::*;
+use std::ops::{Index, IndexMut};
+
+impl<S> Index<&[usize]> for Tensor<S> {
+    type Output = S;
+
+    /// Get the element at an index with one coordinate per axis.
+    fn index(&self, index: &[usize]) -> &S {
+        match self.offset(index) {
+            Some(offset) => &self.elements[offset],
+            None => panic!(
+                "Index {:?} is out of bounds for a tensor of shape {}",
+                index, self.shape
+            ),
+        }
+    }
+}
+
+impl<S> IndexMut<&[usize]> for Tensor<S> {
+    /// Get the element at an index with one coordinate per axis mutably.
+    fn index_mut(&mut self, index: &[usize]) -> &mut S {
+        match self.offset(index) {
+            Some(offset) => &mut self.elements[offset],
+            None => panic!(
+                "Index {:?} is out of bounds for a tensor of shape {}",
+                index, self.shape
+            ),
+        }
+    }
+}
+
+impl<S, const N: usize> Index<[usize; N]> for Tensor<S> {
+    type Output = S;
+
+    /// Get the element at an index with one coordinate per axis.
+    fn index(&self, index: [usize; N]) -> &S {
+        &self[&index[..]]
+    }
+}
+
+impl<S, const N: usize> IndexMut<[usize; N]> for Tensor<S> {
+    /// Get the element at an index with one coordinate per axis mutably.
+    fn index_mut(&mut self, index: [usize; N]) -> &mut S {
+        &mut self[&index[..]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_any_rank() {
+        let mut t = Tensor::<i32>::zeros([2, 3, 4, 5]);
+
+        t[[1, 2, 3, 4]] = 7;
+        t[&[0, 1, 0, 2][..]] += 1;
+
+        assert_eq!(t[[1, 2, 3, 4]], 7);
+        assert_eq!(t.get(&[0, 1, 0, 2]), Some(&1));
+        assert_eq!(t.get(&[0, 3, 0, 0]), None);
+        assert_eq!(t.iter().sum::<i32>(), 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index [0, 3] is out of bounds for a tensor of shape 2x3")]
+    fn index_out_of_bounds() {
+        let _ = Tensor::<i32>::zeros([2, 3])[[0, 3]];
+    }
+}
@@ -0,0 +1,160 @@
+use super::{Offsets, Shape, Tensor};
+use crate::traits::Scalar;
+use std::ops::{Add, Div, Mul, Sub};
+
+impl<S> Tensor<S> {
+    /// The strides that read the tensor as if it had a larger `shape`, which are 0 along new
+    /// leading axes and stretched axes of size 1. `None` if it does not broadcast to `shape`.
+    fn broadcast_strides(&self, shape: &Shape) -> Option<Vec<usize>> {
+        let leading = shape.rank().checked_sub(self.rank())?;
+
+        (0..shape.rank())
+            .map(|axis| {
+                if axis < leading {
+                    return Some(0);
+                }
+
+                let size = self.shape[axis - leading];
+                if size == shape[axis] {
+                    Some(self.strides[axis - leading])
+                } else if size == 1 {
+                    Some(0)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl<S> Tensor<S>
+where
+    S: Clone,
+{
+    /// Copy the tensor into a larger shape, repeating it along new leading axes and axes of
+    /// size 1.
+    pub fn broadcast_to<T: Into<Shape>>(&self, shape: T) -> Tensor<S> {
+        let shape = shape.into();
+        let strides = match self.broadcast_strides(&shape) {
+            Some(strides) => strides,
+            None => panic!(
+                "Tensor of shape {} cannot be broadcast to shape {}",
+                self.shape, shape
+            ),
+        };
+
+        let elements = Offsets::new(shape.dims(), &strides, 0)
+            .map(|offset| self.elements[offset].clone())
+            .collect();
+        Tensor::from_row_major(shape, elements)
+    }
+}
+
+/// Combine the elements of two tensors after broadcasting them to a common shape.
+fn zip_broadcast<S, F>(lhs: &Tensor<S>, rhs: &Tensor<S>, mut f: F) -> Tensor<S>
+where
+    S: Clone,
+    F: FnMut(S, S) -> S,
+{
+    let shape = match lhs.shape.broadcast(&rhs.shape) {
+        Some(shape) => shape,
+        None => panic!(
+            "Tensor shapes must agree or be 1. Left hand side is of shape {} and right hand side is of shape {}",
+            lhs.shape, rhs.shape
+        ),
+    };
+    let lhs_strides = lhs.broadcast_strides(&shape).unwrap();
+    let rhs_strides = rhs.broadcast_strides(&shape).unwrap();
+
+    let elements = Offsets::new(shape.dims(), &lhs_strides, 0)
+        .zip(Offsets::new(shape.dims(), &rhs_strides, 0))
+        .map(|(a, b)| f(lhs.elements[a].clone(), rhs.elements[b].clone()))
+        .collect();
+    Tensor::from_row_major(shape, elements)
+}
+
+// Elementwise operators broadcast following the NumPy rules, see `Shape::broadcast`.
+macro_rules! impl_elementwise {
+    ($trait:ident, $fn:ident) => {
+        impl<S> $trait<&Tensor<S>> for &Tensor<S>
+        where
+            S: Scalar,
+        {
+            type Output = Tensor<S>;
+
+            fn $fn(self, rhs: &Tensor<S>) -> Self::Output {
+                zip_broadcast(self, rhs, |a, b| a.$fn(b))
+            }
+        }
+
+        impl<S> $trait<Tensor<S>> for &Tensor<S>
+        where
+            S: Scalar,
+        {
+            type Output = Tensor<S>;
+
+            fn $fn(self, rhs: Tensor<S>) -> Self::Output {
+                self.$fn(&rhs)
+            }
+        }
+
+        impl<S> $trait<&Tensor<S>> for Tensor<S>
+        where
+            S: Scalar,
+        {
+            type Output = Tensor<S>;
+
+            fn $fn(self, rhs: &Tensor<S>) -> Self::Output {
+                (&self).$fn(rhs)
+            }
+        }
+
+        impl<S> $trait<Tensor<S>> for Tensor<S>
+        where
+            S: Scalar,
+        {
+            type Output = Tensor<S>;
+
+            fn $fn(self, rhs: Tensor<S>) -> Self::Output {
+                (&self).$fn(&rhs)
+            }
+        }
+    };
+}
+
+impl_elementwise!(Add, add);
+impl_elementwise!(Sub, sub);
+impl_elementwise!(Mul, mul);
+impl_elementwise!(Div, div);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn broadcast_shapes() {
+        let a = Tensor::from_row_major([2, 1, 3], vec![1, 2, 3, 4, 5, 6]);
+        let b = Tensor::from_row_major([4, 1], vec![0, 10, 20, 30]);
+
+        let c = &a + &b;
+        assert_eq!(c.shape(), &Shape::from([2, 4, 3]));
+        assert_eq!(c[[1, 2, 0]], 24);
+        assert_eq!(c, a.broadcast_to([2, 4, 3]) + b.broadcast_to([2, 4, 3]));
+
+        // Permuted tensors broadcast through their strides
+        let t = Tensor::from_row_major([3, 2], vec![1, 2, 3, 4, 5, 6]).transpose();
+        let column = Tensor::from_row_major([2, 1], vec![1, 2]);
+        assert_eq!((t * column).into_row_major(), vec![1, 3, 5, 4, 8, 12]);
+
+        let scalar = Tensor::from_row_major(Shape::new(vec![]), vec![2]);
+        assert_eq!((&a - &scalar)[[1, 0, 2]], 4);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Tensor shapes must agree or be 1. Left hand side is of shape 2x3 and right hand side is of shape 2"
+    )]
+    fn incompatible_shapes() {
+        let _ = Tensor::<i32>::zeros([2, 3]) + Tensor::zeros([2]);
+    }
+}
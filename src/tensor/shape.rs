@@ -0,0 +1,112 @@
+use crate::matrix::{broadcast_axis, Dimensions};
+use std::{fmt, ops::Index};
+
+/// The shape of a tensor: the size along each of its axes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Shape {
+    dims: Vec<usize>,
+}
+
+impl Shape {
+    pub fn new(dims: Vec<usize>) -> Shape {
+        Shape { dims }
+    }
+
+    /// The number of axes.
+    pub fn rank(&self) -> usize {
+        self.dims.len()
+    }
+
+    /// The size along each axis.
+    pub fn dims(&self) -> &[usize] {
+        &self.dims
+    }
+
+    /// The total number of elements.
+    pub fn elements(&self) -> usize {
+        self.dims.iter().product()
+    }
+
+    /// The strides of a row-major tensor of this shape, where the last axis is contiguous.
+    pub fn strides(&self) -> Vec<usize> {
+        let mut strides = vec![1; self.rank()];
+        for axis in (1..self.rank()).rev() {
+            strides[axis - 1] = strides[axis] * self.dims[axis];
+        }
+        strides
+    }
+
+    /// The shape of the result of an elementwise operation, or `None` if the shapes are
+    /// incompatible.
+    ///
+    /// As in NumPy, the shapes are aligned at their last axis, missing leading axes count as
+    /// size 1 and an axis of size 1 stretches to match the other side.
+    pub fn broadcast(&self, other: &Shape) -> Option<Shape> {
+        let rank = self.rank().max(other.rank());
+        let size = |shape: &Shape, axis: usize| {
+            (axis + shape.rank())
+                .checked_sub(rank)
+                .map_or(1, |axis| shape.dims[axis])
+        };
+
+        (0..rank)
+            .map(|axis| broadcast_axis(size(self, axis), size(other, axis)))
+            .collect::<Option<Vec<usize>>>()
+            .map(Shape::new)
+    }
+}
+
+impl Index<usize> for Shape {
+    type Output = usize;
+
+    /// Get the size along an axis.
+    fn index(&self, axis: usize) -> &usize {
+        &self.dims[axis]
+    }
+}
+
+impl From<Vec<usize>> for Shape {
+    fn from(dims: Vec<usize>) -> Shape {
+        Shape { dims }
+    }
+}
+
+impl From<&[usize]> for Shape {
+    fn from(dims: &[usize]) -> Shape {
+        Shape {
+            dims: dims.to_vec(),
+        }
+    }
+}
+
+impl<const N: usize> From<[usize; N]> for Shape {
+    fn from(dims: [usize; N]) -> Shape {
+        Shape {
+            dims: dims.to_vec(),
+        }
+    }
+}
+
+impl From<Dimensions> for Shape {
+    fn from(dimensions: Dimensions) -> Shape {
+        Shape {
+            dims: vec![dimensions.rows, dimensions.cols],
+        }
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.dims.is_empty() {
+            return write!(f, "scalar");
+        }
+
+        for (axis, size) in self.dims.iter().enumerate() {
+            if axis != 0 {
+                write!(f, "x")?;
+            }
+            write!(f, "{}", size)?;
+        }
+        Ok(())
+    }
+}
@@ -1,12 +1,150 @@
-use num::{traits::NumAssign, Float, Num};
+use num::{rational::Ratio, traits::NumAssign, Complex, Float, Integer, Num};
 use std::{fmt::Debug, ops::Neg};
 
 /// A scalar value
-pub trait Scalar: Num + Neg<Output = Self> + NumAssign + Debug + Copy + Clone + Sized {}
+///
+/// Scalars only need to be `Clone`, so heap allocated numbers such as `BigRational` can be
+/// used as elements.
+pub trait Scalar: Num + Neg<Output = Self> + NumAssign + Debug + Clone + Sized {}
 
-impl<S> Scalar for S where S: Num + Neg<Output = Self> + NumAssign + Debug + Copy + Clone + Sized {}
+impl<S> Scalar for S where S: Num + Neg<Output = Self> + NumAssign + Debug + Clone + Sized {}
 
 /// A floating point scalar value
+///
+/// This requires `num::Float`, which requires `Copy`, so only primitive sized floats such as
+/// `f64` are covered. Heap allocated multi-precision floats cannot be used with the floating
+/// point algorithms. The elimination algorithms, such as `Matrix::solve`, only require a
+/// `Field`, so they work with heap allocated numbers like `BigRational`, or with a
+/// multi-precision float that implements `Field`.
 pub trait FloatScalar: Scalar + Float {}
 
 impl<S> FloatScalar for S where S: Scalar + Float {}
+
+/// A scalar that forms a field: every non-zero element has a multiplicative inverse, so
+/// division does not truncate like it does for integers.
+///
+/// Elimination algorithms such as `Matrix::rref`, `Matrix::solve` and `Matrix::inverse`
+/// require a field. Floating point types are included, although their division rounds.
+pub trait Field: Scalar {
+    /// Whether `self` is a better pivot for Gaussian elimination than the non-zero `other`.
+    ///
+    /// Exact fields keep the first non-zero element, while floating point types prefer the
+    /// larger magnitude to limit the growth of rounding errors.
+    fn is_better_pivot(&self, other: &Self) -> bool {
+        let _ = other;
+        false
+    }
+}
+
+macro_rules! impl_float_field {
+    ($($float:ty),*) => {
+        $(
+            impl Field for $float {
+                fn is_better_pivot(&self, other: &Self) -> bool {
+                    self.abs() > other.abs()
+                }
+            }
+        )*
+    };
+}
+
+impl_float_field!(f32, f64);
+#[cfg(feature = "half")]
+impl_float_field!(half::f16, half::bf16);
+
+impl<T> Field for Ratio<T>
+where
+    T: Integer + Clone + Debug + Neg<Output = T>,
+    Ratio<T>: Scalar,
+{
+}
+
+impl<T> Field for Complex<T>
+where
+    T: FloatScalar,
+{
+    fn is_better_pivot(&self, other: &Self) -> bool {
+        self.norm_sqr() > other.norm_sqr()
+    }
+}
+
+/// A value in a semiring: a set with an associative and commutative "addition" and an
+/// associative "multiplication" that distributes over it.
+///
+/// Every `Scalar` is a semiring under the usual `+` and `*`. Other semirings, such as
+/// `MinPlus` or `Boolean`, can be used to compute things like shortest paths or
+/// reachability with the same matrix product.
+pub trait Semiring: Clone {
+    /// The identity of `plus`, which is also absorbing for `times`.
+    fn additive_identity() -> Self;
+
+    /// The identity of `times`.
+    fn multiplicative_identity() -> Self;
+
+    /// Semiring addition
+    fn plus(&self, rhs: &Self) -> Self;
+
+    /// Semiring multiplication
+    fn times(&self, rhs: &Self) -> Self;
+}
+
+impl<S> Semiring for S
+where
+    S: Scalar,
+{
+    fn additive_identity() -> Self {
+        S::zero()
+    }
+
+    fn multiplicative_identity() -> Self {
+        S::one()
+    }
+
+    fn plus(&self, rhs: &Self) -> Self {
+        self.clone() + rhs.clone()
+    }
+
+    fn times(&self, rhs: &Self) -> Self {
+        self.clone() * rhs.clone()
+    }
+}
+
+/// A scalar whose sums of products are accumulated in a wider type to avoid losing
+/// precision, such as 16-bit floats accumulated in `f32`.
+///
+/// `dot` and the `*` operator accumulate in the element type. Use `dot_mixed`, `mul_mixed`
+/// and `mul_vector_mixed` to accumulate in the wider type.
+pub trait MixedPrecision: Scalar + Copy {
+    /// The type products are summed in
+    type Accumulator: Scalar;
+
+    /// Convert to the accumulator type without loss.
+    fn widen(self) -> Self::Accumulator;
+
+    /// Round an accumulated value back to this type.
+    fn narrow(acc: Self::Accumulator) -> Self;
+}
+
+macro_rules! impl_mixed_precision {
+    ($scalar:ty, $acc:ty, $widen:expr, $narrow:expr) => {
+        impl MixedPrecision for $scalar {
+            type Accumulator = $acc;
+
+            fn widen(self) -> $acc {
+                $widen(self)
+            }
+
+            fn narrow(acc: $acc) -> $scalar {
+                $narrow(acc)
+            }
+        }
+    };
+}
+
+impl_mixed_precision!(f32, f32, |x| x, |x| x);
+impl_mixed_precision!(f64, f64, |x| x, |x| x);
+
+#[cfg(feature = "half")]
+impl_mixed_precision!(half::f16, f32, half::f16::to_f32, half::f16::from_f32);
+#[cfg(feature = "half")]
+impl_mixed_precision!(half::bf16, f32, half::bf16::to_f32, half::bf16::from_f32);
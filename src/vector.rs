@@ -1,9 +1,17 @@
 pub(crate) mod operations;
 
-use crate::traits::{FloatScalar, Scalar};
+use crate::{
+    matrix::format::{shown_indices, TRUNCATE_THRESHOLD},
+    traits::{FloatScalar, Scalar},
+};
 use std::{fmt, ops::*};
 
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Vector<S> {
     elements: Vec<S>,
 }
@@ -17,12 +25,12 @@ impl<S> Vector<S> {
         self.elements.len()
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &S> {
-        self.elements.iter()
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
     }
 
-    pub fn into_iter(self) -> impl Iterator<Item = S> {
-        self.elements.into_iter()
+    pub fn iter(&self) -> impl Iterator<Item = &S> {
+        self.elements.iter()
     }
 
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut S> {
@@ -46,19 +54,14 @@ where
     }
 }
 
-impl<S> Vector<S>
-where
-    S: Copy,
-{
-    pub fn map<F>(mut self, mut f: F) -> Vector<S>
+impl<S> Vector<S> {
+    pub fn map<F>(self, f: F) -> Vector<S>
     where
         F: FnMut(S) -> S,
     {
-        for elem in self.elements.iter_mut() {
-            *elem = f(*elem);
+        Vector {
+            elements: self.elements.into_iter().map(f).collect(),
         }
-
-        self
     }
 }
 
@@ -78,7 +81,8 @@ where
 }
 
 macro_rules! impl_elementwise_operation {
-    ($name:ident ($($arg:ident: $type:ty),*)) => (
+    ($(#[$attr:meta])* $name:ident ($($arg:ident: $type:ty),*)) => (
+        $(#[$attr])*
         pub fn $name(self $(, $arg: $type)*) -> Vector<F> {
             self.map(|e| e.$name($($arg),*))
         }
@@ -89,86 +93,138 @@ impl<F> Vector<F>
 where
     F: FloatScalar,
 {
-    /// Elementwise absolute value
-    impl_elementwise_operation!(abs());
-
-    /// Elementwise signum:
-    /// - `1.0` if the number is positive, `+0.0` or `F::infinity()`.
-    /// - `-1.0` if the number is negative, `-0.0` or `F::neg_infinity()`.
-    /// - `F::nan()` if the number is `F::nan()`.
-    impl_elementwise_operation!(signum());
-
-    /// Elementwise maximum of two values
-    impl_elementwise_operation!(max(other: F));
-
-    /// Elementwise minimum of two values
-    impl_elementwise_operation!(min(other: F));
-
-    /// Elementwise natural logarithm
-    impl_elementwise_operation!(ln());
-
-    /// Elementwise base of arbitrary base
-    impl_elementwise_operation!(log(base: F));
-
-    /// Elementwise base 2 logarithm
-    impl_elementwise_operation!(log2());
-
-    /// Elementwise base 10 logarithm
-    impl_elementwise_operation!(log10());
-
-    /// Elementwise reciprocal: `1/(self)`
-    impl_elementwise_operation!(recip());
-
-    /// Raise to the power of an integer power elementwise
-    impl_elementwise_operation!(powi(n: i32));
-
-    /// Raise to the power of an integer power elementwise
-    impl_elementwise_operation!(powf(n: F));
-
-    /// Elementwise square root
-    impl_elementwise_operation!(sqrt());
-
-    /// Elementwise exponential function, `e^(self)`.
-    impl_elementwise_operation!(exp());
-
-    /// Elementwise `2^(self)`.
-    impl_elementwise_operation!(exp2());
-
-    /// Elementwise sine in radians
-    impl_elementwise_operation!(sin());
-
-    /// Elementwise cosine in radians
-    impl_elementwise_operation!(cos());
-
-    /// Elementwise tangent in radians
-    impl_elementwise_operation!(tan());
-
-    /// Elementwise arcsine in radians
-    impl_elementwise_operation!(asin());
-
-    /// Elementwise arccosine in radians
-    impl_elementwise_operation!(acos());
-
-    /// Elementwise arctangent in radians
-    impl_elementwise_operation!(atan());
-
-    /// Elementwise hyperbolic sine in radians
-    impl_elementwise_operation!(sinh());
-
-    /// Elementwise hyperbolic cosine in radians
-    impl_elementwise_operation!(cosh());
-
-    /// Elementwise hyperbolic tangent in radians
-    impl_elementwise_operation!(tanh());
-
-    /// Elementwise inverse hyperbolic sine in radians
-    impl_elementwise_operation!(asinh());
-
-    /// Elementwise inverse hyperbolic cosine in radians
-    impl_elementwise_operation!(acosh());
-
-    /// Elementwise inverse hyperbolic tangent in radians
-    impl_elementwise_operation!(atanh());
+    impl_elementwise_operation!(
+        /// Elementwise absolute value
+        abs()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise signum:
+        /// - `1.0` if the number is positive, `+0.0` or `F::infinity()`.
+        /// - `-1.0` if the number is negative, `-0.0` or `F::neg_infinity()`.
+        /// - `F::nan()` if the number is `F::nan()`.
+        signum()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise maximum of two values
+        max(other: F)
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise minimum of two values
+        min(other: F)
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise natural logarithm
+        ln()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise base of arbitrary base
+        log(base: F)
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise base 2 logarithm
+        log2()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise base 10 logarithm
+        log10()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise reciprocal: `1/(self)`
+        recip()
+    );
+
+    impl_elementwise_operation!(
+        /// Raise to the power of an integer power elementwise
+        powi(n: i32)
+    );
+
+    impl_elementwise_operation!(
+        /// Raise to the power of an integer power elementwise
+        powf(n: F)
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise square root
+        sqrt()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise exponential function, `e^(self)`.
+        exp()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise `2^(self)`.
+        exp2()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise sine in radians
+        sin()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise cosine in radians
+        cos()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise tangent in radians
+        tan()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise arcsine in radians
+        asin()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise arccosine in radians
+        acos()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise arctangent in radians
+        atan()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise hyperbolic sine in radians
+        sinh()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise hyperbolic cosine in radians
+        cosh()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise hyperbolic tangent in radians
+        tanh()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise inverse hyperbolic sine in radians
+        asinh()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise inverse hyperbolic cosine in radians
+        acosh()
+    );
+
+    impl_elementwise_operation!(
+        /// Elementwise inverse hyperbolic tangent in radians
+        atanh()
+    );
 }
 
 impl<S> PartialEq<Self> for Vector<S>
@@ -186,9 +242,18 @@ impl<S> From<Vec<S>> for Vector<S> {
     }
 }
 
-impl<S> Into<Vec<S>> for Vector<S> {
-    fn into(self) -> Vec<S> {
-        self.elements
+impl<S> From<Vector<S>> for Vec<S> {
+    fn from(vector: Vector<S>) -> Self {
+        vector.elements
+    }
+}
+
+impl<S> IntoIterator for Vector<S> {
+    type Item = S;
+    type IntoIter = std::vec::IntoIter<S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.into_iter()
     }
 }
 
@@ -224,12 +289,22 @@ impl<S> fmt::Display for Vector<S>
 where
     S: fmt::Display,
 {
+    /// Print the vector as a list. The formatting flags apply to every element.
+    ///
+    /// Vectors with more than 1000 elements only show their first and last three elements,
+    /// unless the alternate flag `{:#}` is used.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let truncate = !f.alternate() && self.len() > TRUNCATE_THRESHOLD;
+        let shown = shown_indices(self.len(), truncate);
+
         write!(f, "[")?;
-        for i in 0..self.len() {
-            self[i].fmt(f)?;
+        for (index, i) in shown.iter().enumerate() {
+            match i {
+                Some(i) => self[*i].fmt(f)?,
+                None => write!(f, "...")?,
+            }
 
-            if i != self.len() - 1 {
+            if index != shown.len() - 1 {
                 write!(f, ", ")?;
             }
         }
@@ -267,13 +342,18 @@ mod tests {
 
     #[test]
     fn display_vector() {
-        let mat = mat![1.234, 1.0/3.0, 7.0001];
+        let mat = mat![1.234, 1.0 / 3.0, 7.0001];
 
         let out = format!("{:.2}", mat);
 
-        assert_eq!(
-            out,
-            "[1.23, 0.33, 7.00]"
-        )
+        assert_eq!(out, "[1.23, 0.33, 7.00]")
+    }
+
+    #[test]
+    fn display_long_vector() {
+        let v = Vector::new((0..2000).collect::<Vec<i32>>());
+
+        assert_eq!(v.to_string(), "[0, 1, 2, ..., 1997, 1998, 1999]");
+        assert_eq!(format!("{:#}", v).matches(", ").count(), 1999);
     }
 }
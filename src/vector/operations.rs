@@ -1,5 +1,8 @@
 use super::*;
-use crate::matrix::Matrix;
+use crate::{
+    matrix::{broadcast_axis, Matrix},
+    traits::Semiring,
+};
 
 macro_rules! assert_equal_length {
     ($lhs:expr, $rhs:expr) => {
@@ -22,16 +25,49 @@ where
 
     lhs.iter()
         .zip(rhs)
-        .map(|(&a, &b)| a * b)
+        .map(|(a, b)| a.clone() * b.clone())
         .fold(S::zero(), |acc, t| acc + t)
 }
 
+/// Dot product where addition and multiplication are those of a semiring.
+pub fn semiring_dot<R>(lhs: &[R], rhs: &[R]) -> R
+where
+    R: Semiring,
+{
+    assert_equal_length!(lhs, rhs);
+
+    lhs.iter()
+        .zip(rhs)
+        .fold(R::additive_identity(), |acc, (a, b)| acc.plus(&a.times(b)))
+}
+
 impl<S> Vector<S>
 where
     S: Scalar,
 {
     pub fn dot(&self, other: &Self) -> S {
-        dot(&self, &other)
+        dot(self, other)
+    }
+
+    /// Multiply every element by a scalar.
+    pub fn scale(&self, factor: S) -> Vector<S> {
+        self * factor
+    }
+
+    /// Add a scalar to every element.
+    pub fn add_scalar(&self, value: S) -> Vector<S> {
+        self + value
+    }
+
+    /// The vector as a matrix with a single row, which broadcasts over the rows of a matrix.
+    pub fn as_row(&self) -> Matrix<S> {
+        Matrix::from_row_major((1, self.len()).into(), self.elements.clone())
+    }
+
+    /// The vector as a matrix with a single column, which broadcasts over the columns of a
+    /// matrix.
+    pub fn as_column(&self) -> Matrix<S> {
+        Matrix::from_row_major((self.len(), 1).into(), self.elements.clone())
     }
 
     /// Perform matrix multiplication between a column and row vector so that for
@@ -41,7 +77,7 @@ where
 
         for i in 0..self.len() {
             for j in 0..other.len() {
-                out[i][j] = self[i] * other[j];
+                out[i][j] = self[i].clone() * other[j].clone();
             }
         }
 
@@ -49,6 +85,33 @@ where
     }
 }
 
+/// The length of the result of an elementwise operation, where a vector of length 1
+/// stretches to match the other side.
+fn broadcast_len(lhs: usize, rhs: usize) -> usize {
+    match broadcast_axis(lhs, rhs) {
+        Some(len) => len,
+        None => panic!(
+            "Vector lengths must agree or be 1. Left hand side has length {} and right hand side has length {}",
+            lhs, rhs
+        ),
+    }
+}
+
+/// Combine the elements of two vectors, repeating a vector of length 1.
+fn zip_broadcast<S, F>(lhs: &[S], rhs: &[S], mut f: F) -> Vector<S>
+where
+    S: Clone,
+    F: FnMut(S, S) -> S,
+{
+    let index = |v: &[S], i: usize| if v.len() == 1 { 0 } else { i };
+    (0..broadcast_len(lhs.len(), rhs.len()))
+        .map(|i| f(lhs[index(lhs, i)].clone(), rhs[index(rhs, i)].clone()))
+        .collect::<Vec<S>>()
+        .into()
+}
+
+// Once the lengths are known to broadcast, cycling through the right hand side repeats it
+// if it has length 1 and visits it once otherwise.
 macro_rules! impl_elementwise {
     ($trait:ident, $fn:ident) => {
         impl<S> $trait<&Vector<S>> for Vector<S>
@@ -58,10 +121,12 @@ macro_rules! impl_elementwise {
             type Output = Vector<S>;
 
             fn $fn(mut self, rhs: &Vector<S>) -> Self::Output {
-                assert_equal_length!(self, rhs);
+                if broadcast_len(self.len(), rhs.len()) != self.len() {
+                    return zip_broadcast(&self, rhs, |a, b| a.$fn(b));
+                }
 
-                for i in 0..self.len() {
-                    self[i] = self[i].$fn(rhs[i]);
+                for (a, b) in self.elements.iter_mut().zip(rhs.elements.iter().cycle()) {
+                    *a = a.clone().$fn(b.clone());
                 }
 
                 self
@@ -75,10 +140,12 @@ macro_rules! impl_elementwise {
             type Output = Vector<S>;
 
             fn $fn(self, mut rhs: Vector<S>) -> Self::Output {
-                assert_equal_length!(self, rhs);
+                if broadcast_len(self.len(), rhs.len()) != rhs.len() {
+                    return zip_broadcast(self, &rhs, |a, b| a.$fn(b));
+                }
 
-                for i in 0..self.len() {
-                    rhs[i] = self[i].$fn(rhs[i]);
+                for (a, b) in self.elements.iter().cycle().zip(rhs.elements.iter_mut()) {
+                    *b = a.clone().$fn(b.clone());
                 }
 
                 rhs
@@ -114,6 +181,19 @@ impl_elementwise!(Sub, sub);
 impl_elementwise!(Mul, mul);
 impl_elementwise!(Div, div);
 
+macro_rules! assert_broadcasts_into {
+    ($lhs:expr, $rhs:expr) => {
+        let lhs = $lhs.len();
+        let rhs = $rhs.len();
+        assert!(
+            broadcast_axis(lhs, rhs) == Some(lhs),
+            "Vector lengths must agree or be 1 on the right hand side. Left hand side has length {} and right hand side has length {}",
+            lhs,
+            rhs
+        )
+    }
+}
+
 macro_rules! impl_elementwise_assign {
     ($trait:ident, $fn:ident) => {
         impl<S> $trait<&Vector<S>> for Vector<S>
@@ -121,10 +201,10 @@ macro_rules! impl_elementwise_assign {
             S: Scalar,
         {
             fn $fn(&mut self, rhs: &Vector<S>) {
-                assert_equal_length!(self, rhs);
+                assert_broadcasts_into!(self, rhs);
 
-                for (a, b) in self.elements.iter_mut().zip(rhs.elements.iter()) {
-                    a.$fn(*b)
+                for (a, b) in self.elements.iter_mut().zip(rhs.elements.iter().cycle()) {
+                    a.$fn(b.clone())
                 }
             }
         }
@@ -142,10 +222,10 @@ macro_rules! impl_elementwise_assign {
             S: Scalar,
         {
             fn $fn(&mut self, rhs: &Vector<S>) {
-                assert_equal_length!(self, rhs);
+                assert_broadcasts_into!(self, rhs);
 
-                for (a, b) in self.elements.iter_mut().zip(rhs.elements.iter()) {
-                    a.$fn(*b)
+                for (a, b) in self.elements.iter_mut().zip(rhs.elements.iter().cycle()) {
+                    a.$fn(b.clone())
                 }
             }
         }
@@ -167,39 +247,56 @@ impl_elementwise_assign!(MulAssign, mul_assign);
 impl_elementwise_assign!(DivAssign, div_assign);
 
 macro_rules! impl_scalar_ops {
-    ($trait:ident, $fn:ident, ($($scalar:ty),+)) => (
-        $(
-            impl_scalar_ops!($trait, $fn, $scalar);
-        )+
-    );
-
-    ($trait:ident, $fn:ident, $scalar:ty) => (
-        impl $trait<$scalar> for Vector<$scalar> {
-            type Output = Vector<$scalar>;
-
-            fn $fn(mut self, rhs: $scalar) -> Self::Output {
-                for i in 0..self.len() {
-                    self[i] = self[i].$fn(rhs);
-                }
+    ($trait:ident, $fn:ident, $assign_trait:ident, $fn_assign:ident) => {
+        impl<S> $trait<S> for Vector<S>
+        where
+            S: Scalar,
+        {
+            type Output = Vector<S>;
 
+            fn $fn(mut self, rhs: S) -> Self::Output {
+                self.$fn_assign(rhs);
                 self
             }
         }
 
-        impl $trait<$scalar> for &Vector<$scalar> {
-            type Output = Vector<$scalar>;
+        impl<S> $trait<S> for &Vector<S>
+        where
+            S: Scalar,
+        {
+            type Output = Vector<S>;
 
-            fn $fn(self, rhs: $scalar) -> Self::Output {
-                let mut tmp = self.clone();
+            fn $fn(self, rhs: S) -> Self::Output {
+                self.clone().$fn(rhs)
+            }
+        }
 
-                for i in 0..tmp.len() {
-                    tmp[i] = tmp[i].$fn(rhs);
+        impl<S> $assign_trait<S> for Vector<S>
+        where
+            S: Scalar,
+        {
+            fn $fn_assign(&mut self, rhs: S) {
+                for e in self.elements.iter_mut() {
+                    e.$fn_assign(rhs.clone())
                 }
-
-                tmp
             }
         }
+    };
+}
+
+impl_scalar_ops!(Add, add, AddAssign, add_assign);
+impl_scalar_ops!(Sub, sub, SubAssign, sub_assign);
+impl_scalar_ops!(Mul, mul, MulAssign, mul_assign);
+impl_scalar_ops!(Div, div, DivAssign, div_assign);
 
+macro_rules! impl_scalar_lhs_ops {
+    ($trait:ident, $fn:ident, ($($scalar:ty),+)) => (
+        $(
+            impl_scalar_lhs_ops!($trait, $fn, $scalar);
+        )+
+    );
+
+    ($trait:ident, $fn:ident, $scalar:ty) => (
         impl $trait<Vector<$scalar>> for $scalar {
             type Output = Vector<$scalar>;
 
@@ -216,85 +313,40 @@ macro_rules! impl_scalar_ops {
             type Output = Vector<$scalar>;
 
             fn $fn(self, rhs: &Vector<$scalar>) -> Self::Output {
-                let mut rhs = rhs.clone();
-
-                for i in 0..rhs.len() {
-                    rhs[i] = self.$fn(rhs[i]);
-                }
-
-                rhs
+                self.$fn(rhs.clone())
             }
         }
     );
 }
 
-impl_scalar_ops!(
+impl_scalar_lhs_ops!(
     Add,
     add,
     (i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
 );
-impl_scalar_ops!(
+impl_scalar_lhs_ops!(
     Sub,
     sub,
     (i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
 );
-impl_scalar_ops!(
+impl_scalar_lhs_ops!(
     Mul,
     mul,
     (i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
 );
-impl_scalar_ops!(
+impl_scalar_lhs_ops!(
     Div,
     div,
     (i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
 );
 
-macro_rules! impl_scalar_assign_ops {
-    ($trait:ident, $fn:ident, ($($scalar:ty),+)) => (
-        $(
-            impl_scalar_assign_ops!($trait, $fn, $scalar);
-        )+
-    );
-
-    ($trait:ident, $fn:ident, $scalar:ty) => (
-        impl $trait<$scalar> for Vector<$scalar> {
-            fn $fn(&mut self, rhs: $scalar) {
-                for e in self.elements.iter_mut() {
-                    e.$fn(rhs)
-                }
-            }
-        }
-    );
-}
-
-impl_scalar_assign_ops!(
-    AddAssign,
-    add_assign,
-    (i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
-);
-impl_scalar_assign_ops!(
-    SubAssign,
-    sub_assign,
-    (i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
-);
-impl_scalar_assign_ops!(
-    MulAssign,
-    mul_assign,
-    (i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
-);
-impl_scalar_assign_ops!(
-    DivAssign,
-    div_assign,
-    (i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64)
-);
-
 impl<S> Neg for Vector<S>
 where
     S: Scalar,
 {
     type Output = Vector<S>;
     fn neg(mut self) -> Vector<S> {
-        self.iter_mut().for_each(|s| *s = -*s);
+        self.iter_mut().for_each(|s| *s = -s.clone());
         self
     }
 }
@@ -306,12 +358,13 @@ where
     type Output = Vector<S>;
     fn neg(self) -> Vector<S> {
         let mut tmp = self.clone();
-        tmp.iter_mut().for_each(|s| *s = -*s);
+        tmp.iter_mut().for_each(|s| *s = -s.clone());
         tmp
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::identity_op)]
 mod tests {
     #[test]
     fn dot_product_small() {
@@ -333,6 +386,19 @@ mod tests {
         assert_eq!(result, mat![[1 * 4, 1 * 5], [2 * 4, 2 * 5], [3 * 4, 3 * 5]]);
     }
 
+    #[test]
+    fn heap_allocated_elements() {
+        use num::BigInt;
+
+        let big = |n: i64| BigInt::from(n) * BigInt::from(u64::MAX);
+        let a = mat![big(1), big(-2), big(3)];
+        let b = mat![big(4), big(5), big(6)];
+
+        assert_eq!(a.dot(&b), big(12) * BigInt::from(u64::MAX));
+        assert_eq!(&a + &b, mat![big(5), big(3), big(9)]);
+        assert_eq!(-a, mat![big(-1), big(2), big(-3)]);
+    }
+
     #[test]
     fn add_small() {
         let a = mat![1, 2, 3];
@@ -365,6 +431,27 @@ mod tests {
         assert_eq!(move_ref, mat![1 - 1, 2 - 2, 3 - 2]);
     }
 
+    #[test]
+    fn broadcast_length_one() {
+        let a = mat![1, 2, 3];
+
+        assert_eq!(&a * &mat![2], mat![2, 4, 6]);
+        assert_eq!(mat![10] - a.clone(), mat![9, 8, 7]);
+        assert_eq!(&mat![10] - &a, mat![9, 8, 7]);
+
+        let mut b = a.clone();
+        b *= mat![-1];
+        assert_eq!(b, -a);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Vector lengths must agree or be 1. Left hand side has length 3 and right hand side has length 2"
+    )]
+    fn broadcast_incompatible() {
+        let _ = mat![1, 2, 3] + mat![1, 2];
+    }
+
     #[test]
     fn vec_add_scalar() {
         let a = mat![1, 2, 3];
@@ -393,6 +480,30 @@ mod tests {
         assert_eq!(result, mat![2, 4, 6]);
     }
 
+    #[test]
+    fn scalar_sub_vec() {
+        let a = mat![1, 2, 3];
+        let result = 10 - &a;
+        assert_eq!(result, mat![9, 8, 7]);
+    }
+
+    #[test]
+    fn vec_mul_generic_scalar() {
+        use num::rational::Ratio;
+
+        let a = mat![Ratio::new(1, 2), Ratio::new(2, 3)];
+
+        assert_eq!(
+            &a * Ratio::new(3, 1),
+            mat![Ratio::new(3, 2), Ratio::new(2, 1)]
+        );
+        assert_eq!(a.scale(Ratio::new(3, 1)), &a * Ratio::new(3, 1));
+        assert_eq!(
+            a.add_scalar(Ratio::new(1, 2)),
+            mat![Ratio::new(1, 1), Ratio::new(7, 6)]
+        );
+    }
+
     #[test]
     fn vec_add_assign_scalar() {
         let mut a = mat![1, 2, 3];